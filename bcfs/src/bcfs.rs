@@ -14,6 +14,7 @@ use wasi_types::{
 
 use crate::{
     file::{File, FileCache, FileKind, CHAIN_DIR_FILENO, HOME_DIR_FILENO},
+    transaction::StagedTransaction,
     Result,
 };
 
@@ -257,7 +258,12 @@ impl BCFS {
                 Ok(cursor.seek(seekfrom_from_offset_whence(offset, whence)?)?)
             }
             FileCache::Absent(ref mut seek) => match whence {
-                Whence::End => unreachable!("file was just populated"),
+                Whence::End => {
+                    // Only stdin remains absent once populated.
+                    let new_offset = Self::checked_offset(ptx.input().len() as u64, offset)?;
+                    *seek = SeekFrom::Start(new_offset);
+                    Ok(new_offset)
+                }
                 Whence::Start => {
                     *seek = seekfrom_from_offset_whence(offset, whence)?;
                     Ok(offset as u64)
@@ -346,6 +352,31 @@ impl BCFS {
         self.do_pwrite_vectored(ptx, fd, bufs, Some(SeekFrom::Start(offset)))
     }
 
+    /// Runs `f` with a `PendingTransaction` that buffers writes to the account's storage.
+    /// The writes are committed to `ptx` only if `f` returns `Ok` and are discarded otherwise.
+    /// Files that are still dirty when `f` returns are flushed as part of the transaction.
+    ///
+    /// Only storage is transactional: output, events, and nested transactions made by `f`
+    /// go directly to `ptx`.
+    pub fn with_transaction<T, E>(
+        &mut self,
+        ptx: &mut dyn PendingTransaction,
+        f: impl FnOnce(&mut Self, &mut dyn PendingTransaction) -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E> {
+        // Writes made before the transaction are not part of it.
+        self.flush_regular_files(ptx);
+
+        let mut staged_ptx = StagedTransaction::new(ptx);
+        let result = f(self, &mut staged_ptx);
+        if result.is_ok() {
+            self.flush_regular_files(&mut staged_ptx);
+            staged_ptx.commit();
+        } else {
+            self.invalidate_regular_files();
+        }
+        result
+    }
+
     pub fn renumber(
         &mut self,
         _ptx: &mut dyn PendingTransaction,
@@ -437,6 +468,50 @@ impl BCFS {
         }
     }
 
+    fn regular_fds(&self) -> Vec<Fd> {
+        self.files
+            .iter()
+            .enumerate()
+            .filter_map(|(fileno, f)| match f {
+                Some(File {
+                    kind: FileKind::Regular { .. },
+                    ..
+                }) => Some(Fd::from(fileno as u32)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn flush_regular_files(&mut self, ptx: &mut dyn PendingTransaction) {
+        for fd in self.regular_fds() {
+            self.flush(ptx, fd).ok(); // flushing an open regular file can't fail
+        }
+    }
+
+    /// Drops the cached contents of regular files so that they are re-read from storage.
+    fn invalidate_regular_files(&mut self) {
+        for fd in self.regular_fds() {
+            let file = self.file(fd).unwrap();
+            let mut buf = file.buf.borrow_mut();
+            let pos = match &*buf {
+                FileCache::Present(cursor) => SeekFrom::Start(cursor.position()),
+                FileCache::Absent(seek_from) => *seek_from,
+            };
+            *buf = FileCache::Absent(pos);
+            file.metadata.set(None);
+            file.dirty.set(false);
+        }
+    }
+
+    /// Returns whether the contents of the file at `fd` have been copied into its cache.
+    #[cfg(test)]
+    pub(crate) fn is_cached(&self, fd: Fd) -> bool {
+        match &*self.file(fd).unwrap().buf.borrow() {
+            FileCache::Present(_) => true,
+            FileCache::Absent(_) => false,
+        }
+    }
+
     fn has_fd(&self, fd: Fd) -> bool {
         match self.files.get(fd_usize(fd)) {
             Some(Some(_)) => true,
@@ -490,9 +565,18 @@ impl BCFS {
     ) -> Result<FileStat> {
         let file_size = match cache {
             FileCache::Present(cursor) => cursor.get_ref().len(),
+            FileCache::Absent(offset) if file.kind.is_stdin() => {
+                // The input is read directly from the transaction rather than being
+                // copied into the cache, so only the position is resolved.
+                let input_len = ptx.input().len();
+                if let SeekFrom::End(end_offset) = offset {
+                    *offset = SeekFrom::Start(Self::checked_offset(input_len as u64, *end_offset)?);
+                }
+                input_len
+            }
             FileCache::Absent(offset) => {
                 let bytes = match &file.kind {
-                    FileKind::Stdin => ptx.input().to_vec(),
+                    FileKind::Stdin => unreachable!("handled above"),
                     FileKind::Bytecode { addr } => match ptx.code_at(addr) {
                         Some(code) => code.to_vec(),
                         None => return Err(ErrNo::NoEnt),
@@ -554,6 +638,9 @@ impl BCFS {
 
         let cursor = match &mut *buf {
             FileCache::Present(ref mut cursor) => cursor,
+            FileCache::Absent(SeekFrom::Start(ref mut pos)) if file.kind.is_stdin() => {
+                return Self::read_input(ptx.input(), pos, bufs, offset);
+            }
             FileCache::Absent(_) => unreachable!("file was just populated"),
         };
 
@@ -569,6 +656,28 @@ impl BCFS {
         }
     }
 
+    /// Reads the transaction input, which is not buffered, starting at `pos` or `offset`.
+    /// Only `pos` is advanced.
+    fn read_input(
+        input: &[u8],
+        pos: &mut u64,
+        bufs: &mut [IoSliceMut],
+        offset: Option<SeekFrom>,
+    ) -> Result<usize> {
+        let start = match offset {
+            Some(SeekFrom::Start(offset)) => offset,
+            _ => *pos,
+        };
+        let mut remaining = input
+            .get(usize::try_from(start).map_err(|_| ErrNo::Inval)?..)
+            .unwrap_or_default();
+        let nbytes = remaining.read_vectored(bufs)?;
+        if offset.is_none() {
+            *pos += nbytes as u64;
+        }
+        Ok(nbytes)
+    }
+
     fn do_pwrite_vectored(
         &mut self,
         ptx: &mut dyn PendingTransaction,
@@ -47,6 +47,13 @@ impl FileKind {
         }
     }
 
+    pub fn is_stdin(&self) -> bool {
+        match self {
+            FileKind::Stdin => true,
+            _ => false,
+        }
+    }
+
     pub fn is_blockchain_intrinsic(&self) -> bool {
         match self {
             FileKind::Log | FileKind::Balance { .. } | FileKind::Bytecode { .. } => true,
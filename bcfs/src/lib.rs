@@ -2,6 +2,7 @@ type Result<T> = std::result::Result<T, wasi_types::ErrNo>;
 
 mod bcfs;
 mod file;
+mod transaction;
 
 pub use crate::bcfs::BCFS;
 
@@ -879,6 +879,75 @@ fn regular_file() {
     );
 }
 
+testcase!(
+    fn transaction_commit(ptx: &mut dyn PendingTransaction) {
+        let mut bcfs = BCFS::new(*ptx.address(), CHAIN_NAME);
+        let res: Result<(), ErrNo> = bcfs.with_transaction(ptx, |bcfs, ptx| {
+            for (path, contents) in [("a", b"first"), ("b", b"again")].iter() {
+                let fd = bcfs.open(
+                    ptx,
+                    HOME_DIR_FILENO.into(),
+                    &Path::new(path),
+                    OpenFlags::CREATE,
+                    FdFlags::empty(),
+                )?;
+                bcfs.write_vectored(ptx, fd, &[IoSlice::new(*contents)])?;
+                // `b` is left dirty and is flushed when the transaction commits.
+                if *path == "a" {
+                    bcfs.close(ptx, fd)?;
+                }
+            }
+            assert!(ptx.state().contains(b"a"));
+            Ok(())
+        });
+        assert_eq!(res, Ok(()));
+        assert_eq!(ptx.state().get(b"a").unwrap(), b"first");
+        assert_eq!(ptx.state().get(b"b").unwrap(), b"again");
+    }
+);
+
+testcase!(
+    fn transaction_abort(ptx: &mut dyn PendingTransaction) {
+        let mut bcfs = BCFS::new(*ptx.address(), CHAIN_NAME);
+        let common_fd = bcfs
+            .open(
+                ptx,
+                HOME_DIR_FILENO.into(),
+                &Path::new("common_key"),
+                OpenFlags::empty(),
+                FdFlags::empty(),
+            )
+            .unwrap();
+
+        let res: Result<(), ErrNo> = bcfs.with_transaction(ptx, |bcfs, ptx| {
+            bcfs.write_vectored(ptx, common_fd, &[IoSlice::new(b"overwritten")])?;
+            bcfs.flush(ptx, common_fd)?;
+
+            let fd = bcfs.open(
+                ptx,
+                HOME_DIR_FILENO.into(),
+                &Path::new("new_file"),
+                OpenFlags::CREATE,
+                FdFlags::empty(),
+            )?;
+            bcfs.write_vectored(ptx, fd, &[IoSlice::new(b"new contents")])?;
+            bcfs.close(ptx, fd)?;
+            Err(ErrNo::Inval)
+        });
+        assert_eq!(res, Err(ErrNo::Inval));
+
+        assert!(!ptx.state().contains(b"new_file"));
+        assert_eq!(ptx.state().get(b"common_key").unwrap(), b"common_value");
+
+        // The cached contents of open files are discarded along with the writes.
+        let mut buf = vec![0u8; 32];
+        let nbytes = bcfs
+            .pread_vectored(ptx, common_fd, &mut [IoSliceMut::new(&mut buf)], 0)
+            .unwrap();
+        assert_eq!(&buf[..nbytes], b"common_value");
+    }
+);
+
 testcase!(
     fn flush_preopens(ptx: &mut dyn PendingTransaction) {
         let mut bcfs = BCFS::new(*ptx.address(), CHAIN_NAME);
@@ -970,6 +1039,70 @@ fn flush_log_to_ptx() {
     )
 }
 
+fn large_input() -> Vec<u8> {
+    (0..(4 << 20)).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn stream_input() {
+    extern "C" fn test_main(ptxp: memchain::PtxPtr) -> u16 {
+        let ptx = unsafe { &mut **ptxp };
+        let mut bcfs = BCFS::new(*ptx.address(), CHAIN_NAME);
+
+        let stdin_fd = Fd::from(0u32);
+        let expected = large_input();
+        assert_eq!(
+            bcfs.filestat(ptx, stdin_fd).unwrap().file_size,
+            expected.len() as u64
+        );
+
+        let mut chunk = [0u8; 64 * 1024];
+        let mut nread = 0;
+        loop {
+            let nbytes = bcfs
+                .read_vectored(ptx, stdin_fd, &mut [IoSliceMut::new(&mut chunk)])
+                .unwrap();
+            if nbytes == 0 {
+                break;
+            }
+            assert_eq!(&chunk[..nbytes], &expected[nread..(nread + nbytes)]);
+            nread += nbytes;
+        }
+        assert_eq!(nread, expected.len());
+
+        let nbytes = bcfs
+            .pread_vectored(ptx, stdin_fd, &mut [IoSliceMut::new(&mut chunk[..4])], 1000)
+            .unwrap();
+        assert_eq!(&chunk[..nbytes], &expected[1000..1004]);
+
+        bcfs.seek(ptx, stdin_fd, -4, Whence::End).unwrap();
+        let nbytes = bcfs
+            .read_vectored(ptx, stdin_fd, &mut [IoSliceMut::new(&mut chunk)])
+            .unwrap();
+        assert_eq!(&chunk[..nbytes], &expected[(expected.len() - 4)..]);
+        assert_eq!(bcfs.tell(ptx, stdin_fd).unwrap(), expected.len() as u64);
+
+        // The input was never copied into the file cache.
+        assert!(!bcfs.is_cached(stdin_fd));
+
+        0
+    }
+
+    let mut bc = create_memchain(vec![None, Some(test_main)]);
+
+    let receipt = bc.last_block_mut().transact(
+        ADDR_1,
+        ADDR_2,
+        ADDR_1, /* payer */
+        0,      /* value */
+        &large_input(),
+        BASE_GAS,
+        GAS_PRICE,
+    );
+
+    assert_eq!(receipt.outcome(), TransactionOutcome::Success);
+}
+
 #[test]
 fn flush_output_to_ptx() {
     const OUTPUT: &[u8] = b"output";
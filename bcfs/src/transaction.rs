@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use blockchain_traits::{KVStore, KVStoreMut, PendingTransaction, Receipt};
+use oasis_types::{AccountMeta, Address};
+
+/// A `PendingTransaction` that buffers writes to the state of the current account
+/// until they are committed to the wrapped `PendingTransaction`.
+/// All other operations are forwarded directly.
+pub struct StagedTransaction<'a> {
+    ptx: &'a mut dyn PendingTransaction,
+
+    /// The buffered writes. A value of `None` denotes a removed key.
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> StagedTransaction<'a> {
+    pub fn new(ptx: &'a mut dyn PendingTransaction) -> Self {
+        Self {
+            ptx,
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// Writes the buffered changes to the state of the wrapped `PendingTransaction`.
+    pub fn commit(self) {
+        let state = self.ptx.state_mut();
+        for (key, value) in self.writes.iter() {
+            match value {
+                Some(value) => state.set(key, value),
+                None => state.remove(key),
+            }
+        }
+    }
+}
+
+impl<'a> KVStore for StagedTransaction<'a> {
+    fn contains(&self, key: &[u8]) -> bool {
+        match self.writes.get(key) {
+            Some(value) => value.is_some(),
+            None => self.ptx.state().contains(key),
+        }
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.ptx.state().get(key),
+        }
+    }
+}
+
+impl<'a> KVStoreMut for StagedTransaction<'a> {
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
+
+impl<'a> PendingTransaction for StagedTransaction<'a> {
+    fn address(&self) -> &Address {
+        self.ptx.address()
+    }
+
+    fn sender(&self) -> &Address {
+        self.ptx.sender()
+    }
+
+    fn origin(&self) -> &Address {
+        self.ptx.origin()
+    }
+
+    fn value(&self) -> u128 {
+        self.ptx.value()
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.ptx.timestamp()
+    }
+
+    fn input(&self) -> &[u8] {
+        self.ptx.input()
+    }
+
+    fn create(&mut self, value: u128, code: &[u8]) -> Box<dyn Receipt> {
+        self.ptx.create(value, code)
+    }
+
+    fn transact(&mut self, callee: Address, value: u128, input: &[u8]) -> Box<dyn Receipt> {
+        self.ptx.transact(callee, value, input)
+    }
+
+    fn ret(&mut self, data: &[u8]) {
+        self.ptx.ret(data)
+    }
+
+    fn err(&mut self, data: &[u8]) {
+        self.ptx.err(data)
+    }
+
+    fn emit(&mut self, topics: &[&[u8]], data: &[u8]) {
+        self.ptx.emit(topics, data)
+    }
+
+    fn state(&self) -> &dyn KVStore {
+        self
+    }
+
+    fn state_mut(&mut self) -> &mut dyn KVStoreMut {
+        self
+    }
+
+    fn code_at(&self, addr: &Address) -> Option<&[u8]> {
+        self.ptx.code_at(addr)
+    }
+
+    fn account_meta_at(&self, addr: &Address) -> Option<AccountMeta> {
+        self.ptx.account_meta_at(addr)
+    }
+}
@@ -18,9 +18,13 @@ pub trait Block {
     /// Returns the height of this block.
     fn height(&self) -> u64;
 
+    /// Returns the time at which this block was created, in seconds since the Unix epoch.
+    fn timestamp(&self) -> u64;
+
     /// Executes a RPC to `callee` with provided `input` and `gas` computational resources.
     /// `value` tokens will be transferred from the `caller` to the `callee`.
-    /// The `caller` is charged `gas * gas_price` for the computation.
+    /// The `payer` is charged `gas_used * gas_price` for the computation, but must be
+    /// able to afford `gas * gas_price` before the transaction is executed.
     /// A transaction that aborts (panics) will have its changes rolled back.
     /// This `transact` should be called by an Externally Owned Account (EOA).
     #[allow(clippy::too_many_arguments)]
@@ -45,7 +49,8 @@ pub trait Block {
     /// Returns the state of the account at `addr`, if it exists.
     fn state_at(&self, addr: &Address) -> Option<&dyn KVStore>;
 
-    /// Returns the events emitted during the course of this block.
+    /// Returns the events emitted during the course of this block, in the order
+    /// in which they were emitted.
     fn events(&self) -> Vec<&Event>;
 
     /// Returns the receipts of transactions executed in this block.
@@ -60,9 +65,16 @@ pub trait PendingTransaction {
     /// Returns the address of the sender of the transaction.
     fn sender(&self) -> &Address;
 
+    /// Returns the address of the account that initiated the top-level transaction.
+    /// Unlike `sender`, this does not change across nested transactions.
+    fn origin(&self) -> &Address;
+
     /// Returns the value sent to the current transaction.
     fn value(&self) -> u128;
 
+    /// Returns the timestamp of the block in which this transaction is executed.
+    fn timestamp(&self) -> u64;
+
     /// Returns the input provided by the calling context.
     fn input(&self) -> &[u8];
 
@@ -82,7 +94,9 @@ pub trait PendingTransaction {
     /// Returns error data to the calling context.
     fn err(&mut self, data: &[u8]);
 
-    /// Publishes a broadcast message in this block.
+    /// Publishes a broadcast message in this block. Events are recorded in emission
+    /// order, including those emitted by nested transactions, unless the emitting
+    /// transaction is reverted.
     fn emit(&mut self, topics: &[&[u8]], data: &[u8]);
 
     /// Returns the state of the current account.
@@ -125,7 +139,7 @@ pub trait Receipt {
     /// Returns the total gas used during the execution of the transaction.
     fn gas_used(&self) -> u64;
 
-    /// Returns the events emitted during the transaction.
+    /// Returns the events emitted during the transaction, in emission order.
     fn events(&self) -> Vec<&Event>;
 
     /// Returns the outcome of this transaction.
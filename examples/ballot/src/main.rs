@@ -128,3 +128,166 @@ mod tests {
         assert_eq!(ballot.winner(&voter_ctx).unwrap(), 1);
     }
 }
+
+/// Tests the deployed service through its generated client.
+/// These are built by `oasis-build` and require `ballot.wasm` to have been built.
+#[cfg(all(test, feature = "memchain-tests"))]
+mod memchain_tests {
+    use super::*;
+
+    use std::borrow::Cow;
+
+    use blockchain_traits::{Block as _, Blockchain as _};
+    use memchain::{Account, Memchain, PtxPtr};
+    use oasis_client::{Gateway as _, MemchainGateway};
+    use oasis_std::RpcError;
+
+    const SENDER: Address = Address([1u8; 20]);
+
+    extern "C" fn ballot_deploy(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, || Ballot::__deploy_raw().map(|()| Vec::new()))
+    }
+
+    extern "C" fn ballot_main(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, Ballot::__call_raw)
+    }
+
+    fn ballot_bytecode() -> Vec<u8> {
+        let wasm_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../target/wasm32-wasi/release/ballot.wasm"
+        );
+        std::fs::read(wasm_path).unwrap()
+    }
+
+    fn ballot_gateway(sender_balance: u128) -> MemchainGateway<'static> {
+        let mut genesis_state = std::collections::HashMap::new();
+        let sender_account = Account {
+            balance: sender_balance,
+            ..Default::default()
+        };
+        genesis_state.insert(SENDER, Cow::Owned(sender_account));
+        let memchain = Memchain::new("testnet", genesis_state, 0u64 /* base gas */);
+        let mut gateway = MemchainGateway::new(memchain, SENDER);
+        gateway.register(&ballot_bytecode(), ballot_deploy, ballot_main);
+        gateway
+    }
+
+    fn deploy<'a>(gateway: &'a MemchainGateway, ctx: &Context) -> ballot::BallotClient<'a> {
+        let candidates = vec!["beef".to_string(), "yogurt".to_string()];
+        ballot::BallotClient::deploy(gateway, ctx, "What's for dinner?", &candidates)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deploy_and_vote() {
+        let gateway = ballot_gateway(0);
+        let ctx = Context::default();
+        let mut ballot = deploy(&gateway, &ctx);
+        assert_eq!(gateway.code(ballot.address()).unwrap(), ballot_bytecode());
+
+        assert_eq!(
+            ballot.description(&ctx).unwrap().unwrap(),
+            "What's for dinner?"
+        );
+        ballot.vote(&ctx, 0).unwrap().unwrap();
+        ballot.vote(&ctx, 1).unwrap().unwrap(); // changes the sender's vote
+        assert_eq!(
+            ballot.vote(&ctx, 2).unwrap(),
+            Err("Invalid candidate `2`.".to_string())
+        );
+        assert_eq!(
+            ballot.winner(&ctx).unwrap(),
+            Err("Voting is not closed.".to_string())
+        );
+
+        ballot.close(&ctx).unwrap().unwrap();
+        assert_eq!(
+            ballot.vote(&ctx, 0).unwrap(),
+            Err("Voting is closed.".to_string())
+        );
+        assert_eq!(ballot.winner(&ctx).unwrap(), Ok(1));
+
+        let other_ballot = deploy(&gateway, &ctx);
+        assert_ne!(other_ballot.address(), ballot.address());
+        assert_eq!(
+            other_ballot.winner(&ctx).unwrap(),
+            Err("Voting is not closed.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_query() {
+        let gateway = ballot_gateway(0);
+        let ctx = Context::default();
+        let ballot = deploy(&gateway, &ctx);
+        let num_receipts = gateway.memchain().last_block().receipts().len();
+
+        assert_eq!(
+            ballot.candidates(&ctx).unwrap().unwrap(),
+            vec!["beef".to_string(), "yogurt".to_string()]
+        );
+        assert_eq!(
+            gateway.memchain().last_block().receipts().len(),
+            num_receipts
+        );
+    }
+
+    #[test]
+    fn test_transaction_log() {
+        let gateway = ballot_gateway(0);
+        let ctx = Context::default();
+        let mut ballot = deploy(&gateway, &ctx);
+        ballot.vote(&ctx, 1).unwrap().unwrap();
+        ballot.candidates(&ctx).unwrap().unwrap();
+        ballot.close(&ctx).unwrap().unwrap();
+
+        let memchain = gateway.memchain();
+        let log = memchain.transaction_log();
+        assert_eq!(log.len(), 3);
+        assert!(log[0].deployment.is_some());
+
+        let replayed = memchain.replay(&log);
+        assert_eq!(replayed.blocks, memchain.blocks);
+        let replayed = MemchainGateway::new(replayed, SENDER);
+        let replayed_ballot = ballot::BallotClient::new(&replayed, ballot.address());
+        assert_eq!(replayed_ballot.winner(&ctx).unwrap(), Ok(1));
+    }
+
+    #[test]
+    fn test_failed_deploy() {
+        let gateway = ballot_gateway(0);
+        match gateway.deploy(b"unknown bytecode", Default::default()) {
+            Err(RpcError::InvalidInput(None)) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        // The constructor's arguments are missing.
+        match gateway.deploy(&ballot_bytecode(), Default::default()) {
+            Err(RpcError::InvalidInput(None)) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match ballot::BallotClient::new(&gateway, Address([9u8; 20])).winner(&Context::default()) {
+            Err(RpcError::InvalidCallee) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_deploy_with_value() {
+        let gateway = ballot_gateway(10);
+        let ballot = deploy(&gateway, &Context::default().with_value(4));
+        let ballot_meta = gateway
+            .memchain()
+            .last_block()
+            .account_meta_at(&ballot.address());
+        assert_eq!(ballot_meta.unwrap().balance, 4);
+
+        let candidates = vec!["beef".to_string()];
+        let ctx = Context::default().with_value(7);
+        match ballot::BallotClient::deploy(&gateway, &ctx, "Lunch?", &candidates) {
+            Err(RpcError::InsufficientFunds) => (),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use oasis_std::{
     abi::*,
     collections::{map::Entry, Map, Set},
-    Address, Context, Event,
+    view, Address, Context, Event,
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -65,7 +65,10 @@ impl ERC20Token {
     }
 
     /// Get balance
-    pub fn balance_of(&mut self, ctx: &Context) -> Result<u64> {
+    // *Note*: RPCs are sent as read-only queries only when they take `&self`.
+    // `#[view]` checks that a getter does, so that clients do not pay to call it.
+    #[view]
+    pub fn balance_of(&self, ctx: &Context) -> Result<u64> {
         Ok(self
             .accounts
             .get(&ctx.sender())
@@ -74,7 +77,8 @@ impl ERC20Token {
     }
 
     /// Get total supply
-    pub fn total_supply(&mut self, _ctx: &Context) -> Result<u64> {
+    #[view]
+    pub fn total_supply(&self, _ctx: &Context) -> Result<u64> {
         Ok(self.total_supply)
     }
 
@@ -141,7 +145,8 @@ impl ERC20Token {
     }
 
     /// read allowance
-    pub fn allowance(&mut self, ctx: &Context, spender: Address) -> Result<u64> {
+    #[view]
+    pub fn allowance(&self, ctx: &Context, spender: Address) -> Result<u64> {
         if !self.allowed.contains_key(&ctx.sender()) {
             return Ok(0u64);
         }
@@ -213,7 +218,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use oasis_std::{Address, Context};
+    use oasis_std::{Address, Context, RpcError};
 
     /// Creates a new account and a `Context` with the new account as the sender.
     fn create_account() -> (Address, Context) {
@@ -250,4 +255,182 @@ mod tests {
         balance = erc20.balance_of(&bctx).unwrap();
         assert_eq!(balance, 400u64);
     }
+
+    #[test]
+    fn event_topics() {
+        use oasis_std::exe::encode_event_topic;
+
+        assert_eq!(Transfer::topic_hash(), encode_event_topic(&"Transfer"));
+        assert_ne!(Transfer::topic_hash(), Approval::topic_hash());
+
+        let transfer = Transfer {
+            from: Address([1u8; 20]),
+            to: Address([2u8; 20]),
+            amount: 3,
+        };
+        let topics = transfer.indexed_topics();
+        assert_eq!(topics.len(), 3);
+        assert_eq!(topics[0], encode_event_topic(&transfer.from));
+        assert_eq!(topics[2], encode_event_topic(&transfer.amount));
+    }
+
+    #[test]
+    fn typed_errors() {
+        let (_getafix, gctx) = create_account();
+        let (caesar, cctx) = create_account();
+        let (brutus, _bctx) = create_account();
+
+        let mut erc20 = ERC20Token::new(&gctx, 1000).unwrap();
+
+        // Caesar has no tokens to give to Brutus.
+        let err = erc20.transfer(&cctx, brutus, 100).unwrap_err();
+
+        // A client receives the error encoded by the service's dispatcher.
+        let rpc_err = RpcError::Execution(err.try_to_vec().unwrap());
+        assert_eq!(
+            rpc_err.into_typed::<Error>().unwrap(),
+            Error::InsufficientFunds { address: caesar }
+        );
+    }
+}
+
+/// Tests the deployed service through its generated client.
+/// These are built by `oasis-build` and require `erc20.wasm` to have been built.
+#[cfg(all(test, feature = "memchain-tests"))]
+mod memchain_tests {
+    use super::*;
+
+    use std::borrow::Cow;
+
+    use blockchain_traits::{Block as _, Blockchain as _};
+    use memchain::{Account, Memchain, PtxPtr};
+    use oasis_client::{CallOptions, Estimate, Gateway as _, MemchainGateway};
+    use oasis_std::RpcError;
+
+    const SENDER: Address = Address([1u8; 20]);
+    const RECIPIENT: Address = Address([2u8; 20]);
+    const CAESAR: Address = Address([3u8; 20]);
+    const BRUTUS: Address = Address([4u8; 20]);
+    const BASE_GAS: u64 = 2100;
+
+    extern "C" fn erc20_deploy(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, || ERC20Token::__deploy_raw().map(|()| Vec::new()))
+    }
+
+    extern "C" fn erc20_main(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, ERC20Token::__call_raw)
+    }
+
+    fn erc20_gateway() -> MemchainGateway<'static> {
+        let mut genesis_state = std::collections::HashMap::new();
+        for holder in &[SENDER, CAESAR, BRUTUS] {
+            genesis_state.insert(*holder, Cow::Owned(Account::default()));
+        }
+        let memchain = Memchain::new("testnet", genesis_state, BASE_GAS);
+        let mut gateway = MemchainGateway::new(memchain, SENDER);
+        let wasm_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../target/wasm32-wasi/release/erc20.wasm"
+        );
+        gateway.register(&std::fs::read(wasm_path).unwrap(), erc20_deploy, erc20_main);
+        gateway
+    }
+
+    /// Returns the payload of the `transfer` RPC, which clients do not expose for estimation.
+    fn transfer_payload(to: Address, amount: u64) -> Vec<u8> {
+        let (_, transfer_selector) = ERC20Token::METHODS
+            .iter()
+            .find(|(name, _)| *name == "transfer")
+            .unwrap();
+        oasis_std::abi_encode!(*transfer_selector as u8, to, amount).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_failed_transfer() {
+        let gateway = erc20_gateway();
+        let ctx = Context::default();
+        let token = erc20::Erc20TokenClient::deploy(&gateway, &ctx, 10 /* total supply */)
+            .unwrap()
+            .unwrap();
+        let num_receipts = gateway.memchain().last_block().receipts().len();
+
+        let estimate = gateway
+            .estimate(
+                token.address(),
+                &transfer_payload(RECIPIENT, 11),
+                CallOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            estimate,
+            Estimate {
+                output: Error::InsufficientFunds { address: SENDER }
+                    .try_to_vec()
+                    .unwrap(),
+                gas_used: BASE_GAS,
+                reverted: true,
+            }
+        );
+
+        let estimate = gateway
+            .estimate(
+                token.address(),
+                &transfer_payload(RECIPIENT, 4),
+                CallOptions::default(),
+            )
+            .unwrap();
+        assert!(!estimate.reverted);
+        let transfer = Transfer {
+            from: SENDER,
+            to: RECIPIENT,
+            amount: 4,
+        };
+        assert_eq!(estimate.output, transfer.try_to_vec().unwrap());
+
+        // Estimates are not persisted.
+        assert_eq!(
+            gateway.memchain().last_block().receipts().len(),
+            num_receipts
+        );
+        assert_eq!(token.balance_of(&ctx).unwrap(), Ok(10));
+
+        match gateway.estimate(
+            Address([9u8; 20]),
+            &transfer_payload(RECIPIENT, 4),
+            CallOptions::default(),
+        ) {
+            Err(RpcError::InvalidCallee) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_replay_happy_path() {
+        let gateway = erc20_gateway();
+        let ctx = Context::default();
+        let mut token =
+            erc20::Erc20TokenClient::deploy(&gateway, &ctx, 1000 /* total supply */)
+                .unwrap()
+                .unwrap();
+        token.transfer(&ctx, CAESAR, 500).unwrap().unwrap();
+        token.approve(&ctx, BRUTUS, 400).unwrap().unwrap();
+        token
+            .transfer_from(&ctx, SENDER, BRUTUS, 400)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.balance_of(&ctx).unwrap(), Ok(100));
+
+        let memchain = gateway.memchain();
+        let log = memchain.transaction_log();
+        assert_eq!(log.len(), 4);
+        assert!(log[0].deployment.is_some());
+        assert_eq!(memchain.replay(&log).blocks, memchain.blocks);
+
+        // Each holder queries its balance, which is that of its sender, from its own replay.
+        for &(holder, balance) in &[(SENDER, 100), (CAESAR, 500), (BRUTUS, 400)] {
+            let replayed = MemchainGateway::new(memchain.replay(&log), holder);
+            let replayed_token = erc20::Erc20TokenClient::new(&replayed, token.address());
+            assert_eq!(replayed_token.balance_of(&ctx).unwrap(), Ok(balance));
+        }
+    }
 }
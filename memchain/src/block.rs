@@ -1,33 +1,111 @@
+use std::{borrow::Cow, cell::Cell};
+
 use blockchain_traits::TransactionOutcome;
 use oasis_types::{AccountMeta, Address, Event};
 
-use crate::{output::Receipt, pending_transaction::PendingTransaction, State};
+use crate::{
+    output::{CallTrace, Deployment, Receipt, TxRecord},
+    pending_transaction::PendingTransaction,
+    Account, GasSchedule, State,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Block<'bc> {
-    pub base_gas: u64,
+    pub gas_schedule: GasSchedule,
+    pub max_call_depth: usize,
+    pub tracing: bool,
     pub height: u64,
+    /// The time at which this block was created, in seconds since the Unix epoch.
+    pub timestamp: u64,
     pub state: State<'bc>,
     pub completed_transactions: Vec<Receipt>,
+    pub(crate) transaction_log: Vec<TxRecord>,
+    last_trace: Option<CallTrace>,
 }
 
 impl<'bc> Block<'bc> {
-    pub fn new(height: u64, state: State<'bc>, base_gas: u64) -> Self {
+    pub fn new(
+        height: u64,
+        timestamp: u64,
+        state: State<'bc>,
+        gas_schedule: GasSchedule,
+        max_call_depth: usize,
+        tracing: bool,
+    ) -> Self {
         Self {
             height,
+            timestamp,
             state,
             completed_transactions: Vec::new(),
-            base_gas,
+            transaction_log: Vec::new(),
+            gas_schedule,
+            max_call_depth,
+            tracing,
+            last_trace: None,
         }
     }
-}
 
-impl<'bc> blockchain_traits::Block for Block<'bc> {
-    fn height(&self) -> u64 {
-        self.height
+    /// Returns the trace of the last transaction executed in this block, if tracing is enabled.
+    pub fn last_trace(&self) -> Option<&CallTrace> {
+        self.last_trace.as_ref()
     }
 
-    fn transact(
+    fn record_trace(&mut self, receipt: &Receipt, input_len: usize, calls: Vec<CallTrace>) {
+        if self.tracing {
+            self.last_trace = Some(CallTrace::new(receipt, input_len, calls));
+        }
+    }
+
+    /// Creates a service at `address` that runs `deployment.main` once it has been
+    /// initialized by a transaction from `caller` to `deployment.ctor` with `value` and `input`.
+    /// The service is removed if the initializing transaction fails.
+    /// Like `transact`, the deployment is recorded in the transaction log.
+    #[allow(clippy::too_many_arguments)]
+    pub fn deploy(
+        &mut self,
+        caller: Address,
+        address: Address,
+        deployment: Deployment,
+        value: u128,
+        input: &[u8],
+        gas: u64,
+        gas_price: u64,
+    ) -> Box<dyn blockchain_traits::Receipt> {
+        self.transaction_log.push(TxRecord {
+            height: self.height,
+            timestamp: self.timestamp,
+            caller,
+            callee: address,
+            payer: caller,
+            value,
+            input: input.to_vec(),
+            gas,
+            gas_price,
+            deployment: Some(deployment.clone()),
+        });
+
+        self.state.insert(
+            address,
+            Cow::Owned(Account {
+                code: deployment.code,
+                main: Some(deployment.ctor),
+                ..Default::default()
+            }),
+        );
+        let receipt = self.execute(caller, address, caller, value, input, gas, gas_price);
+        if receipt.outcome == TransactionOutcome::Success {
+            self.state.get_mut(&address).unwrap().to_mut().main = Some(deployment.main);
+        } else {
+            self.state.remove(&address);
+        }
+        box receipt
+    }
+
+    /// Executes a transaction like `transact` and then rolls back its effects, so that
+    /// the block is left as it was, except for its `last_trace`. Simulated transactions
+    /// are not recorded in the transaction log.
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate(
         &mut self,
         caller: Address,
         callee: Address,
@@ -37,11 +115,29 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
         gas: u64,
         gas_price: u64,
     ) -> Box<dyn blockchain_traits::Receipt> {
+        let state = self.state.clone();
+        let receipt = self.execute(caller, callee, payer, value, input, gas, gas_price);
+        self.state = state;
+        self.completed_transactions.pop();
+        box receipt
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn execute(
+        &mut self,
+        caller: Address,
+        callee: Address,
+        payer: Address,
+        value: u128,
+        input: &[u8],
+        gas: u64,
+        gas_price: u64,
+    ) -> Receipt {
         let mut receipt = Receipt {
             caller,
             callee,
             value,
-            gas_used: gas,
+            gas_used: 0,
             output: Vec::new(),
             events: Vec::new(),
             outcome: TransactionOutcome::Success,
@@ -50,8 +146,9 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
         macro_rules! early_return {
             ($outcome:ident) => {{
                 receipt.outcome = TransactionOutcome::$outcome;
+                self.record_trace(&receipt, input.len(), Vec::new());
                 self.completed_transactions.push(receipt.clone());
-                return box receipt;
+                return receipt;
             }};
         }
 
@@ -59,49 +156,55 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
             early_return!(InvalidCallee);
         }
 
-        if gas < self.base_gas {
+        let intrinsic_gas = self.gas_schedule.intrinsic_gas(input);
+        if gas < intrinsic_gas {
             early_return!(InsufficientGas);
         }
 
-        match self.state.get_mut(&payer) {
-            Some(payer_acct) => {
-                let payer_acct = payer_acct.to_mut();
-                let gas_cost = gas * gas_price;
-                if payer_acct.balance < u128::from(gas_cost) {
-                    payer_acct.balance = 0;
-                    early_return!(InsufficientFunds);
-                }
-                payer_acct.balance -= u128::from(gas_cost);
-            }
-            None => early_return!(InvalidCallee),
-        };
+        let (payer_balance, caller_balance) =
+            match (self.state.get(&payer), self.state.get(&caller)) {
+                (Some(payer_acct), Some(caller_acct)) => (payer_acct.balance, caller_acct.balance),
+                _ => early_return!(InvalidCallee),
+            };
 
-        let mut ptx_state = self.state.clone();
+        // The full gas limit is reserved up front. Unused gas is refunded below.
+        // Both the gas and the value must be affordable before any balance is changed.
+        let gas_cost = u128::from(gas) * u128::from(gas_price);
+        let caller_gas_cost = if caller == payer { gas_cost } else { 0 };
+        let callee_balance = self.state.get(&callee).unwrap().balance;
+        let can_afford = payer_balance >= gas_cost
+            && caller_balance
+                .checked_sub(caller_gas_cost)
+                .and_then(|balance| balance.checked_sub(value))
+                .is_some()
+            && (caller == callee || callee_balance.checked_add(value).is_some());
+        if !can_afford {
+            early_return!(InsufficientFunds);
+        }
 
-        match ptx_state.get_mut(&caller) {
-            Some(caller_acct) => {
-                let caller_acct = caller_acct.to_mut();
-                if caller_acct.balance < value {
-                    early_return!(InsufficientFunds);
-                }
-                caller_acct.balance -= value;
-            }
-            None => early_return!(InvalidCallee),
-        };
+        self.state.get_mut(&payer).unwrap().to_mut().balance -= gas_cost;
 
+        let mut ptx_state = self.state.clone();
+        ptx_state.get_mut(&caller).unwrap().to_mut().balance -= value;
         ptx_state.get_mut(&callee).unwrap().to_mut().balance += value;
 
         let mut pending_transaction = PendingTransaction {
+            origin: caller,
             caller,
             callee,
             value,
+            timestamp: self.timestamp,
             input: input.to_vec(),
             outcome: TransactionOutcome::Success,
             state: ptx_state,
             events: Vec::new(),
             output: Vec::new(),
-            base_gas: self.base_gas,
-            gas_left: gas - self.base_gas,
+            gas_schedule: self.gas_schedule,
+            gas_limit: gas,
+            gas_used: Cell::new(intrinsic_gas),
+            depth: 1,
+            max_call_depth: self.max_call_depth,
+            calls: if self.tracing { Some(Vec::new()) } else { None },
         };
 
         if let Some(main) = self.state.get(&callee).unwrap().main {
@@ -113,10 +216,12 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
                 &(std::mem::transmute::<&mut _, &'static mut _>(ptx) as *mut _) as *const _
             });
             if errno != 0 {
-                pending_transaction.outcome = TransactionOutcome::Aborted;
+                pending_transaction.outcome = crate::failed_outcome(errno);
             }
         }
 
+        pending_transaction.check_gas();
+        receipt.gas_used = pending_transaction.gas_used.get();
         receipt.outcome = pending_transaction.outcome;
         receipt.output = pending_transaction.output;
         if blockchain_traits::Receipt::reverted(&receipt) {
@@ -125,8 +230,52 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
             self.state = pending_transaction.state;
             receipt.events.append(&mut pending_transaction.events);
         }
+        if let Some(payer_acct) = self.state.get_mut(&payer) {
+            payer_acct.to_mut().balance +=
+                u128::from(gas - receipt.gas_used) * u128::from(gas_price);
+        }
+        self.record_trace(
+            &receipt,
+            input.len(),
+            pending_transaction.calls.unwrap_or_default(),
+        );
         self.completed_transactions.push(receipt.clone());
-        box receipt
+        receipt
+    }
+}
+
+impl<'bc> blockchain_traits::Block for Block<'bc> {
+    fn height(&self) -> u64 {
+        self.height
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    fn transact(
+        &mut self,
+        caller: Address,
+        callee: Address,
+        payer: Address,
+        value: u128,
+        input: &[u8],
+        gas: u64,
+        gas_price: u64,
+    ) -> Box<dyn blockchain_traits::Receipt> {
+        self.transaction_log.push(TxRecord {
+            height: self.height,
+            timestamp: self.timestamp,
+            caller,
+            callee,
+            payer,
+            value,
+            input: input.to_vec(),
+            gas,
+            gas_price,
+            deployment: None,
+        });
+        box self.execute(caller, callee, payer, value, input, gas, gas_price)
     }
 
     fn code_at(&self, addr: &Address) -> Option<&[u8]> {
@@ -137,6 +286,7 @@ impl<'bc> blockchain_traits::Block for Block<'bc> {
         self.state.get(addr).map(|acct| AccountMeta {
             balance: acct.balance,
             expiry: acct.expiry,
+            storage_size: acct.storage_size(),
         })
     }
 
@@ -7,43 +7,206 @@ mod pending_transaction;
 
 use std::{borrow::Cow, collections::HashMap, convert::TryInto};
 
-use blockchain_traits::Blockchain;
+use blockchain_traits::{Blockchain, TransactionOutcome};
 use oasis_types::Address;
 
 pub use block::Block;
+pub use output::{CallTrace, Deployment, Receipt, TxRecord};
 
 type State<'bc> = HashMap<Address, Cow<'bc, Account>>;
 
 pub type PtxPtr = *const *mut dyn blockchain_traits::PendingTransaction;
+
+/// Returns `0` if the transaction succeeded, `TransactionOutcome::InvalidInput as u16` if
+/// the account could not decode the transaction's input, or any other nonzero value if
+/// the transaction was aborted. Any error output is written using `PendingTransaction::err`.
 pub type AccountMain = extern "C" fn(PtxPtr) -> u16;
 
+/// Returns the outcome of a transaction whose `AccountMain` returned the nonzero `errno`.
+fn failed_outcome(errno: u16) -> TransactionOutcome {
+    if errno == TransactionOutcome::InvalidInput as u16 {
+        TransactionOutcome::InvalidInput
+    } else {
+        TransactionOutcome::Aborted
+    }
+}
+
+/// The default maximum depth of nested transactions. See `Memchain::max_call_depth`.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
+
 #[derive(Debug)]
 pub struct Memchain<'bc> {
     pub name: String,
     pub blocks: Vec<Block<'bc>>,
-    pub base_gas: u64,
+    pub gas_schedule: GasSchedule,
+
+    /// The maximum number of nested transactions, including the top-level one.
+    /// A call that would exceed the limit is aborted instead of being executed.
+    pub max_call_depth: usize,
+
+    /// Whether blocks record a `CallTrace` of each transaction.
+    pub tracing: bool,
+
+    /// The state of the first block before any transactions, from which a chain is replayed.
+    genesis_state: State<'bc>,
 }
 
 impl<'bc> Memchain<'bc> {
-    pub fn new<S: AsRef<str>>(name: S, genesis_state: State<'bc>, base_gas: u64) -> Self {
+    /// Creates a new `Memchain`. `gas_schedule` may be a plain `u64`, which
+    /// charges a flat base cost per transaction.
+    pub fn new<S: AsRef<str>, G: Into<GasSchedule>>(
+        name: S,
+        genesis_state: State<'bc>,
+        gas_schedule: G,
+    ) -> Self {
         let mut bc = Self {
             name: name.as_ref().to_string(),
             blocks: Vec::new(),
-            base_gas,
+            gas_schedule: gas_schedule.into(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            tracing: false,
+            genesis_state: genesis_state.clone(),
         };
         bc.create_block_with_state(genesis_state);
         bc
     }
 
+    /// Sets the maximum depth of nested transactions in this and all future blocks.
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.max_call_depth = max_call_depth;
+        for block in self.blocks.iter_mut() {
+            block.max_call_depth = max_call_depth;
+        }
+        self
+    }
+
+    /// Enables or disables the tracing of transactions in this and all future blocks.
+    /// The trace of the last transaction is returned by `Block::last_trace`.
+    pub fn with_tracing(mut self, tracing: bool) -> Self {
+        self.tracing = tracing;
+        for block in self.blocks.iter_mut() {
+            block.tracing = tracing;
+        }
+        self
+    }
+
+    /// Adds `accounts` to the genesis state and, so, to every block. Each account replaces
+    /// any account already at its address.
+    pub fn with_genesis(mut self, accounts: Vec<(Address, Account)>) -> Self {
+        for (addr, account) in accounts.iter() {
+            self.genesis_state
+                .insert(*addr, Cow::Owned(account.clone()));
+        }
+        for block in self.blocks.iter_mut() {
+            for (addr, account) in accounts.iter() {
+                block.state.insert(*addr, Cow::Owned(account.clone()));
+            }
+        }
+        self
+    }
+
+    /// Adds a service to the genesis state at `addr`. The service runs `main` when called
+    /// and its storage is initially `storage`.
+    pub fn with_service(
+        self,
+        addr: Address,
+        main: AccountMain,
+        storage: HashMap<Vec<u8>, Vec<u8>>,
+    ) -> Self {
+        self.with_genesis(vec![(
+            addr,
+            Account {
+                storage,
+                main: Some(main),
+                ..Default::default()
+            },
+        )])
+    }
+
+    /// Returns the transactions submitted to each block, in order, including those that
+    /// failed. Nested transactions are not included, as they are made by the replayed ones.
+    pub fn transaction_log(&self) -> Vec<TxRecord> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.transaction_log.iter().cloned())
+            .collect()
+    }
+
+    /// Returns a new chain to which the transactions in `log` have been submitted in order.
+    /// `self` supplies only the new chain's genesis state and configuration (its name, gas
+    /// schedule, maximum call depth, and tracing), so a log may be replayed by any chain
+    /// created with the same genesis state. Blocks are created as needed to reach the
+    /// height of each transaction. Replaying a chain's own `transaction_log` reproduces
+    /// the chain's blocks.
+    pub fn replay(&self, log: &[TxRecord]) -> Self {
+        let mut bc = Self {
+            name: self.name.clone(),
+            blocks: Vec::new(),
+            gas_schedule: self.gas_schedule,
+            max_call_depth: self.max_call_depth,
+            tracing: self.tracing,
+            genesis_state: self.genesis_state.clone(),
+        };
+        bc.create_block_with_state(bc.genesis_state.clone());
+        for tx in log {
+            while (bc.blocks.len() as u64) <= tx.height {
+                bc.create_block();
+            }
+            let block = &mut bc.blocks[tx.height as usize];
+            block.timestamp = tx.timestamp;
+            match &tx.deployment {
+                Some(deployment) => {
+                    block.deploy(
+                        tx.caller,
+                        tx.callee,
+                        deployment.clone(),
+                        tx.value,
+                        &tx.input,
+                        tx.gas,
+                        tx.gas_price,
+                    );
+                }
+                None => {
+                    blockchain_traits::Block::transact(
+                        block,
+                        tx.caller,
+                        tx.callee,
+                        tx.payer,
+                        tx.value,
+                        &tx.input,
+                        tx.gas,
+                        tx.gas_price,
+                    );
+                }
+            }
+        }
+        bc
+    }
+
+    /// Appends a block whose state starts as a copy of the last block's. Each block keeps
+    /// its own state, so earlier blocks continue to reflect the state at their heights.
+    /// The block is timestamped with the current time, but never earlier than its parent.
     pub fn create_block(&mut self) -> &mut Block<'bc> {
         self.create_block_with_state(self.blocks.last().unwrap().state.clone())
     }
 
     fn create_block_with_state(&mut self, state: State<'bc>) -> &mut Block<'bc> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or_default();
+        let timestamp = self
+            .blocks
+            .last()
+            .map(|parent| parent.timestamp.max(now))
+            .unwrap_or(now);
         self.blocks.push(Block::new(
             self.blocks.len().try_into().unwrap(),
+            timestamp,
             state,
-            self.base_gas,
+            self.gas_schedule,
+            self.max_call_depth,
+            self.tracing,
         ));
         self.blocks.last_mut().unwrap()
     }
@@ -69,6 +232,54 @@ impl<'bc> Blockchain for Memchain<'bc> {
     }
 }
 
+/// The gas costs charged for the operations performed by a transaction.
+/// The total is reported as the transaction receipt's `gas_used`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// Charged once for every transaction, including nested ones.
+    pub base: u64,
+
+    /// Charged for every read of (or existence check against) account storage.
+    pub storage_read: u64,
+
+    /// Charged for every write to (or removal from) account storage.
+    pub storage_write: u64,
+
+    /// Charged for every byte of call input.
+    pub input_byte: u64,
+}
+
+impl GasSchedule {
+    /// A schedule that charges for storage accesses. oasis-build uses it to estimate
+    /// the gas of an RPC from the number of times that the RPC accesses its state.
+    pub const METERED: Self = Self {
+        base: 2_100,
+        storage_read: 200,
+        storage_write: 5_000,
+        input_byte: 10,
+    };
+
+    /// Returns a schedule that charges only `base` gas per transaction.
+    pub fn flat(base: u64) -> Self {
+        Self {
+            base,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the gas charged before a transaction with `input` is executed.
+    pub fn intrinsic_gas(&self, input: &[u8]) -> u64 {
+        self.base
+            .saturating_add(self.input_byte.saturating_mul(input.len() as u64))
+    }
+}
+
+impl From<u64> for GasSchedule {
+    fn from(base: u64) -> Self {
+        Self::flat(base)
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct Account {
     pub balance: u128,
@@ -79,10 +290,18 @@ pub struct Account {
     /// Callable account entrypoint. `main` takes an pointer to a
     /// `Blockchain` trait object which can be used via FFI bindings
     /// to interact with the memchain. Returns nonzero to revert transaction.
+    /// @see `AccountMain`.
     /// This pointer is not valid after the call to `main` has returned.
     pub main: Option<AccountMain>,
 }
 
+impl Account {
+    /// Returns the number of bytes occupied by the keys and values in this account's storage.
+    pub fn storage_size(&self) -> usize {
+        self.storage.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+}
+
 impl blockchain_traits::KVStore for Account {
     fn contains(&self, key: &[u8]) -> bool {
         self.storage.contains_key(key)
@@ -1,6 +1,8 @@
 use blockchain_traits::TransactionOutcome;
 use oasis_types::{Address, Event};
 
+use crate::AccountMain;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Receipt {
     pub outcome: TransactionOutcome,
@@ -14,6 +16,62 @@ pub struct Receipt {
     pub output: Vec<u8>,
 }
 
+/// The parameters of a top-level transaction submitted to a block, from which the
+/// transaction can be replayed. See `Memchain::transaction_log`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxRecord {
+    /// The height of the block to which the transaction was submitted.
+    pub height: u64,
+    /// The timestamp of the block at the time that the transaction was submitted.
+    pub timestamp: u64,
+    pub caller: Address,
+    pub callee: Address,
+    pub payer: Address,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas: u64,
+    pub gas_price: u64,
+    /// The service created by the transaction, if it was a deployment.
+    /// The `callee` is the address of the new service and the `input` its constructor's.
+    pub deployment: Option<Deployment>,
+}
+
+/// A service created using `Block::deploy`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    pub code: Vec<u8>,
+    /// Called with the constructor input when the service is deployed.
+    pub ctor: AccountMain,
+    /// Called by the transactions made to the service once it is deployed.
+    pub main: AccountMain,
+}
+
+/// A record of a transaction and the nested transactions that it made.
+/// Traces are only collected when tracing is enabled using `Memchain::with_tracing`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallTrace {
+    pub caller: Address,
+    pub callee: Address,
+    pub value: u128,
+    pub input_len: usize,
+    pub outcome: TransactionOutcome,
+    /// The traces of the nested transactions, in the order in which they were made.
+    pub calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    pub(crate) fn new(receipt: &Receipt, input_len: usize, calls: Vec<CallTrace>) -> Self {
+        Self {
+            caller: receipt.caller,
+            callee: receipt.callee,
+            value: receipt.value,
+            input_len,
+            outcome: receipt.outcome,
+            calls,
+        }
+    }
+}
+
 impl blockchain_traits::Receipt for Receipt {
     fn caller(&self) -> &Address {
         &self.caller
@@ -1,20 +1,63 @@
+use std::cell::Cell;
+
 use blockchain_traits::TransactionOutcome;
 use oasis_types::{AccountMeta, Address, Event};
 
-use crate::{output::Receipt, State};
+use crate::{
+    output::{CallTrace, Receipt},
+    Account, GasSchedule, State,
+};
 
 #[derive(Debug)]
 pub struct PendingTransaction<'bc> {
+    pub origin: Address,
     pub caller: Address,
     pub callee: Address,
     pub value: u128,
+    pub timestamp: u64,
     pub state: State<'bc>,
     pub input: Vec<u8>,
     pub outcome: TransactionOutcome,
     pub output: Vec<u8>,
     pub events: Vec<Event>,
-    pub gas_left: u64,
-    pub base_gas: u64,
+    pub gas_schedule: GasSchedule,
+    pub gas_limit: u64,
+    pub gas_used: Cell<u64>,
+
+    /// The number of transactions on the call stack, including this one.
+    pub depth: usize,
+    pub max_call_depth: usize,
+
+    /// The traces of the nested transactions made so far, if tracing is enabled.
+    pub calls: Option<Vec<CallTrace>>,
+}
+
+impl<'bc> PendingTransaction<'bc> {
+    fn account(&self) -> &Account {
+        self.state.get(&self.callee).unwrap()
+    }
+
+    fn account_mut(&mut self) -> &mut Account {
+        self.state.get_mut(&self.callee).unwrap().to_mut()
+    }
+
+    fn charge(&self, gas: u64) {
+        self.gas_used.set(self.gas_used.get().saturating_add(gas));
+    }
+
+    fn record_call(&mut self, receipt: &Receipt, input_len: usize, calls: Vec<CallTrace>) {
+        if let Some(traces) = &mut self.calls {
+            traces.push(CallTrace::new(receipt, input_len, calls));
+        }
+    }
+
+    /// Marks the transaction as having run out of gas if it used more than its limit.
+    pub(crate) fn check_gas(&mut self) {
+        if self.gas_used.get() > self.gas_limit {
+            self.gas_used.set(self.gas_limit);
+            self.outcome = TransactionOutcome::InsufficientGas;
+        }
+    }
 }
 
 impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
@@ -26,10 +69,18 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
         &self.caller
     }
 
+    fn origin(&self) -> &Address {
+        &self.origin
+    }
+
     fn value(&self) -> u128 {
         self.value
     }
 
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
     fn input(&self) -> &[u8] {
         self.input.as_slice()
     }
@@ -49,20 +100,33 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
             caller,
             callee,
             value,
-            gas_used: 0, // TODO(#116)
+            gas_used: 0,
             output: Vec::new(),
             events: Vec::new(),
             outcome: TransactionOutcome::Success,
         };
 
-        if self.gas_left < self.base_gas {
-            receipt.outcome = TransactionOutcome::InsufficientGas;
-            return box receipt;
+        macro_rules! early_return {
+            ($outcome:ident) => {{
+                receipt.outcome = TransactionOutcome::$outcome;
+                self.record_call(&receipt, input.len(), Vec::new());
+                return box receipt;
+            }};
+        }
+
+        // Aborting here keeps re-entrant services from overflowing the native stack.
+        if self.depth >= self.max_call_depth {
+            early_return!(Aborted);
+        }
+
+        let gas_left = self.gas_limit.saturating_sub(self.gas_used.get());
+        let intrinsic_gas = self.gas_schedule.intrinsic_gas(input);
+        if gas_left < intrinsic_gas {
+            early_return!(InsufficientGas);
         }
 
         if !self.state.contains_key(&callee) {
-            receipt.outcome = TransactionOutcome::InvalidCallee;
-            return box receipt;
+            early_return!(InvalidCallee);
         }
 
         let mut ptx_state = self.state.clone();
@@ -70,8 +134,7 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
         let caller_acct = ptx_state.get_mut(&caller).unwrap().to_mut();
 
         if caller_acct.balance < value {
-            receipt.outcome = TransactionOutcome::InsufficientFunds;
-            return box receipt;
+            early_return!(InsufficientFunds);
         } else {
             caller_acct.balance -= value
         }
@@ -79,16 +142,22 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
         ptx_state.get_mut(&callee).unwrap().to_mut().balance += value;
 
         let mut pending_transaction = PendingTransaction {
+            origin: self.origin,
             caller: self.callee,
             callee,
             value,
+            timestamp: self.timestamp,
             input: input.to_vec(),
             outcome: TransactionOutcome::Success,
             state: ptx_state,
             events: Vec::new(),
             output: Vec::new(),
-            base_gas: self.base_gas,
-            gas_left: self.gas_left - self.base_gas,
+            gas_schedule: self.gas_schedule,
+            gas_limit: gas_left,
+            gas_used: Cell::new(intrinsic_gas),
+            depth: self.depth + 1,
+            max_call_depth: self.max_call_depth,
+            calls: self.calls.as_ref().map(|_| Vec::new()),
         };
 
         if let Some(main) = self.state.get(&callee).unwrap().main {
@@ -100,10 +169,13 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
                 &(std::mem::transmute::<&mut _, &'static mut _>(ptx) as *mut _) as *const _
             });
             if errno != 0 {
-                pending_transaction.outcome = TransactionOutcome::Aborted;
+                pending_transaction.outcome = crate::failed_outcome(errno);
             }
         }
 
+        pending_transaction.check_gas();
+        receipt.gas_used = pending_transaction.gas_used.get();
+        self.charge(receipt.gas_used);
         receipt.outcome = pending_transaction.outcome;
         receipt.output = pending_transaction.output;
         if blockchain_traits::Receipt::reverted(&receipt) {
@@ -115,6 +187,11 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
                 .append(&mut pending_transaction.events.clone());
             self.events.append(&mut pending_transaction.events);
         }
+        self.record_call(
+            &receipt,
+            input.len(),
+            pending_transaction.calls.unwrap_or_default(),
+        );
         box receipt
     }
 
@@ -146,14 +223,11 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
     }
 
     fn state(&self) -> &dyn blockchain_traits::KVStore {
-        self.state.get(&self.callee).map(|acct| &**acct).unwrap()
+        self
     }
 
     fn state_mut(&mut self) -> &mut dyn blockchain_traits::KVStoreMut {
-        self.state
-            .get_mut(&self.callee)
-            .map(std::borrow::Cow::to_mut)
-            .unwrap()
+        self
     }
 
     fn code_at(&self, addr: &Address) -> Option<&[u8]> {
@@ -164,6 +238,32 @@ impl<'bc> blockchain_traits::PendingTransaction for PendingTransaction<'bc> {
         self.state.get(addr).map(|acct| AccountMeta {
             balance: acct.balance,
             expiry: acct.expiry,
+            storage_size: acct.storage_size(),
         })
     }
 }
+
+/// Storage accesses made through the `PendingTransaction` are metered by its `GasSchedule`.
+impl<'bc> blockchain_traits::KVStore for PendingTransaction<'bc> {
+    fn contains(&self, key: &[u8]) -> bool {
+        self.charge(self.gas_schedule.storage_read);
+        self.account().contains(key)
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.charge(self.gas_schedule.storage_read);
+        self.account().get(key)
+    }
+}
+
+impl<'bc> blockchain_traits::KVStoreMut for PendingTransaction<'bc> {
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.charge(self.gas_schedule.storage_write);
+        self.account_mut().set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.charge(self.gas_schedule.storage_write);
+        self.account_mut().remove(key);
+    }
+}
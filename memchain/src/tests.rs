@@ -6,6 +6,7 @@ use crate::*;
 
 const ADDR_1: Address = Address([1u8; 20]);
 const ADDR_2: Address = Address([2u8; 20]);
+const ADDR_3: Address = Address([3u8; 20]);
 
 const BASE_GAS: u64 = 2100;
 
@@ -37,6 +38,20 @@ extern "C" fn fail_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
     1
 }
 
+extern "C" fn invalid_input_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    ptx.err(&7u32.to_le_bytes()); // the selector of the requested method
+    blockchain_traits::TransactionOutcome::InvalidInput as u16
+}
+
+extern "C" fn write_fail_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    ptx.state_mut().set(b"common_key", b"uncommon_value");
+    ptx.state_mut().set(b"new_key", b"new_value");
+    ptx.err(b"panicked");
+    1
+}
+
 extern "C" fn subtx_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
     let ptx = unsafe { &mut **ptx };
     let subtx = ptx.transact(ADDR_1, 0 /* value */, &ptx.input().to_vec());
@@ -54,8 +69,84 @@ extern "C" fn subtx_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
     0
 }
 
+extern "C" fn caller_info_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let mut rv = ptx.sender().0.to_vec();
+    rv.extend_from_slice(&ptx.origin().0);
+    ptx.ret(&rv);
+    0
+}
+
+extern "C" fn read_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let value = ptx.state().get(b"common_key").unwrap();
+    ptx.ret(&value);
+    0
+}
+
+extern "C" fn write_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    for i in 0..3u8 {
+        ptx.state_mut().set(&[i], b"value");
+    }
+    0
+}
+
+extern "C" fn pay_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let subtx = ptx.transact(ADDR_1, 7 /* value */, &ptx.input().to_vec());
+    subtx.reverted() as u16
+}
+
+extern "C" fn emit_child_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    ptx.emit(&[b"child".as_ref()], &[2]);
+    0
+}
+
+extern "C" fn emit_parent_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    ptx.emit(&[b"parent".as_ref()], &[1]);
+    ptx.transact(ADDR_1, 0 /* value */, &[]);
+    ptx.emit(&[b"parent".as_ref()], &[3]);
+    0
+}
+
+/// Calls itself with an incremented counter until a nested call fails, then
+/// returns the number of frames that were on the call stack.
+extern "C" fn reentrant_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let num_callers = ptx.input()[0];
+    let subtx = ptx.transact(*ptx.address(), 0 /* value */, &[num_callers + 1]);
+    if subtx.reverted() {
+        ptx.ret(&[num_callers + 1]);
+    } else {
+        ptx.ret(subtx.output());
+    }
+    0
+}
+
+/// Returns the timestamp seen by itself followed by the one seen by a nested call to itself.
+extern "C" fn timestamp_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let mut rv = ptx.timestamp().to_le_bytes().to_vec();
+    if ptx.input().is_empty() {
+        let subtx = ptx.transact(*ptx.address(), 0 /* value */, b"nested");
+        rv.extend_from_slice(subtx.output());
+    }
+    ptx.ret(&rv);
+    0
+}
+
 fn create_bc<'bc>(
     mains: Vec<Option<extern "C" fn(*const *mut dyn PendingTransaction) -> u16>>,
+) -> Memchain<'bc> {
+    create_bc_with_gas_schedule(mains, GasSchedule::flat(BASE_GAS))
+}
+
+fn create_bc_with_gas_schedule<'bc>(
+    mains: Vec<Option<extern "C" fn(*const *mut dyn PendingTransaction) -> u16>>,
+    gas_schedule: GasSchedule,
 ) -> Memchain<'bc> {
     let genesis_state = mains
         .into_iter()
@@ -86,7 +177,85 @@ fn create_bc<'bc>(
         })
         .collect();
 
-    Memchain::new("memchain".to_string(), genesis_state, BASE_GAS)
+    Memchain::new("memchain".to_string(), genesis_state, gas_schedule)
+}
+
+/// Increments the counter stored under `count` and returns its new value.
+extern "C" fn counter_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let count = ptx.state().get(b"count").unwrap()[0] + 1;
+    ptx.state_mut().set(b"count", &[count]);
+    ptx.ret(&[count]);
+    0
+}
+
+#[test]
+fn genesis_service() {
+    let mut storage = HashMap::new();
+    storage.insert(b"count".to_vec(), vec![41]);
+    let mut bc = Memchain::new("memchain", HashMap::new(), BASE_GAS)
+        .with_genesis(vec![(
+            ADDR_1,
+            Account {
+                balance: giga(1),
+                ..Default::default()
+            },
+        )])
+        .with_service(ADDR_2, counter_main, storage);
+
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS, 0);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(receipt.output(), &[42]);
+
+    bc.create_block();
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS, 0);
+    assert_eq!(receipt.output(), &[43]);
+}
+
+/// Writes `new_key` if the input is empty, or removes it otherwise.
+extern "C" fn set_or_remove_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    if ptx.input().is_empty() {
+        ptx.state_mut().set(b"new_key", b"new_value");
+    } else {
+        ptx.state_mut().remove(b"new_key");
+    }
+    0
+}
+
+#[test]
+fn storage_size() {
+    let mut bc = create_bc(vec![None, Some(set_or_remove_main)]);
+    let storage_size = |bc: &Memchain| {
+        bc.last_block()
+            .account_meta_at(&ADDR_2)
+            .unwrap()
+            .storage_size
+    };
+
+    // `common_key`, `common_value`, `key_2`, and `value_2` from `create_bc`
+    let initial_size = 10 + 12 + 5 + 7;
+    assert_eq!(storage_size(&bc), initial_size);
+
+    bc.last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS, 0);
+    assert_eq!(storage_size(&bc), initial_size + 7 + 9);
+
+    // Overwriting a key with a value of the same length does not change the size.
+    bc.last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS, 0);
+    assert_eq!(storage_size(&bc), initial_size + 7 + 9);
+
+    bc.last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[1], BASE_GAS, 0);
+    assert_eq!(storage_size(&bc), initial_size);
 }
 
 #[test]
@@ -158,6 +327,37 @@ fn static_account() {
     );
 }
 
+/// Stores the input under `common_key`.
+extern "C" fn store_input_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+    let ptx = unsafe { &mut **ptx };
+    let input = ptx.input().to_vec();
+    ptx.state_mut().set(b"common_key", &input);
+    0
+}
+
+#[test]
+fn historical_state() {
+    let mut bc = create_bc(vec![None, Some(store_input_main)]);
+
+    bc.create_block();
+    bc.last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, b"first", BASE_GAS, 0);
+    bc.create_block();
+    bc.last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, b"second", BASE_GAS, 0);
+
+    let value_at = |height: usize| {
+        bc.block(height)
+            .unwrap()
+            .state_at(&ADDR_2)
+            .unwrap()
+            .get(b"common_key")
+    };
+    assert_eq!(value_at(0), Some(b"common_value".to_vec()));
+    assert_eq!(value_at(1), Some(b"first".to_vec()));
+    assert_eq!(value_at(2), Some(b"second".to_vec()));
+}
+
 #[test]
 fn simple_tx() {
     let mut bc = create_bc(vec![Some(simple_main), None]);
@@ -184,6 +384,97 @@ fn revert_tx() {
     );
 }
 
+#[test]
+fn invalid_input_tx() {
+    let mut bc = create_bc(vec![None, Some(invalid_input_main), Some(fail_main)]);
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::InvalidInput
+    );
+    assert_eq!(receipt.output(), 7u32.to_le_bytes());
+
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_3, ADDR_1, 0, &[], BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Aborted
+    );
+}
+
+#[test]
+fn insufficient_funds() {
+    let mut bc = create_bc(vec![None, Some(nop_main)]);
+    let state = bc.blocks.last().unwrap().state.clone();
+
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_1, ADDR_2, ADDR_1, giga(1) + 1, &[], BASE_GAS, 0);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::InsufficientFunds
+    );
+
+    // The value is affordable, but not together with the gas.
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, giga(1), &[], BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::InsufficientFunds
+    );
+
+    let receipt = bc.last_block_mut().transact(
+        ADDR_1,
+        ADDR_2,
+        ADDR_1,
+        0,
+        &[],
+        u64::max_value(),
+        u64::max_value(),
+    );
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::InsufficientFunds
+    );
+
+    assert_eq!(bc.blocks.last().unwrap().state, state);
+}
+
+#[test]
+fn revert_tx_state() {
+    let mut bc = create_bc(vec![None, Some(write_fail_main)]);
+    let value = 10_000;
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_1, ADDR_2, ADDR_1, value, &Vec::new(), BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Aborted
+    );
+    assert_eq!(receipt.output(), b"panicked");
+
+    let callee_state = bc.last_block().state_at(&ADDR_2).unwrap();
+    assert_eq!(callee_state.get(b"new_key"), None);
+    assert_eq!(
+        callee_state.get(b"common_key"),
+        Some(b"common_value".to_vec())
+    );
+
+    // Only the gas is charged; the value transfer is rolled back.
+    assert_eq!(
+        bc.last_block().account_meta_at(&ADDR_1).unwrap().balance,
+        giga(1) - u128::from(BASE_GAS),
+    );
+    assert_eq!(
+        bc.last_block().account_meta_at(&ADDR_2).unwrap().balance,
+        giga(2),
+    );
+}
+
 #[test]
 fn subtx_ok() {
     let mut bc = create_bc(vec![Some(simple_main), Some(subtx_main)]);
@@ -233,3 +524,214 @@ fn subtx_revert() {
         Some(b"common_value".to_vec())
     );
 }
+
+#[test]
+fn subtx_origin() {
+    let mut bc = create_bc(vec![Some(caller_info_main), Some(subtx_main), None]);
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_3, ADDR_2, ADDR_3, 0, &Vec::new(), BASE_GAS * 2, 0);
+
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+
+    // `subtx_main` calls `caller_info_main`, which returns its sender and origin.
+    let output = receipt.output();
+    assert_eq!(&output[..20], &ADDR_2.0);
+    assert_eq!(&output[20..40], &ADDR_3.0);
+}
+
+#[test]
+fn flat_gas() {
+    let mut bc = create_bc(vec![Some(write_main), None]);
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_2, ADDR_1, ADDR_2, 0, &[1, 2, 3], BASE_GAS * 2, 1);
+    assert_eq!(receipt.gas_used(), BASE_GAS);
+}
+
+#[test]
+fn unused_gas_refunded() {
+    let mut bc = create_bc(vec![Some(write_main), None]);
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_2, ADDR_1, ADDR_2, 0, &[1, 2, 3], BASE_GAS * 10, 3);
+    assert_eq!(receipt.gas_used(), BASE_GAS);
+    assert_eq!(
+        bc.last_block().account_meta_at(&ADDR_2).unwrap().balance,
+        giga(2) - u128::from(BASE_GAS * 3),
+    );
+}
+
+#[test]
+fn metered_gas() {
+    const GAS_SCHEDULE: GasSchedule = GasSchedule::METERED;
+    let mut bc =
+        create_bc_with_gas_schedule(vec![Some(read_main), Some(write_main), None], GAS_SCHEDULE);
+
+    let read_receipt =
+        bc.last_block_mut()
+            .transact(ADDR_3, ADDR_1, ADDR_3, 0, &[1, 2], BASE_GAS * 10, 0);
+    assert_eq!(
+        read_receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(read_receipt.output(), b"common_value");
+    assert_eq!(read_receipt.gas_used(), BASE_GAS + 2 * 10 + 200);
+
+    let write_receipt =
+        bc.last_block_mut()
+            .transact(ADDR_3, ADDR_2, ADDR_3, 0, &[1, 2], BASE_GAS * 10, 0);
+    assert_eq!(
+        write_receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(write_receipt.gas_used(), BASE_GAS + 2 * 10 + 3 * 5000);
+
+    assert!(write_receipt.gas_used() > read_receipt.gas_used());
+}
+
+#[test]
+fn metered_gas_exhausted() {
+    let gas_schedule = GasSchedule {
+        storage_write: 5000,
+        ..GasSchedule::flat(BASE_GAS)
+    };
+    let mut bc = create_bc_with_gas_schedule(vec![Some(write_main), None], gas_schedule);
+    let receipt =
+        bc.last_block_mut()
+            .transact(ADDR_2, ADDR_1, ADDR_2, 0, &Vec::new(), BASE_GAS + 5000, 0);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::InsufficientGas
+    );
+    assert_eq!(receipt.gas_used(), BASE_GAS + 5000);
+    assert_eq!(bc.last_block().state_at(&ADDR_1).unwrap().get(&[0]), None);
+}
+
+#[test]
+fn max_call_depth() {
+    let mut bc = create_bc(vec![Some(reentrant_main), None]).with_max_call_depth(16);
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_2, ADDR_1, ADDR_2, 0, &[0], BASE_GAS * 100, 0);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(receipt.output(), &[16]);
+}
+
+#[test]
+fn call_trace() {
+    let mut bc = create_bc(vec![Some(nop_main), Some(pay_main), None]).with_tracing(true);
+    bc.last_block_mut()
+        .transact(ADDR_3, ADDR_2, ADDR_3, 100, &[1, 2, 3], BASE_GAS * 2, 0);
+    assert_eq!(
+        bc.blocks.last().unwrap().last_trace(),
+        Some(&CallTrace {
+            caller: ADDR_3,
+            callee: ADDR_2,
+            value: 100,
+            input_len: 3,
+            outcome: blockchain_traits::TransactionOutcome::Success,
+            calls: vec![CallTrace {
+                caller: ADDR_2,
+                callee: ADDR_1,
+                value: 7,
+                input_len: 3,
+                outcome: blockchain_traits::TransactionOutcome::Success,
+                calls: Vec::new(),
+            }],
+        })
+    );
+    assert_eq!(
+        bc.last_block().account_meta_at(&ADDR_1).unwrap().balance,
+        giga(1) + 7
+    );
+}
+
+#[test]
+fn call_trace_disabled() {
+    let mut bc = create_bc(vec![Some(nop_main), Some(pay_main), None]);
+    bc.last_block_mut()
+        .transact(ADDR_3, ADDR_2, ADDR_3, 100, &[1, 2, 3], BASE_GAS * 2, 0);
+    assert_eq!(bc.blocks.last().unwrap().last_trace(), None);
+}
+
+#[test]
+fn event_order() {
+    let mut bc = create_bc(vec![Some(emit_child_main), Some(emit_parent_main), None]);
+    for _ in 0..2 {
+        bc.last_block_mut()
+            .transact(ADDR_3, ADDR_2, ADDR_3, 0, &[], BASE_GAS * 2, 0);
+    }
+
+    let receipt = bc.last_block().receipts()[1];
+    let receipt_data: Vec<_> = receipt.events().iter().map(|e| e.data[0]).collect();
+    assert_eq!(receipt_data, vec![1, 2, 3]);
+    assert_eq!(receipt.events()[1].emitter, ADDR_1);
+
+    let block_data: Vec<_> = bc.last_block().events().iter().map(|e| e.data[0]).collect();
+    assert_eq!(block_data, vec![1, 2, 3, 1, 2, 3]);
+}
+
+#[test]
+fn replay_deploy() {
+    let mut bc = create_bc(vec![None, None, None]);
+    let service = Address([5u8; 20]);
+    let deployment = Deployment {
+        code: b"code".to_vec(),
+        ctor: nop_main,
+        main: write_main,
+    };
+    let receipt = bc.blocks[0].deploy(ADDR_1, service, deployment, 0, &[], BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(bc.last_block().code_at(&service), Some(b"code".as_ref()));
+
+    // Simulated transactions leave no trace on the chain.
+    let receipt = bc.blocks[0].simulate(ADDR_1, service, ADDR_1, 0, &[], BASE_GAS, 1);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    assert_eq!(bc.last_block().state_at(&service).unwrap().get(&[0]), None);
+
+    let log = bc.transaction_log();
+    assert_eq!(log.len(), 1);
+    assert!(log[0].deployment.is_some());
+
+    let replayed = bc.replay(&log);
+    assert_eq!(replayed.blocks, bc.blocks);
+    assert_eq!(
+        replayed.last_block().code_at(&service),
+        Some(b"code".as_ref())
+    );
+}
+
+#[test]
+fn block_timestamp() {
+    let mut bc = create_bc(vec![None, Some(timestamp_main)]);
+    assert!(bc.blocks[0].timestamp > 0);
+
+    bc.create_block().timestamp = 1_600_000_000;
+    let receipt = bc
+        .last_block_mut()
+        .transact(ADDR_1, ADDR_2, ADDR_1, 0, &[], BASE_GAS * 2, 0);
+    assert_eq!(
+        receipt.outcome(),
+        blockchain_traits::TransactionOutcome::Success
+    );
+    let timestamp = 1_600_000_000u64.to_le_bytes();
+    assert_eq!(receipt.output(), [timestamp, timestamp].concat().as_slice());
+
+    // Blocks are never timestamped before their parents.
+    bc.blocks.last_mut().unwrap().timestamp = u64::max_value();
+    bc.create_block();
+    assert_eq!(bc.last_block().timestamp(), u64::max_value());
+}
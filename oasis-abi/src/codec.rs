@@ -0,0 +1,328 @@
+//! The `Serialize` and `Deserialize` traits used without `std`.
+//! They produce the same encoding as the `oasis-borsh` traits that they replace.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+
+use crate::io::{Error, ErrorKind, Read, Write};
+
+pub trait Serialize {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error>;
+
+    /// Serializes this instance into a vector of bytes.
+    fn try_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        self.serialize(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+pub trait Deserialize: Sized {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error>;
+
+    /// Deserializes an instance from the entirety of `bytes`.
+    fn try_from_slice(mut bytes: &[u8]) -> Result<Self, Error> {
+        let value = Self::deserialize(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, "not all bytes read"));
+        }
+        Ok(value)
+    }
+}
+
+impl Serialize for () {
+    fn serialize<W: Write>(&self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl Deserialize for () {
+    fn deserialize<R: Read>(_reader: &mut R) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_for_integer {
+    ($($int:ty),+) => {
+        $(
+            impl Serialize for $int {
+                fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Deserialize for $int {
+                fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    let mut le_bytes = [0u8; core::mem::size_of::<$int>()];
+                    reader.read_exact(&mut le_bytes)?;
+                    Ok(<$int>::from_le_bytes(le_bytes))
+                }
+            }
+        )+
+    };
+}
+
+impl_for_integer!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// Like `oasis-borsh`, NaNs are rejected because their bits are not portable.
+macro_rules! impl_for_float {
+    ($($float:ty => $bits:ty),+) => {
+        $(
+            impl Serialize for $float {
+                fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+                    if self.is_nan() {
+                        return Err(Error::new(ErrorKind::InvalidInput, "cannot serialize NaN"));
+                    }
+                    self.to_bits().serialize(writer)
+                }
+            }
+
+            impl Deserialize for $float {
+                fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    let float = <$float>::from_bits(<$bits>::deserialize(reader)?);
+                    if float.is_nan() {
+                        return Err(Error::new(ErrorKind::InvalidInput, "cannot deserialize NaN"));
+                    }
+                    Ok(float)
+                }
+            }
+        )+
+    };
+}
+
+impl_for_float!(f32 => u32, f64 => u64);
+
+/// Reads the tag of an `Option`, `Result`, or `bool`, which must be 0 or 1.
+fn deserialize_flag<R: Read>(reader: &mut R, msg: &'static str) -> Result<bool, Error> {
+    match u8::deserialize(reader)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::new(ErrorKind::InvalidInput, msg)),
+    }
+}
+
+impl Serialize for bool {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        (*self as u8).serialize(writer)
+    }
+}
+
+impl Deserialize for bool {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        deserialize_flag(reader, "invalid bool representation")
+    }
+}
+
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        match self {
+            None => 0u8.serialize(writer),
+            Some(value) => {
+                1u8.serialize(writer)?;
+                value.serialize(writer)
+            }
+        }
+    }
+}
+
+impl<T: Deserialize> Deserialize for Option<T> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        if deserialize_flag(reader, "invalid Option representation")? {
+            T::deserialize(reader).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Serialize, E: Serialize> Serialize for Result<T, E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        match self {
+            Err(err) => {
+                0u8.serialize(writer)?;
+                err.serialize(writer)
+            }
+            Ok(ok) => {
+                1u8.serialize(writer)?;
+                ok.serialize(writer)
+            }
+        }
+    }
+}
+
+impl<T: Deserialize, E: Deserialize> Deserialize for Result<T, E> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        if deserialize_flag(reader, "invalid Result representation")? {
+            T::deserialize(reader).map(Ok)
+        } else {
+            E::deserialize(reader).map(Err)
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for &T {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        (**self).serialize(writer)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Box<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        (**self).serialize(writer)
+    }
+}
+
+impl<T: Deserialize> Deserialize for Box<T> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        T::deserialize(reader).map(Box::new)
+    }
+}
+
+/// Writes the length prefix of a sequence of `len` items.
+fn serialize_len<W: Write>(len: usize, writer: &mut W) -> Result<(), Error> {
+    if len > u32::max_value() as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, "sequence is too long"));
+    }
+    (len as u32).serialize(writer)
+}
+
+/// Decodes a length-prefixed sequence of items.
+fn deserialize_seq<R: Read, T: Deserialize, C: Extend<T>>(
+    reader: &mut R,
+    mut seq: C,
+) -> Result<C, Error> {
+    let len = u32::deserialize(reader)?;
+    for _ in 0..len {
+        seq.extend(core::iter::once(T::deserialize(reader)?));
+    }
+    Ok(seq)
+}
+
+impl<T: Serialize> Serialize for [T] {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        serialize_len(self.len(), writer)?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+}
+
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        self.as_slice().serialize(writer)
+    }
+}
+
+impl<T: Deserialize> Deserialize for Vec<T> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        deserialize_seq(reader, Vec::new())
+    }
+}
+
+impl Serialize for str {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        self.as_bytes().serialize(writer)
+    }
+}
+
+impl Serialize for String {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        self.as_bytes().serialize(writer)
+    }
+}
+
+impl Deserialize for String {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        String::from_utf8(Vec::deserialize(reader)?)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid UTF-8"))
+    }
+}
+
+impl<T: Serialize> Serialize for BTreeSet<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        serialize_len(self.len(), writer)?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+}
+
+impl<T: Deserialize + Ord> Deserialize for BTreeSet<T> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        deserialize_seq(reader, BTreeSet::new())
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for BTreeMap<K, V> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        serialize_len(self.len(), writer)?;
+        self.iter().try_for_each(|entry| entry.serialize(writer))
+    }
+}
+
+impl<K: Deserialize + Ord, V: Deserialize> Deserialize for BTreeMap<K, V> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        deserialize_seq(reader, BTreeMap::new())
+    }
+}
+
+macro_rules! impl_for_array {
+    ($($len:literal),+) => {
+        $(
+            impl<T: Serialize> Serialize for [T; $len] {
+                fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+                    self.iter().try_for_each(|item| item.serialize(writer))
+                }
+            }
+
+            impl<T: Deserialize + Default> Deserialize for [T; $len] {
+                fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    let mut arr = Self::default();
+                    for item in arr.iter_mut() {
+                        *item = T::deserialize(reader)?;
+                    }
+                    Ok(arr)
+                }
+            }
+        )+
+    };
+}
+
+impl_for_array!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32
+);
+
+macro_rules! impl_for_tuple {
+    ($(($($ty:ident $idx:tt),+)),+) => {
+        $(
+            impl<$($ty: Serialize),+> Serialize for ($($ty,)+) {
+                fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+                    $(self.$idx.serialize(writer)?;)+
+                    Ok(())
+                }
+            }
+
+            impl<$($ty: Deserialize),+> Deserialize for ($($ty,)+) {
+                fn deserialize<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    Ok(($($ty::deserialize(reader)?,)+))
+                }
+            }
+        )+
+    };
+}
+
+impl_for_tuple!(
+    (T0 0),
+    (T0 0, T1 1),
+    (T0 0, T1 1, T2 2),
+    (T0 0, T1 1, T2 2, T3 3),
+    (T0 0, T1 1, T2 2, T3 3, T4 4),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11)
+);
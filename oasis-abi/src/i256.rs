@@ -0,0 +1,292 @@
+//! A 256-bit signed integer for services that need more range than `i128`.
+//!
+//! `I256` is encoded as the 32 little-endian bytes of its two's complement representation,
+//! which is how the IDL type `i256` is represented on the wire.
+
+use core::cmp::Ordering;
+
+use primitive_types::U256 as Bits;
+
+use crate::{
+    io::{self, Read, Write},
+    Deserialize, Serialize, U256,
+};
+
+/// Arithmetic operators panic on overflow, like those of `U256`.
+/// Use the `checked_*` methods to handle overflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct I256(Bits); // two's complement
+
+impl I256 {
+    pub fn zero() -> Self {
+        Self(Bits::zero())
+    }
+
+    pub fn max_value() -> Self {
+        Self(Bits::max_value() >> 1)
+    }
+
+    pub fn min_value() -> Self {
+        Self(Bits::one() << 255)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.bit(255)
+    }
+
+    /// Returns the absolute value of `self`, which does not overflow even for `min_value()`.
+    pub fn unsigned_abs(&self) -> U256 {
+        U256(if self.is_negative() {
+            negate(self.0)
+        } else {
+            self.0
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Self::from_sign_and_magnitude(!self.is_negative(), self.unsigned_abs().0)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let sum = Self(self.0.overflowing_add(other.0).0);
+        // The sum overflowed if it does not have the sign shared by both operands.
+        if self.is_negative() == other.is_negative() && sum.is_negative() != self.is_negative() {
+            None
+        } else {
+            Some(sum)
+        }
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let difference = Self(self.0.overflowing_sub(other.0).0);
+        // The difference overflowed if the operands' signs differ and it lacks the sign of `self`.
+        if self.is_negative() != other.is_negative()
+            && difference.is_negative() != self.is_negative()
+        {
+            None
+        } else {
+            Some(difference)
+        }
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let magnitude = self.unsigned_abs().0.checked_mul(other.unsigned_abs().0)?;
+        Self::from_sign_and_magnitude(self.is_negative() != other.is_negative(), magnitude)
+    }
+
+    /// Returns the number with the given sign and magnitude, or `None` if it is out of range.
+    fn from_sign_and_magnitude(is_negative: bool, magnitude: Bits) -> Option<Self> {
+        if magnitude.is_zero() {
+            Some(Self::zero())
+        } else if is_negative {
+            if magnitude > Self::min_value().0 {
+                None
+            } else {
+                Some(Self(negate(magnitude)))
+            }
+        } else if magnitude > Self::max_value().0 {
+            None
+        } else {
+            Some(Self(magnitude))
+        }
+    }
+}
+
+/// Returns the two's complement negation of `bits`.
+fn negate(bits: Bits) -> Bits {
+    (!bits).overflowing_add(Bits::one()).0
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => self.0.cmp(&other.0),
+        }
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! impl_op {
+    ($($op:ident::$op_fn:ident => $checked_fn:ident),+) => {
+        $(
+            impl core::ops::$op for I256 {
+                type Output = Self;
+
+                fn $op_fn(self, other: Self) -> Self {
+                    self.$checked_fn(other).expect("arithmetic operation overflow")
+                }
+            }
+        )+
+    };
+}
+
+impl_op!(Add::add => checked_add, Sub::sub => checked_sub, Mul::mul => checked_mul);
+
+impl core::ops::Neg for I256 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("arithmetic operation overflow")
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),+) => {
+        $(
+            impl From<$int> for I256 {
+                fn from(n: $int) -> Self {
+                    let n = n as i128;
+                    // `wrapping_abs` of `i128::MIN` is itself, whose bits are its magnitude.
+                    let magnitude = Bits::from(n.wrapping_abs() as u128);
+                    Self::from_sign_and_magnitude(n < 0, magnitude).unwrap()
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128);
+
+macro_rules! impl_from_uint {
+    ($($uint:ty),+) => {
+        $(
+            impl From<$uint> for I256 {
+                fn from(n: $uint) -> Self {
+                    Self(n.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128);
+
+impl core::fmt::Display for I256 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        self.unsigned_abs().fmt(f)
+    }
+}
+
+impl Serialize for I256 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        let mut le_bytes = [0u8; 32];
+        self.0.to_little_endian(&mut le_bytes);
+        writer.write_all(&le_bytes)
+    }
+}
+
+impl Deserialize for I256 {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        let mut le_bytes = [0u8; 32];
+        reader.read_exact(&mut le_bytes)?;
+        Ok(Self(Bits::from_little_endian(&le_bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(n: I256) -> Vec<u8> {
+        let encoded = n.try_to_vec().unwrap();
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(I256::try_from_slice(&encoded).unwrap(), n);
+        encoded
+    }
+
+    #[test]
+    fn test_round_trip_boundaries() {
+        assert_eq!(round_trip(I256::zero()), vec![0u8; 32]);
+        assert_eq!(round_trip(I256::from(-1i8)), vec![0xffu8; 32]);
+
+        let mut max = vec![0xffu8; 32];
+        max[31] = 0x7f;
+        assert_eq!(round_trip(I256::max_value()), max);
+
+        let mut min = vec![0u8; 32];
+        min[31] = 0x80;
+        assert_eq!(round_trip(I256::min_value()), min);
+
+        let mut i128_min = vec![0xffu8; 32];
+        i128_min[..16].copy_from_slice(&i128::min_value().to_le_bytes());
+        assert_eq!(round_trip(I256::from(i128::min_value())), i128_min);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        assert!(I256::try_from_slice(&[0u8; 31]).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (two, three) = (I256::from(2u8), I256::from(3u8));
+        assert_eq!(two - three, I256::from(-1i8));
+        assert_eq!(-two * three, I256::from(-6i8));
+        assert_eq!(-two * -three, I256::from(6i8));
+        assert_eq!(I256::from(-6i8) + three + three, I256::zero());
+        assert_eq!(
+            I256::from(i128::max_value()) + I256::from(1u8),
+            I256::from(u128::max_value() / 2 + 1)
+        );
+        assert_eq!(I256::from(-1i8).unsigned_abs(), U256::from(1u8));
+        assert_eq!(
+            I256::min_value().unsigned_abs(),
+            U256(I256::max_value().0 + 1)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let (min, max, one) = (I256::min_value(), I256::max_value(), I256::from(1u8));
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(min.checked_neg(), None);
+        assert_eq!(min.checked_mul(-one), None);
+        assert_eq!(max.checked_mul(I256::from(2u8)), None);
+        assert_eq!(min.checked_add(max), Some(-one));
+        assert_eq!(max.checked_neg().unwrap().checked_sub(one), Some(min));
+        assert_eq!(min.checked_mul(one), Some(min));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut nums = vec![
+            I256::max_value(),
+            I256::from(1u8),
+            I256::min_value(),
+            I256::zero(),
+            I256::from(-1i8),
+        ];
+        nums.sort();
+        assert_eq!(
+            nums,
+            vec![
+                I256::min_value(),
+                I256::from(-1i8),
+                I256::zero(),
+                I256::from(1u8),
+                I256::max_value(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(I256::from(-42i8).to_string(), "-42");
+        assert_eq!(I256::from(42u8).to_string(), "42");
+        assert_eq!(
+            I256::min_value().to_string(),
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! The subset of `std::io` used by the encoding, for use without `std`.
+
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The input ended before a value could be decoded.
+    UnexpectedEof,
+
+    /// The decoded bytes are not a valid encoding of the value.
+    InvalidData,
+
+    /// A value cannot be encoded or decoded, e.g., a NaN.
+    InvalidInput,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    msg: &'static str,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, msg: &'static str) -> Self {
+        Self { kind, msg }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.msg)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+pub trait Read {
+    /// Fills `buf` with the next `buf.len()` bytes of input.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()>;
+}
+
+impl Read for &[u8] {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() > self.len() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ));
+        }
+        let (head, tail) = self.split_at(buf.len());
+        buf.copy_from_slice(head);
+        *self = tail;
+        Ok(())
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+}
+
+pub trait Write {
+    /// Writes all of `buf` to the output.
+    fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+}
+
+impl Write for Vec<u8> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_all(buf)
+    }
+}
@@ -0,0 +1,123 @@
+//! The encoding of the RPC payloads and outputs of Oasis services.
+//!
+//! This crate is re-exported by `oasis-std` as `oasis_std::abi`. It can also be used by
+//! off-chain code, like a verifier of RPC payloads, that does not have `std`.
+//!
+//! ## `no_std`
+//!
+//! Disabling the default `std` feature makes this crate `#![no_std]` (it still requires `alloc`).
+//! The encoding is the same in both modes, but the following become unavailable:
+//!
+//! * `#[derive(Serialize, Deserialize)]`. Without `std`, `Serialize` and `Deserialize` are this
+//!   crate's own traits rather than the `oasis-borsh` traits, so they must be implemented by hand.
+//! * The implementations for `HashMap` and `HashSet`. `BTreeMap` and `BTreeSet` remain available.
+//! * The implementations for arrays of more than 32 items. Arrays require `T: Default` to decode.
+//! * Interoperation with `std::io`. The `io` module provides the `Read` and `Write` traits,
+//!   which are implemented for `&[u8]` and `Vec<u8>`, and an `Error` with a static message.
+//!
+//! The float canonicalization and the conversion of encoding errors into an `RpcError` live in
+//! `oasis-std`, which requires `std`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+mod codec;
+pub mod i256;
+#[cfg(not(feature = "std"))]
+pub mod io;
+mod stream;
+pub mod u256;
+
+#[cfg(feature = "std")]
+pub use std::io;
+
+#[cfg(not(feature = "std"))]
+pub use codec::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+pub use oasis_borsh::{BorshDeserialize as Deserialize, BorshSerialize as Serialize};
+
+pub use i256::I256;
+pub use stream::StreamDecoder;
+pub use u256::U256;
+
+#[doc(hidden)]
+pub mod reexports {
+    pub use alloc::vec::Vec;
+}
+
+/// Encodes arguments into the format expected by Oasis services.
+/// Returns an `io::Error` if an argument could not be encoded.
+///
+/// ## Example
+///
+/// ```
+/// let method_id = 4u8;
+/// let payload =
+///     oasis_abi::abi_encode!(method_id, "some data", &["some", "more", "args"], 42u32,).unwrap();
+/// assert_eq!(payload[0], method_id);
+/// ```
+#[macro_export]
+macro_rules! abi_encode {
+    ($( $arg:expr ),* $(,)?) => {
+        $crate::abi_encode!($($arg),* => $crate::reexports::Vec::new())
+    };
+    ($( $arg:expr ),* $(,)? => $buf:expr) => {
+        Ok($buf)
+            $(
+                .and_then(|mut buf| {
+                    #[allow(unused)] {
+                        use $crate::Serialize as _;
+                        $arg.serialize(&mut buf)?;
+                    }
+                    Ok(buf)
+                })
+            )*
+            .map_err(|err: $crate::io::Error| err)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+    #[test]
+    fn test_invoke() {
+        type T = ([u8; 32], String, Vec<u8>);
+        let things = ([1u8; 32], "an arg", (1..100).collect::<Vec<_>>());
+        let encoded = abi_encode!(&things).unwrap();
+        let decoded = T::try_from_slice(&encoded).unwrap();
+        assert_eq!(decoded.0, things.0);
+        assert_eq!(decoded.1, things.1);
+        assert_eq!(decoded.2, things.2);
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mut map = BTreeMap::new();
+        map.insert(2u16, Some(-1i8));
+        map.insert(1u16, None);
+        let encoded = abi_encode!(7u8, true, "hi", map.clone(), Result::<u8, ()>::Ok(3)).unwrap();
+        assert_eq!(
+            encoded,
+            vec![7, 1, 2, 0, 0, 0, b'h', b'i', 2, 0, 0, 0, 1, 0, 0, 2, 0, 1, 0xff, 1, 3]
+        );
+        let decoded =
+            <(u8, bool, String, BTreeMap<u16, Option<i8>>, Result<u8, ()>)>::try_from_slice(
+                &encoded,
+            )
+            .unwrap();
+        assert_eq!(decoded, (7, true, "hi".into(), map, Ok(3)));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(u32::try_from_slice(&[1, 2, 3]).is_err());
+        assert!(u8::try_from_slice(&[1, 2]).is_err());
+        assert!(bool::try_from_slice(&[2]).is_err());
+        assert!(String::try_from_slice(&[1, 0, 0, 0, 0xff]).is_err());
+    }
+}
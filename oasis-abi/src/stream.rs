@@ -0,0 +1,97 @@
+use core::marker::PhantomData;
+
+use crate::{io, Deserialize};
+
+/// Lazily decodes the items of an encoded sequence (e.g., a `Vec<T>`).
+/// Each item is read from `reader` only when the iterator is advanced, so a
+/// large RPC output can be processed without first decoding the entire `Vec`.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_abi::{Serialize as _, StreamDecoder};
+///
+/// let encoded = vec![1u32, 2, 3].try_to_vec().unwrap();
+/// let mut items = StreamDecoder::<_, u32>::new(encoded.as_slice()).unwrap();
+/// assert_eq!(items.len(), 3);
+/// assert_eq!(items.next().unwrap().unwrap(), 1);
+/// ```
+pub struct StreamDecoder<R, T> {
+    reader: R,
+    remaining: usize,
+    _item: PhantomData<T>,
+}
+
+impl<R: io::Read, T: Deserialize> StreamDecoder<R, T> {
+    /// Reads the length prefix of the sequence from `reader`.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let len = u32::deserialize(&mut reader)?;
+        Ok(Self {
+            reader,
+            remaining: len as usize,
+            _item: PhantomData,
+        })
+    }
+
+    /// Returns the reader, positioned after the last decoded item.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: io::Read, T: Deserialize> Iterator for StreamDecoder<R, T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = T::deserialize(&mut self.reader);
+        self.remaining = if item.is_ok() { self.remaining - 1 } else { 0 };
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<R: io::Read, T: Deserialize> ExactSizeIterator for StreamDecoder<R, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Serialize as _;
+
+    #[test]
+    fn test_stream_decode() {
+        let num_items = 10_000;
+        let posts: Vec<(u32, String)> =
+            (0..num_items).map(|i| (i, format!("post {}", i))).collect();
+        let mut encoded = posts.try_to_vec().unwrap();
+        encoded.extend_from_slice(b"trailing");
+
+        let mut decoder = StreamDecoder::<_, (u32, String)>::new(encoded.as_slice()).unwrap();
+        assert_eq!(decoder.len(), num_items as usize);
+        for i in 0..num_items {
+            let (id, text) = decoder.next().unwrap().unwrap();
+            assert_eq!(id, i);
+            assert_eq!(text, format!("post {}", i));
+            assert_eq!(decoder.len(), (num_items - i - 1) as usize);
+        }
+        assert!(decoder.next().is_none());
+        assert_eq!(decoder.into_inner(), b"trailing");
+    }
+
+    #[test]
+    fn test_stream_decode_truncated() {
+        let encoded = vec![1u64, 2, 3].try_to_vec().unwrap();
+        let mut decoder = StreamDecoder::<_, u64>::new(&encoded[..encoded.len() - 1]).unwrap();
+        assert_eq!(decoder.next().unwrap().unwrap(), 1);
+        assert_eq!(decoder.next().unwrap().unwrap(), 2);
+        assert!(decoder.next().unwrap().is_err());
+        assert!(decoder.next().is_none());
+        assert!(StreamDecoder::<_, u64>::new(&[0u8, 0][..]).is_err());
+    }
+}
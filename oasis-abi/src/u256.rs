@@ -0,0 +1,120 @@
+//! A 256-bit unsigned integer for services that need more range than `u128`.
+//!
+//! `U256` is encoded as its 32 little-endian bytes, which is how the IDL type `u256`
+//! is represented on the wire.
+
+use crate::{
+    io::{self, Read, Write},
+    Deserialize, Serialize,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U256(pub primitive_types::U256);
+
+impl U256 {
+    pub fn zero() -> Self {
+        Self(primitive_types::U256::zero())
+    }
+
+    pub fn max_value() -> Self {
+        Self(primitive_types::U256::max_value())
+    }
+}
+
+impl core::ops::Deref for U256 {
+    type Target = primitive_types::U256;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for U256 {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<primitive_types::U256> for U256 {
+    fn from(n: primitive_types::U256) -> Self {
+        Self(n)
+    }
+}
+
+impl From<U256> for primitive_types::U256 {
+    fn from(n: U256) -> Self {
+        n.0
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($uint:ty),+) => {
+        $(
+            impl From<$uint> for U256 {
+                fn from(n: $uint) -> Self {
+                    Self(n.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128);
+
+impl core::fmt::Display for U256 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for U256 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        let mut le_bytes = [0u8; 32];
+        self.0.to_little_endian(&mut le_bytes);
+        writer.write_all(&le_bytes)
+    }
+}
+
+impl Deserialize for U256 {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        let mut le_bytes = [0u8; 32];
+        reader.read_exact(&mut le_bytes)?;
+        Ok(Self(primitive_types::U256::from_little_endian(&le_bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(n: U256) -> Vec<u8> {
+        let encoded = n.try_to_vec().unwrap();
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(U256::try_from_slice(&encoded).unwrap(), n);
+        encoded
+    }
+
+    #[test]
+    fn test_round_trip_boundaries() {
+        assert_eq!(round_trip(U256::zero()), vec![0u8; 32]);
+        assert_eq!(round_trip(U256::max_value()), vec![0xffu8; 32]);
+
+        let mut u128_max = vec![0xffu8; 16];
+        u128_max.resize(32, 0);
+        assert_eq!(round_trip(U256::from(u128::max_value())), u128_max);
+
+        let mut u128_max_plus_one = vec![0u8; 32];
+        u128_max_plus_one[16] = 1;
+        assert_eq!(
+            round_trip(U256(
+                primitive_types::U256::from(u128::max_value()) + primitive_types::U256::one()
+            )),
+            u128_max_plus_one
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        assert!(U256::try_from_slice(&[0u8; 31]).is_err());
+    }
+}
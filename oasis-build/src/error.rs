@@ -22,6 +22,8 @@ pub enum RpcError {
         span: Span,
     },
     CtorIsDefault(Span),
+    CtorIsSkipped(Span),
+    CtorIsView(Span),
     DefaultFnHasArg(Span),
     HasAbi(Span),
     HasAsync(Span),
@@ -31,6 +33,7 @@ pub enum RpcError {
         span: Span,
     },
     MissingSelf(Span),
+    MutView(Span),
     Unsafe(Span),
 }
 
@@ -42,10 +45,12 @@ impl std::fmt::Display for RpcError {
             BadStruct(..) => write!(f, "Service state definition must have named fields."),
             BadCtorReturn { self_ty, .. } => write!(
                 f,
-                "Service constructor must return `Self` (aka `{}`)",
+                "Service constructor must return `Self` (aka `{}`) or `Result<Self, E>`",
                 pprust::ty_to_string(self_ty)
             ),
             CtorIsDefault(..) => write!(f, "Service constructor cannot be the default function."),
+            CtorIsSkipped(..) => write!(f, "Service constructor cannot be skipped."),
+            CtorIsView(..) => write!(f, "Service constructor cannot be a view."),
             DefaultFnHasArg(..) => {
                 write!(f, "Default function cannot take arguments after `Context`.")
             }
@@ -66,6 +71,7 @@ impl std::fmt::Display for RpcError {
                 f,
                 "RPC method must take `&self` or `&mut self` as its first argument."
             ),
+            MutView(..) => write!(f, "View method must take `&self` as its first argument."),
             Unsafe(..) => write!(f, "RPC method cannot be unsafe."),
         }
     }
@@ -79,12 +85,15 @@ impl RpcError {
             | BadStruct(span)
             | BadCtorReturn { span, .. }
             | CtorIsDefault(span)
+            | CtorIsSkipped(span)
+            | CtorIsView(span)
             | DefaultFnHasArg(span)
             | HasAbi(span)
             | HasAsync(span)
             | HasGenerics(span)
             | MissingContext { span, .. }
             | MissingSelf(span)
+            | MutView(span)
             | Unsafe(span) => *span,
         }
     }
@@ -35,7 +35,10 @@ pub fn quote_ty(ty: &oasis_rpc::Type) -> TokenStream {
         Type::String => quote!(String),
         Type::Address => quote!(oasis_std::Address),
         Type::Balance => quote!(oasis_std::Balance),
+        Type::U256 => quote!(oasis_std::abi::U256),
+        Type::I256 => quote!(oasis_std::abi::I256),
         Type::RpcError => quote!(oasis_std::RpcError),
+        Type::Duration => quote!(std::time::Duration),
         Type::Defined { namespace, ty } => {
             let tyq = format_ident!("{}", ty);
             match namespace {
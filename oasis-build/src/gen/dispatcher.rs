@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{convert::TryFrom, path::Path};
 
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -8,7 +8,7 @@ use syntax::ast::{self, Crate, ItemKind, StmtKind};
 
 use crate::{
     format_ident, hash,
-    visitor::parsed_rpc::{ParsedRpc, ParsedRpcKind},
+    visitor::parsed_rpc::{ArgPassing, ParsedRpc, ParsedRpcKind},
     BuildContext,
 };
 
@@ -25,6 +25,7 @@ pub fn insert(build_ctx: &BuildContext, krate: &mut Crate, service_def: &Service
         name: service_name,
         rpcs,
         ctor,
+        invariants,
     } = service_def;
 
     let default_fn = rpcs.iter().find(|rpc| match rpc.kind {
@@ -33,7 +34,8 @@ pub fn insert(build_ctx: &BuildContext, krate: &mut Crate, service_def: &Service
     });
 
     if !rpcs.is_empty() {
-        let rpcs_dispatcher = generate_rpc_dispatcher(*service_name, &rpcs, default_fn);
+        let rpcs_dispatcher =
+            generate_rpc_dispatcher(*service_name, &rpcs, default_fn, &invariants);
         let dispatcher_str = rpcs_dispatcher.to_string();
         let rpcs_include_file = out_dir.join(format!(
             "{}_dispatcher-{:016x}.rs",
@@ -44,7 +46,7 @@ pub fn insert(build_ctx: &BuildContext, krate: &mut Crate, service_def: &Service
         insert_rpc_dispatcher_stub(krate, &rpcs_include_file);
     }
 
-    let ctor_fn = generate_ctor_fn(*service_name, &ctor);
+    let ctor_fn = generate_ctor_fn(*service_name, ctor.as_ref());
     let ctor_fn_str = ctor_fn.to_string();
     let ctor_include_file = out_dir.join(format!(
         "{}_ctor-{:016x}.rs",
@@ -62,13 +64,16 @@ fn generate_rpc_dispatcher(
     service_name: Symbol,
     rpcs: &[ParsedRpc],
     default_fn: Option<&ParsedRpc>,
+    invariants: &[Symbol],
 ) -> TokenStream {
     let service_ident = format_ident!("{}", service_name);
-    let mut any_rpc_returns_result = false;
     let mut rpc_payload_variants = Vec::with_capacity(rpcs.len());
+    let mut mut_rpc_idxs = Vec::new();
+    let mut rpc_selectors = Vec::with_capacity(rpcs.len());
     let rpc_match_arms = rpcs
         .iter()
-        .map(|rpc| {
+        .enumerate()
+        .map(|(rpc_idx, rpc)| {
             let arg_tys: Vec<_> = rpc.arg_types().map(|ty| ty_tokenizable(&ty)).collect();
             let variant_arg_tys = if !arg_tys.is_empty() {
                 quote!(#(#arg_tys),*)
@@ -79,55 +84,150 @@ fn generate_rpc_dispatcher(
             let rpc_name = format_ident!("{}", rpc.name);
             rpc_payload_variants.push(quote!(#rpc_name(#variant_arg_tys)));
 
-            any_rpc_returns_result |= rpc.output.is_result();
-            DispatchArm::new(&service_ident, &rpc)
+            let rpc_name_str = rpc.name.to_string();
+            let rpc_selector = rpc_idx as u32;
+            rpc_selectors.push(quote!((#rpc_name_str, #rpc_selector)));
+
+            if rpc.is_mut() {
+                // The plugin rejects services with more RPCs than a `u8` can select.
+                mut_rpc_idxs.push(u8::try_from(rpc_idx).unwrap());
+            }
+
+            DispatchArm::new(&rpc)
         })
         .collect::<Vec<_>>();
 
-    let default_fn_invocation = if let Some(rpc) = default_fn {
-        let default_dispatch = DispatchArm::new(&service_ident, &rpc).invocation;
+    let payload_dispatch = quote! {
+        let payload: RpcPayload = match Deserialize::try_from_slice(&input) {
+            Ok(payload) => payload,
+            Err(_) => return Err(oasis_std::RpcError::InvalidInput(method)),
+        };
+        match payload {
+            #(#rpc_match_arms)*
+        }
+    };
+
+    let output_dispatch = if let Some(rpc) = default_fn {
+        let default_dispatch = DispatchArm::new(&rpc).body();
         quote! {
             if input.is_empty() {
-                #default_dispatch;
+                #default_dispatch
+            } else {
+                #payload_dispatch
             }
         }
     } else {
-        quote!()
+        payload_dispatch
     };
 
-    let output_err_ty = if any_rpc_returns_result {
-        quote!(Vec<u8>)
-    } else {
-        quote!(())
+    // The payload of an RPC begins with the index of the function.
+    let default_fn_is_mut = default_fn.map(ParsedRpc::is_mut).unwrap_or_default();
+    let rpc_is_mut = quote! {
+        match input.first() {
+            Some(rpc_idx) => [#(#mut_rpc_idxs),*].contains(rpc_idx),
+            None => #default_fn_is_mut,
+        }
     };
 
-    let err_returner = if any_rpc_returns_result {
-        quote!(oasis_std::backend::err(&err_output))
+    // Debug builds, including tests, check the invariants after each successful mutating RPC.
+    let invariant_checks = if invariants.is_empty() {
+        quote!()
     } else {
-        quote!(unreachable!("No RPC function returns Err"))
+        let invariant_idents: Vec<_> = invariants
+            .iter()
+            .map(|name| format_ident!("{}", name))
+            .collect();
+        quote! {
+            if cfg!(debug_assertions) && output.is_ok() && #rpc_is_mut {
+                #(
+                    assert!(
+                        service.#invariant_idents(),
+                        "service invariant `{}` does not hold",
+                        stringify!(#invariant_idents)
+                    );
+                )*
+            }
+        }
     };
 
     quote! {
         #[allow(warnings)]
-        fn _oasis_dispatcher() {
-            use oasis_std::{abi::*, Service as _};
+        impl #service_ident {
+            /// The name of each RPC method and its selector, which is the index
+            /// that begins the payload of an RPC to the method.
+            pub const METHODS: &'static [(&'static str, u32)] = &[#(#rpc_selectors),*];
 
-            #[derive(Deserialize)]
-            enum RpcPayload {
-                #(#rpc_payload_variants),*
+            /// Decodes `payload` and calls the requested RPC on `state`, as the service's
+            /// dispatcher does, so fuzzers may call it with arbitrary bytes.
+            /// It does not persist `state`. It is only available in native builds.
+            #[cfg(not(target_os = "wasi"))]
+            #[doc(hidden)]
+            pub fn __dispatch_raw(
+                state: &mut Self,
+                ctx: &oasis_std::Context,
+                payload: &[u8],
+            ) -> std::result::Result<Vec<u8>, oasis_std::RpcError> {
+                Self::__dispatch(state, ctx, payload)
             }
 
-            let ctx = oasis_std::Context::default(); // TODO(#33)
-            let mut service = <#service_ident>::coalesce();
-            let input = oasis_std::backend::input();
-            #default_fn_invocation
-            let payload: RpcPayload = Deserialize::try_from_slice(&input).unwrap();
-            let output: std::result::Result<Vec<u8>, #output_err_ty> = match payload {
-                #(#rpc_match_arms)*
-            };
-            match output {
+            fn __dispatch(
+                state: &mut Self,
+                ctx: &oasis_std::Context,
+                payload: &[u8],
+            ) -> std::result::Result<Vec<u8>, oasis_std::RpcError> {
+                use oasis_std::{abi::*, EncodeOutput as _, EncodeResultOutput as _};
+
+                #[derive(Deserialize)]
+                enum RpcPayload {
+                    #(#rpc_payload_variants),*
+                }
+
+                let (service, input) = (state, payload);
+                // The selector of the requested RPC, which an `InvalidInput` error reports.
+                let method = input
+                    .first()
+                    .map(|&rpc_idx| u32::from(rpc_idx))
+                    .filter(|&selector| (selector as usize) < Self::METHODS.len());
+                let output: std::result::Result<Vec<u8>, Vec<u8>> = {
+                    #output_dispatch
+                };
+                #invariant_checks
+                output.map_err(oasis_std::RpcError::Execution)
+            }
+
+            /// Calls the RPC requested by the input of the current transaction, as the
+            /// service's `main` export does, but returns the output instead of exiting.
+            /// @see `oasis_test::run_in_transaction`
+            #[doc(hidden)]
+            pub fn __call_raw() -> std::result::Result<Vec<u8>, oasis_std::RpcError> {
+                use oasis_std::Service as _;
+
+                let ctx = oasis_std::Context::default(); // TODO(#33)
+                let mut service = Self::coalesce();
+                let input = oasis_std::backend::input();
+                let output = Self::__dispatch(&mut service, &ctx, &input);
+                if #rpc_is_mut {
+                    Self::sunder(service);
+                }
+                output
+            }
+        }
+
+        #[allow(warnings)]
+        fn _oasis_dispatcher() {
+            use oasis_std::abi::*;
+
+            std::panic::set_hook(Box::new(oasis_std::panic_hook));
+
+            match <#service_ident>::__call_raw() {
                 Ok(output) => oasis_std::backend::ret(&output),
-                Err(err_output) => #err_returner,
+                Err(oasis_std::RpcError::Execution(err_output)) => {
+                    oasis_std::backend::err(&err_output)
+                }
+                Err(oasis_std::RpcError::InvalidInput(method)) => {
+                    oasis_std::backend::invalid_input(method)
+                }
+                Err(err) => oasis_std::backend::err(&err.try_to_vec().unwrap()),
             }
         }
     }
@@ -137,29 +237,37 @@ mod armery {
     use super::*;
 
     pub struct DispatchArm {
-        pub guard: TokenStream,
-        pub invocation: TokenStream,
-        sunderer: Option<TokenStream>,
+        guard: TokenStream,
+        invocation: TokenStream,
     }
 
     impl DispatchArm {
-        pub fn new(service_ident: &syn::Ident, rpc: &ParsedRpc) -> Self {
+        pub fn new(rpc: &ParsedRpc) -> Self {
             let fn_name = format_ident!("{}", rpc.name);
             let arg_names: Vec<_> = rpc
                 .arg_names()
                 .map(|name| format_ident!("{}", name))
                 .collect();
-            let invocation = if rpc.output.is_result() {
-                quote! {
-                    match service.#fn_name(&ctx, #(#arg_names),*) {
-                        Ok(output) => Ok(Serialize::try_to_vec(&output).unwrap()),
-                        Err(err) => Err(Serialize::try_to_vec(&err).unwrap()),
-                    }
-                }
-            } else {
-                quote! {
-                    Ok(service.#fn_name(&ctx, #(#arg_names),*).try_to_vec().unwrap())
-                }
+            let arg_exprs = arg_exprs(rpc);
+            // Floats are canonicalized so that each value has exactly one encoding.
+            // The output is a `Result` only if its resolved type is, regardless of
+            // the name by which it is referred. `encode_output` dispatches on the type.
+            let invocation = quote! {
+                #(
+                    let #arg_names = match oasis_std::abi::float::Canonicalize::canonicalize(
+                        #arg_names
+                    ) {
+                        Ok(arg) => arg,
+                        Err(_) => return Err(oasis_std::RpcError::InvalidInput(method)),
+                    };
+                )*
+                let output = match oasis_std::abi::float::Canonicalize::canonicalize(
+                    service.#fn_name(ctx, #(#arg_exprs),*)
+                ) {
+                    Ok(output) => output,
+                    Err(err) => return Err(err),
+                };
+                (&output).encode_output()
             };
             let variant_args = if !arg_names.is_empty() {
                 quote!(#(#arg_names),*)
@@ -169,49 +277,68 @@ mod armery {
             Self {
                 guard: quote!(RpcPayload::#fn_name(#variant_args)),
                 invocation,
-                sunderer: if rpc.is_mut() {
-                    Some(quote!(<#service_ident>::sunder(service);))
-                } else {
-                    None
-                },
             }
         }
+
+        /// Returns a block that invokes the RPC and evaluates to its encoded output.
+        pub fn body(&self) -> TokenStream {
+            let invocation = &self.invocation;
+            quote!({ #invocation })
+        }
     }
 
     impl quote::ToTokens for DispatchArm {
         fn to_tokens(&self, tokens: &mut TokenStream) {
-            let DispatchArm {
-                guard, invocation, ..
-            } = self;
-            tokens.extend(match &self.sunderer {
-                Some(sunderer) => {
-                    quote! {
-                        #guard => {
-                            let output = #invocation;
-                            #sunderer
-                            output
-                        }
-                    }
-                }
-                None => quote!(#guard => { #invocation }),
-            });
+            let guard = &self.guard;
+            let body = self.body();
+            tokens.extend(quote!(#guard => #body));
         }
     }
 }
 use armery::DispatchArm;
 
-fn generate_ctor_fn(service_name: Symbol, ctor: &ParsedRpc) -> TokenStream {
+/// Returns the expressions that pass the decoded (owned) arguments to `rpc`.
+fn arg_exprs(rpc: &ParsedRpc) -> Vec<TokenStream> {
+    rpc.arg_names()
+        .zip(rpc.arg_passings())
+        .map(|(name, passing)| {
+            let name = format_ident!("{}", name);
+            match passing {
+                ArgPassing::Value => quote!(#name),
+                ArgPassing::Ref => quote!(&#name),
+                ArgPassing::Cow => quote!(std::borrow::Cow::Owned(#name)),
+            }
+        })
+        .collect()
+}
+
+fn generate_ctor_fn(service_name: Symbol, ctor: Option<&ParsedRpc>) -> TokenStream {
     let arg_names: Vec<_> = ctor
-        .arg_names()
+        .into_iter()
+        .flat_map(|ctor| ctor.arg_names())
         .map(|name| format_ident!("{}", name))
         .collect();
-    let arg_tys: Vec<_> = ctor.arg_types().map(|ty| ty_tokenizable(&ty)).collect();
+    let arg_tys: Vec<_> = ctor
+        .into_iter()
+        .flat_map(|ctor| ctor.arg_types())
+        .map(|ty| ty_tokenizable(&ty))
+        .collect();
     let (ctor_struct_args, ctor_payload_unpack) = if !arg_names.is_empty() {
         let struct_args = quote!(#(#arg_tys),*,);
         let payload_unpack = quote! {
             let input = oasis_std::backend::input();
-            let CtorPayload(#(#arg_names),*,) =
-                Deserialize::try_from_slice(&input).unwrap();
+            let CtorPayload(#(#arg_names),*,) = match Deserialize::try_from_slice(&input) {
+                Ok(payload) => payload,
+                Err(_) => return Err(oasis_std::RpcError::InvalidInput(None)),
+            };
+            #(
+                let #arg_names = match oasis_std::abi::float::Canonicalize::canonicalize(
+                    #arg_names
+                ) {
+                    Ok(arg) => arg,
+                    Err(err) => return Err(err),
+                };
+            )*
         };
         (struct_args, payload_unpack)
     } else {
@@ -220,35 +347,66 @@ fn generate_ctor_fn(service_name: Symbol, ctor: &ParsedRpc) -> TokenStream {
 
     let service_ident = format_ident!("{}", service_name);
 
-    let ctor_stmt = if ctor.output.is_result() {
-        quote! {
-            match <#service_ident>::new(&ctx, #(#arg_names),*) {
-                Ok(service) => service,
-                Err(err) => {
-                    oasis_std::backend::err(&format!("{:#?}", err).into_bytes());
-                    return 1;
-                }
-            }
-        }
+    let ctor_call = if let Some(ctor) = ctor {
+        let arg_exprs = arg_exprs(ctor);
+        quote!(<#service_ident>::new(&ctx, #(#arg_exprs),*))
     } else {
-        quote! { <#service_ident>::new(&ctx, #(#arg_names),*) }
+        quote!(<#service_ident as std::default::Default>::default())
+    };
+
+    // The ctor returns either `Self` or some `Result` of `Self`.
+    let ctor_stmt = quote! {
+        match oasis_std::CtorOutput::into_ctor_result(#ctor_call) {
+            Ok(service) => service,
+            Err(err) => return Err(oasis_std::RpcError::Execution(err)),
+        }
     };
 
     quote! {
+        #[allow(warnings)]
+        impl #service_ident {
+            /// Constructs the service from the input of the current transaction and
+            /// stores it, as the service's `_oasis_deploy` export does, but returns the
+            /// constructor's error instead of exiting. @see `oasis_test::run_in_transaction`
+            #[doc(hidden)]
+            pub fn __deploy_raw() -> std::result::Result<(), oasis_std::RpcError> {
+                use oasis_std::{abi::*, Service as _};
+
+                #[derive(Deserialize)]
+                #[allow(non_camel_case_types)]
+                struct CtorPayload(#ctor_struct_args);
+
+                impl oasis_std::CtorOutput<#service_ident> for #service_ident {
+                    fn into_ctor_result(self) -> std::result::Result<Self, Vec<u8>> {
+                        Ok(self)
+                    }
+                }
+
+                let ctx = oasis_std::Context::default(); // TODO(#33)
+                #ctor_payload_unpack
+                let mut service = #ctor_stmt;
+                <#service_ident>::sunder(service);
+                Ok(())
+            }
+        }
+
         #[allow(warnings)]
         #[no_mangle]
         extern "C" fn _oasis_deploy() -> u8 {
-            use oasis_std::{abi::*, Service as _};
+            use oasis_std::abi::*;
 
-            #[derive(Deserialize)]
-            #[allow(non_camel_case_types)]
-            struct CtorPayload(#ctor_struct_args);
+            std::panic::set_hook(Box::new(oasis_std::panic_hook));
 
-            let ctx = oasis_std::Context::default(); // TODO(#33)
-            #ctor_payload_unpack
-            let mut service = #ctor_stmt;
-            <#service_ident>::sunder(service);
-            return 0;
+            match <#service_ident>::__deploy_raw() {
+                Ok(()) => 0,
+                Err(oasis_std::RpcError::Execution(err_output)) => {
+                    oasis_std::backend::err(&err_output)
+                }
+                Err(oasis_std::RpcError::InvalidInput(method)) => {
+                    oasis_std::backend::invalid_input(method)
+                }
+                Err(err) => oasis_std::backend::err(&err.try_to_vec().unwrap()),
+            }
         }
     }
 }
@@ -3,7 +3,7 @@ use std::{path::Path, str::FromStr as _};
 use colored::*;
 use heck::{CamelCase as _, SnakeCase as _};
 use oasis_rpc::import::{resolve_imports, ImportLocation, ImportedService};
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
 
 use crate::{format_ident, hash};
@@ -97,6 +97,24 @@ pub fn build(
     Ok(import)
 }
 
+/// Checks that the `version` of the imported service `name` satisfies the
+/// Cargo-style `version_req` (e.g., `^1.2`) declared by the importer.
+pub fn check_version(name: &str, version: &str, version_req: &str) -> anyhow::Result<()> {
+    let req = semver::VersionReq::parse(version_req)
+        .map_err(|e| anyhow::format_err!("invalid version requirement for `{}`: {}", name, e))?;
+    let semver = semver::Version::parse(version)
+        .map_err(|e| anyhow::format_err!("invalid version of `{}`: {}", name, e))?;
+    if !req.matches(&semver) {
+        anyhow::bail!(
+            "imported `{}` v{} does not satisfy the required version `{}`",
+            name,
+            version,
+            version_req
+        );
+    }
+    Ok(())
+}
+
 fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = TokenStream> + 'a {
     defs.iter().map(|def| {
         let name = format_ident!("{}", def.name());
@@ -108,13 +126,23 @@ fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = Token
             quote!()
         };
         let derives = quote!(Serialize, Deserialize, Debug, Clone, PartialEq, #hash_derive);
+        // A field that directly contains the type being defined is boxed so that the type
+        // has a finite size. The box does not change the encoding of the field.
+        let quote_field_ty = |ty: &oasis_rpc::Type| {
+            let tyq = quote_ty(ty);
+            if is_directly_recursive(ty, def.name()) {
+                quote!(Box<#tyq>)
+            } else {
+                tyq
+            }
+        };
         match def {
             oasis_rpc::TypeDef::Struct { fields, .. } => {
                 let is_newtype = fields
                     .iter()
                     .enumerate()
                     .all(|(i, f)| usize::from_str(&f.name) == Ok(i));
-                let tys = fields.iter().map(|f| quote_ty(&f.ty));
+                let tys = fields.iter().map(|f| quote_field_ty(&f.ty));
                 if is_newtype {
                     quote! {
                         #[derive(#derives)]
@@ -131,12 +159,24 @@ fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = Token
                 }
             }
             oasis_rpc::TypeDef::Enum { variants, .. } => {
-                let variants = variants.iter().map(|v| {
+                let is_tagged = variants.iter().any(|v| v.tag.is_some());
+                let derives = if is_tagged {
+                    quote!(TaggedEnum, Debug, Clone, PartialEq, #hash_derive)
+                } else {
+                    derives
+                };
+                let variants = variants.iter().enumerate().map(|(i, v)| {
                     let name = format_ident!("{}", v.name);
-                    match &v.fields {
+                    let tag_attr = if is_tagged {
+                        let tag = Literal::u8_unsuffixed(v.tag.unwrap_or(i as u8));
+                        quote!(#[oasis(tag = #tag)])
+                    } else {
+                        quote!()
+                    };
+                    let variant = match &v.fields {
                         Some(oasis_rpc::EnumFields::Named(fields)) => {
                             let field_names = fields.iter().map(|f| format_ident!("{}", f.name));
-                            let tys = fields.iter().map(|f| quote_ty(&f.ty));
+                            let tys = fields.iter().map(|f| quote_field_ty(&f.ty));
                             quote! {
                                 #name {
                                     #(#field_names: #tys),*
@@ -144,11 +184,12 @@ fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = Token
                             }
                         }
                         Some(oasis_rpc::EnumFields::Tuple(tys)) => {
-                            let tys = tys.iter().map(quote_ty);
+                            let tys = tys.iter().map(quote_field_ty);
                             quote!(#name(#(#tys),*))
                         }
                         None => quote!(#name),
-                    }
+                    };
+                    quote!(#tag_attr #variant)
                 });
                 quote! {
                     #[derive(#derives)]
@@ -162,7 +203,7 @@ fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = Token
                 ..
             } => {
                 let field_names = indexed_fields.iter().map(|f| format_ident!("{}", f.name));
-                let tys = indexed_fields.iter().map(|f| quote_ty(&f.ty));
+                let tys = indexed_fields.iter().map(|f| quote_field_ty(&f.ty));
                 let indexeds = indexed_fields.iter().map(|f| {
                     if f.indexed {
                         quote!(#[indexed])
@@ -181,6 +222,24 @@ fn gen_def_tys<'a>(defs: &'a [oasis_rpc::TypeDef]) -> impl Iterator<Item = Token
     })
 }
 
+/// Returns whether `ty` contains the type def named `def_name` other than through a
+/// collection, which would already provide indirection.
+fn is_directly_recursive(ty: &oasis_rpc::Type, def_name: &str) -> bool {
+    use oasis_rpc::Type;
+    match ty {
+        Type::Defined {
+            namespace: None,
+            ty,
+        } => ty == def_name,
+        Type::Tuple(tys) => tys.iter().any(|ty| is_directly_recursive(ty, def_name)),
+        Type::Array(ty, _) | Type::Optional(ty) => is_directly_recursive(ty, def_name),
+        Type::Result(ok_ty, err_ty) => {
+            is_directly_recursive(ok_ty, def_name) || is_directly_recursive(err_ty, def_name)
+        }
+        _ => false,
+    }
+}
+
 fn gen_client(service: &ImportedService) -> TokenStream {
     let ImportedService {
         interface,
@@ -189,9 +248,11 @@ fn gen_client(service: &ImportedService) -> TokenStream {
 
     let client_ident = format_ident!("{}Client", sanitize_ident(&interface.name).to_camel_case());
 
-    let rpcs = gen_rpcs(&interface.functions).collect::<Vec<_>>();
-
-    let service_bytecode = quote!(&[#(#bytecode),*]); // TODO(#247)
+    let rpcs = gen_rpcs(
+        &interface.functions,
+        interface.default_function.as_ref().map(String::as_str),
+    )
+    .collect::<Vec<_>>();
 
     let (ctor_arg_names, ctor_arg_tys): (Vec<Ident>, Vec<TokenStream>) = interface
         .constructor
@@ -200,7 +261,76 @@ fn gen_client(service: &ImportedService) -> TokenStream {
         .map(|field| (format_ident!("{}", field.name), quote_borrow(&field.ty)))
         .unzip();
 
+    // A constructor error is returned as `Ok(Err(error))`, like the error of an RPC.
+    let (deploy_output_ty, deployed_client, ctor_err_arm) = match &interface.constructor.error {
+        Some(err_ty) => {
+            let quot_err_ty = quote_ty(err_ty);
+            (
+                quote!(Result<Self, #quot_err_ty>),
+                quote!(Ok(client)),
+                quote! {
+                    Err(oasis_std::RpcError::Execution(err_output)) => {
+                        Ok(Err(<#quot_err_ty>::try_from_slice(&err_output)
+                            .map_err(|_| oasis_std::RpcError::Execution(err_output))?))
+                    }
+                },
+            )
+        }
+        None => (quote!(Self), quote!(client), quote!()),
+    };
+
+    // An interface imported without bytecode (e.g., from JSON) describes a service that
+    // can be called but not deployed.
+    let (service_bytecode, wasi_deploy, native_deploy) = if bytecode.is_empty() {
+        (quote!(), quote!(), quote!())
+    } else {
+        (
+            quote!(static SERVICE_BYTECODE: &[u8] = &[#(#bytecode),*];), // TODO(#247)
+            quote! {
+                pub fn deploy(
+                    ctx: &Context,
+                    #(#ctor_arg_names: #ctor_arg_tys),*
+                ) -> Result<#deploy_output_ty, RpcError> {
+                    let mut initcode = SERVICE_BYTECODE.to_vec();
+                    abi_encode!(#(#ctor_arg_names),* => &mut initcode)?;
+                    match oasis_std::backend::create(ctx.value(), &initcode) {
+                        Ok(address) => {
+                            let client = Self { address };
+                            Ok(#deployed_client)
+                        }
+                        #ctor_err_arm
+                        Err(e) => Err(e),
+                    }
+                }
+            },
+            quote! {
+                pub fn deploy(
+                    gateway: &'a dyn Gateway,
+                    ctx: &Context,
+                    #(#ctor_arg_names: #ctor_arg_tys),*
+                ) -> Result<#deploy_output_ty, RpcError> {
+                    let mut initcode = SERVICE_BYTECODE.to_vec();
+                    abi_encode!(#(#ctor_arg_names),* => &mut initcode)?;
+                    let options = CallOptions {
+                        gas: ctx.gas,
+                        value: ctx.value,
+                    };
+                    match gateway.deploy(&initcode, options) {
+                        Ok(address) => {
+                            let client = Self { address, gateway };
+                            Ok(#deployed_client)
+                        }
+                        #ctor_err_arm
+                        Err(e) => Err(e),
+                    }
+                }
+            },
+        )
+    };
+
     quote! {
+        #service_bytecode
+
         #[cfg(target_os = "wasi")]
         mod client {
             use super::*;
@@ -216,10 +346,21 @@ fn gen_client(service: &ImportedService) -> TokenStream {
                     }
                 }
 
+                pub fn address(&self) -> Address {
+                    self.address
+                }
+
+                #wasi_deploy
+
                 fn rpc(&self, ctx: &Context, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
                     self.address.call(ctx, payload)
                 }
 
+                fn query(&self, ctx: &Context, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+                    // A service calls other services within its own transaction.
+                    self.rpc(ctx, payload)
+                }
+
                 #(#rpcs)*
             }
         }
@@ -228,15 +369,13 @@ fn gen_client(service: &ImportedService) -> TokenStream {
         mod client {
             use super::*;
 
-            use oasis_std::reexports::oasis_client::gateway::Gateway;
+            use oasis_std::reexports::oasis_client::gateway::{CallOptions, Gateway};
 
             pub struct #client_ident<'a> {
                 address: Address,
                 gateway: &'a dyn Gateway,
             }
 
-            static SERVICE_BYTECODE: &[u8] = #service_bytecode;
-
             impl<'a> #client_ident<'a> {
                 pub fn new(gateway: &'a dyn Gateway, address: Address) -> Self {
                     Self {
@@ -245,23 +384,20 @@ fn gen_client(service: &ImportedService) -> TokenStream {
                     }
                 }
 
-                pub fn deploy(
-                    gateway: &'a dyn Gateway,
-                    ctx: &Context,
-                    #(#ctor_arg_names: #ctor_arg_tys),*
-                ) -> Result<Self, RpcError> {
-                    let mut initcode = SERVICE_BYTECODE.to_vec();
-                    abi_encode!(#(#ctor_arg_names),* => &mut initcode)?;
-                    Ok(Self {
-                        address: gateway.deploy(&initcode)?,
-                        gateway,
-                    })
+                pub fn address(&self) -> Address {
+                    self.address
                 }
 
+                #native_deploy
+
                 fn rpc(&self, ctx: &Context, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
                     self.gateway.rpc(self.address, payload)
                 }
 
+                fn query(&self, ctx: &Context, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+                    self.gateway.query(self.address, payload)
+                }
+
                 #(#rpcs)*
             }
         }
@@ -270,13 +406,17 @@ fn gen_client(service: &ImportedService) -> TokenStream {
     }
 }
 
-fn gen_rpcs<'a>(functions: &'a [oasis_rpc::Function]) -> impl Iterator<Item = TokenStream> + 'a {
-    functions.iter().enumerate().map(|(func_idx, func)| {
+fn gen_rpcs<'a>(
+    functions: &'a [oasis_rpc::Function],
+    default_function: Option<&'a str>,
+) -> impl Iterator<Item = TokenStream> + 'a {
+    functions.iter().enumerate().map(move |(func_idx, func)| {
         let fn_name = format_ident!("{}", func.name);
 
-        let self_ref = match func.mutability {
-            oasis_rpc::StateMutability::Immutable => quote! { &self },
-            oasis_rpc::StateMutability::Mutable => quote! { &mut self },
+        // View (`&self`) methods are sent as read-only queries.
+        let (self_ref, call) = match func.mutability {
+            oasis_rpc::StateMutability::Immutable => (quote! { &self }, quote!(query)),
+            oasis_rpc::StateMutability::Mutable => (quote! { &mut self }, quote!(rpc)),
         };
 
         let (arg_names, arg_tys): (Vec<Ident>, Vec<TokenStream>) = func
@@ -294,9 +434,10 @@ fn gen_rpcs<'a>(functions: &'a [oasis_rpc::Function]) -> impl Iterator<Item = To
                     Ok(<#quot_ok_ty>::try_from_slice(&output)
                         .map_err(|_| oasis_std::RpcError::InvalidOutput(output))?)
                 };
+                // An error output that is not an `E`, e.g., a panic message, is passed through.
                 let err_deserializer = quote! {
                     Err(<#quot_err_ty>::try_from_slice(&err_output)
-                        .map_err(|_| oasis_std::RpcError::InvalidOutput(err_output))?)
+                        .map_err(|_| oasis_std::RpcError::Execution(err_output))?)
                 };
                 (output_deserializer, err_deserializer)
             }
@@ -317,14 +458,21 @@ fn gen_rpcs<'a>(functions: &'a [oasis_rpc::Function]) -> impl Iterator<Item = To
             ),
         };
 
+        let payload = if default_function == Some(func.name.as_str()) {
+            // The default function takes no args and is called by sending empty input.
+            quote!(Vec::new())
+        } else {
+            quote!(abi_encode!(#func_idx as u8, #(#arg_names),*).unwrap())
+        };
+
         quote! {
             pub fn #fn_name(
                 #self_ref,
                 ctx: &oasis_std::Context,
                 #(#arg_names: #arg_tys),*
            ) -> Result<#output_ty, oasis_std::RpcError> {
-                let payload = abi_encode!(#func_idx as u8, #(#arg_names),*).unwrap();
-                match self.rpc(ctx, &payload) {
+                let payload = #payload;
+                match self.#call(ctx, &payload) {
                     Ok(output) => {
                         Ok(#output_deserializer)
                     }
@@ -383,4 +531,40 @@ mod tests {
         assert_eq!(&sanitize_ident("˙´¬¬ø ∑ø®¬∂"), "");
         assert_eq!(&sanitize_ident(" \n\t\r"), "");
     }
+
+    #[test]
+    fn test_check_version() {
+        assert!(check_version("b", "1.4.0", "^1.2").is_ok());
+        assert!(check_version("b", "1.2.0", "1.2").is_ok());
+        assert_eq!(
+            check_version("b", "2.0.0", "^1.2").unwrap_err().to_string(),
+            "imported `b` v2.0.0 does not satisfy the required version `^1.2`"
+        );
+        assert!(check_version("b", "1.1.9", "^1.2").is_err());
+        assert!(check_version("b", "1.2.0", "not a req").is_err());
+    }
+
+    #[test]
+    fn test_is_directly_recursive() {
+        use oasis_rpc::Type;
+        let tree = || Type::Defined {
+            namespace: None,
+            ty: "Tree".to_string(),
+        };
+        assert!(is_directly_recursive(&tree(), "Tree"));
+        assert!(is_directly_recursive(&Type::Optional(box tree()), "Tree"));
+        assert!(is_directly_recursive(
+            &Type::Tuple(vec![Type::U64, tree()]),
+            "Tree"
+        ));
+        assert!(!is_directly_recursive(&Type::List(box tree()), "Tree"));
+        assert!(!is_directly_recursive(&tree(), "Forest"));
+        assert!(!is_directly_recursive(
+            &Type::Defined {
+                namespace: Some("other".to_string()),
+                ty: "Tree".to_string(),
+            },
+            "Tree"
+        ));
+    }
 }
@@ -4,8 +4,11 @@ pub mod imports;
 
 pub struct ServiceDefinition {
     pub name: rustc_span::symbol::Symbol,
-    pub ctor: crate::visitor::parsed_rpc::ParsedRpc,
+    /// The service's `new` function, or `None` if the service is constructed using `Default`.
+    pub ctor: Option<crate::visitor::parsed_rpc::ParsedRpc>,
     pub rpcs: Vec<crate::visitor::parsed_rpc::ParsedRpc>,
+    /// The `#[invariant]` methods, which take `&self` and return `bool`.
+    pub invariants: Vec<rustc_span::symbol::Symbol>,
 }
 
 pub fn insert_oasis_bindings(
@@ -15,12 +15,18 @@ extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_hir;
 extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_session;
 extern crate rustc_span;
 extern crate rustc_target;
+extern crate rustc_typeck;
 extern crate syntax;
+#[macro_use]
+extern crate log;
 
 mod error;
 mod gen;
+mod lint;
 mod plugin;
 mod rpc;
 mod utils;
@@ -0,0 +1,88 @@
+use std::sync::{Arc, Mutex};
+
+use rustc_lint::{EarlyContext, EarlyLintPass, LintContext as _};
+use rustc_session::{declare_lint, impl_lint_pass};
+use rustc_span::symbol::Symbol;
+use syntax::ast;
+
+declare_lint! {
+    pub NON_PUB_RPC,
+    Warn,
+    "service methods that take a `Context` but are not `pub`, and so are not RPC methods"
+}
+
+/// Warns about methods of the service that look like RPC methods but, lacking `pub`,
+/// will not be exposed by the service.
+///
+/// The lint is registered only by oasis-build, so plain `rustc` and clippy (e.g., with
+/// `-D warnings`) reject `#[allow(non_pub_rpc)]` as an unknown lint. A crate that is also
+/// built without oasis-build should use `#[allow(unknown_lints, non_pub_rpc)]`.
+///
+/// The lint pass runs after expansion, by which time the `oasis_std::service!` invocation
+/// is gone, so the name of the service is shared by the `BuildPlugin` that finds it.
+pub struct NonPubRpc {
+    service_name: Arc<Mutex<Option<Symbol>>>,
+}
+
+impl_lint_pass!(NonPubRpc => [NON_PUB_RPC]);
+
+impl NonPubRpc {
+    pub fn new(service_name: Arc<Mutex<Option<Symbol>>>) -> Self {
+        Self { service_name }
+    }
+}
+
+impl EarlyLintPass for NonPubRpc {
+    fn check_item(&mut self, cx: &EarlyContext, item: &ast::Item) {
+        let service_name = match *self.service_name.lock().unwrap() {
+            Some(service_name) => service_name,
+            None => return,
+        };
+        let impl_items = match &item.kind {
+            ast::ItemKind::Impl {
+                of_trait: None,
+                self_ty,
+                items,
+                ..
+            } if match &self_ty.kind {
+                ast::TyKind::Path(_, p) => *p == service_name,
+                _ => false,
+            } =>
+            {
+                items
+            }
+            _ => return,
+        };
+        for impl_item in impl_items {
+            if let ast::VisibilityKind::Public = impl_item.vis.node {
+                continue;
+            }
+            if impl_item.ident.name == Symbol::intern("new") {
+                continue; // The constructor need not be `pub`.
+            }
+            let msig = match &impl_item.kind {
+                ast::AssocItemKind::Fn(msig, _) => msig,
+                _ => continue,
+            };
+            let mut args = msig.decl.inputs.iter();
+            let takes_self_and_ctx = args
+                .next()
+                .map(|arg| crate::utils::is_self_ref(&arg.ty))
+                .unwrap_or_default()
+                && args
+                    .next()
+                    .map(|arg| crate::utils::is_context_ref(&arg.ty))
+                    .unwrap_or_default();
+            if !takes_self_and_ctx {
+                continue;
+            }
+            cx.struct_span_lint(NON_PUB_RPC, impl_item.ident.span, |lint| {
+                lint.build(&format!(
+                    "`{}::{}` takes a `Context` but is not an RPC method because it is not `pub`.",
+                    service_name, impl_item.ident
+                ))
+                .emit()
+            });
+        }
+    }
+}
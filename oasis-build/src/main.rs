@@ -18,6 +18,8 @@ use rustc::util::common::ErrorReported;
 
 fn main() {
     rustc_driver::init_rustc_env_logger();
+    // `RUST_LOG=oasis_build=debug` logs the visited items and the generated interface.
+    env_logger::init();
     let outcome = rustc_driver::catch_fatal_errors(move || {
         let mut args: Vec<String> = std::env::args().collect();
         if args.len() <= 1 {
@@ -42,6 +44,15 @@ fn main() {
         let is_wasi = get_arg("--target", &args).map(String::as_str) == Some("wasm32-wasi");
         let is_service = is_wasi && is_bin && !is_nonprimary_bin;
         let is_test = args.iter().any(|arg| arg == "--test");
+        // The test harness of a service is built from the service's own source file.
+        let is_service_test = is_test
+            && !is_nonprimary_bin
+            && args.iter().any(|arg| {
+                let path = std::path::Path::new(arg);
+                path.extension() == Some("rs".as_ref())
+                    && (path.ends_with("src/main.rs")
+                        || path.parent().and_then(|p| p.file_name()) == Some("bin".as_ref()))
+            });
         let is_app = !is_wasi && is_bin && !is_nonprimary_bin;
         let is_compiletest = args
             .iter()
@@ -69,53 +80,61 @@ fn main() {
             manifest_path.push("Cargo.toml");
             let oasis_deps = OasisDependencies::load(&manifest_path).map_err(report_err)?;
 
-            let get_oasis_deps = |crate_name: &str| -> Vec<(String, ImportLocation)> {
+            let root_deps = if is_test {
+                oasis_deps.dev_dependencies.clone()
+            } else {
                 oasis_deps
                     .service_configs
                     .get(crate_name)
-                    .map(|cfg| cfg.dependencies.clone().into_iter().collect())
+                    .map(|cfg| cfg.dependencies.clone())
                     .unwrap_or_default()
             };
 
-            let mut dep_stack: Vec<(String, ImportLocation)> = if is_test {
-                oasis_deps.dev_dependencies.clone().into_iter().collect()
-            } else {
-                get_oasis_deps(&crate_name)
-            };
-
-            let mut preorder_deps = Vec::new();
-            while let Some(dep) = dep_stack.pop() {
-                let (dep_name, _dep_loc) = &dep;
-                if preorder_deps.iter().any(|(name, _)| name == dep_name) {
-                    continue;
-                }
-
-                dep_stack.append(
-                    &mut oasis_deps
-                        .service_configs
-                        .get(dep_name)
-                        .map(|cfg| cfg.dependencies.clone().into_iter().collect())
-                        .unwrap_or_default(),
-                );
-
-                preorder_deps.push(dep);
-            }
-
             let rustc_args = collect_import_rustc_args(&args);
-            let mut externs = Vec::with_capacity(preorder_deps.len() * 2);
-            for dep in preorder_deps.into_iter().rev() {
-                let import = oasis_build::imports::build(
-                    dep,
-                    &gen_dir,
-                    &out_dir,
-                    rustc_args.iter().chain(externs.iter()).cloned().collect(),
-                )
-                .map_err(report_err)?;
-
-                externs.push("--extern".to_string());
-                externs.push(format!("{}={}", import.name, import.lib_path.display()));
-
-                import_semvers.push((import.name, import.version));
+            let mut externs = Vec::new();
+            let import_levels = oasis_deps.import_levels(root_deps).map_err(report_err)?;
+            for level in import_levels {
+                // Imports in the same level do not depend on one another,
+                // so they can be built concurrently.
+                let level_args: Vec<String> =
+                    rustc_args.iter().chain(externs.iter()).cloned().collect();
+                let (builders, version_reqs): (Vec<_>, Vec<_>) = level
+                    .into_iter()
+                    .map(|(dep_name, dep)| {
+                        let gen_dir = gen_dir.clone();
+                        let out_dir = out_dir.clone();
+                        let rustc_args = level_args.clone();
+                        let builder = std::thread::spawn(move || {
+                            oasis_build::imports::build(
+                                (dep_name, dep.location),
+                                &gen_dir,
+                                &out_dir,
+                                rustc_args,
+                            )
+                        });
+                        (builder, dep.version)
+                    })
+                    .unzip();
+                let imports: Vec<_> = builders
+                    .into_iter()
+                    .map(|builder| builder.join().map_err(|_| ErrorReported))
+                    .collect();
+                for (import, version_req) in imports.into_iter().zip(version_reqs) {
+                    let import = import?.map_err(report_err)?;
+                    if let Some(version_req) = &version_req {
+                        oasis_build::imports::check_version(
+                            &import.name,
+                            &import.version,
+                            version_req,
+                        )
+                        .map_err(report_err)?;
+                    }
+
+                    externs.push("--extern".to_string());
+                    externs.push(format!("{}={}", import.name, import.lib_path.display()));
+
+                    import_semvers.push((import.name, (import.version, version_req)));
+                }
             }
 
             args.append(&mut externs);
@@ -131,11 +150,13 @@ fn main() {
 
         let mut idl8r = oasis_build::BuildPlugin::new(build_target, import_semvers);
         let mut default_cbs = DefaultCallbacks;
-        let callbacks: &mut (dyn rustc_driver::Callbacks + Send) = if is_service || is_compiletest {
-            &mut idl8r
-        } else {
-            &mut default_cbs
-        };
+        // A service's tests are built with its dispatcher so that they can call `__dispatch_raw`.
+        let callbacks: &mut (dyn rustc_driver::Callbacks + Send) =
+            if is_service || is_service_test || is_compiletest {
+                &mut idl8r
+            } else {
+                &mut default_cbs
+            };
         rustc_driver::run_compiler(&args, callbacks, None, None)?;
 
         if !is_service {
@@ -240,9 +261,85 @@ impl OasisDependencies {
             None => Ok(Self::default()),
         }
     }
+
+    /// Returns the transitive imports of `roots` grouped into levels such that each
+    /// import depends only on imports in preceding levels. Each level is sorted by
+    /// import name so that the build order is stable.
+    /// Returns an error naming the participants of any import cycle.
+    fn import_levels(&self, roots: Dependencies) -> anyhow::Result<Vec<Vec<(String, Dependency)>>> {
+        let mut imports = Dependencies::new();
+        let mut dep_stack: Vec<(String, Dependency)> = roots.into_iter().collect();
+        while let Some((dep_name, dep)) = dep_stack.pop() {
+            if imports.contains_key(&dep_name) {
+                continue;
+            }
+            dep_stack.extend(
+                self.service_deps(&dep_name)
+                    .map(|(name, dep)| (name.clone(), dep.clone())),
+            );
+            imports.insert(dep_name, dep);
+        }
+
+        let mut import_levels = BTreeMap::new();
+        let mut levels: Vec<Vec<(String, Dependency)>> = Vec::new();
+        for (name, dep) in imports {
+            let level = self.import_level(&name, &mut import_levels, &mut Vec::new())?;
+            if levels.len() <= level {
+                levels.resize_with(level + 1, Vec::new);
+            }
+            levels[level].push((name, dep));
+        }
+        Ok(levels)
+    }
+
+    /// Returns the length of the longest chain of imports starting at `name`.
+    /// `import_path` holds the imports currently being visited and is used to detect cycles.
+    fn import_level(
+        &self,
+        name: &str,
+        import_levels: &mut BTreeMap<String, usize>,
+        import_path: &mut Vec<String>,
+    ) -> anyhow::Result<usize> {
+        if let Some(level) = import_levels.get(name) {
+            return Ok(*level);
+        }
+        if let Some(cycle_start) = import_path.iter().position(|n| n == name) {
+            let mut cycle = import_path[cycle_start..].to_vec();
+            cycle.push(name.to_string());
+            anyhow::bail!("import cycle: {}", cycle.join(" -> "));
+        }
+        import_path.push(name.to_string());
+        let mut level = 0;
+        for (dep_name, _) in self.service_deps(name) {
+            level = level.max(self.import_level(dep_name, import_levels, import_path)? + 1);
+        }
+        import_path.pop();
+        import_levels.insert(name.to_string(), level);
+        Ok(level)
+    }
+
+    fn service_deps<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = (&'a String, &'a Dependency)> {
+        self.service_configs
+            .get(name)
+            .into_iter()
+            .flat_map(|cfg| cfg.dependencies.iter())
+    }
 }
 
-type Dependencies = BTreeMap<String, ImportLocation>;
+type Dependencies = BTreeMap<String, Dependency>;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct Dependency {
+    #[serde(flatten)]
+    location: ImportLocation,
+
+    /// A Cargo-style requirement (e.g., `^1.2`) on the version of the imported service.
+    #[serde(default)]
+    version: Option<String>,
+}
 
 fn pack_iface_into_wasm(
     iface: &oasis_rpc::Interface,
@@ -256,3 +353,129 @@ fn pack_iface_into_wasm(
     module.emit_wasm_file(wasm_path).unwrap();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level_names(levels: &[Vec<(String, Dependency)>]) -> Vec<Vec<&str>> {
+        levels
+            .iter()
+            .map(|level| level.iter().map(|(name, _)| name.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_import_levels_diamond() {
+        // top -> {left, right} -> bottom
+        let oasis_deps: OasisDependencies = toml::from_str(
+            r#"
+            [dev-dependencies]
+            top = { path = "top.wasm" }
+
+            [top.dependencies]
+            right = { path = "right.wasm" }
+            left = { path = "left.wasm" }
+
+            [left.dependencies]
+            bottom = { path = "bottom.wasm" }
+
+            [right.dependencies]
+            bottom = { path = "bottom.wasm" }
+            "#,
+        )
+        .unwrap();
+
+        let levels = oasis_deps
+            .import_levels(oasis_deps.dev_dependencies.clone())
+            .unwrap();
+        assert_eq!(
+            level_names(&levels),
+            vec![vec!["bottom"], vec!["left", "right"], vec!["top"]]
+        );
+        assert_eq!(
+            levels[0][0].1.location,
+            ImportLocation::Path(PathBuf::from("bottom.wasm"))
+        );
+
+        // The order does not depend on where the traversal starts.
+        let roots = oasis_deps.service_configs["top"].dependencies.clone();
+        assert_eq!(
+            level_names(&oasis_deps.import_levels(roots).unwrap()),
+            vec![vec!["bottom"], vec!["left", "right"]]
+        );
+    }
+
+    #[test]
+    fn test_import_levels_independent() {
+        let oasis_deps: OasisDependencies = toml::from_str(
+            r#"
+            [dev-dependencies]
+            b = { path = "b.wasm" }
+            c = { path = "c.wasm" }
+            a = { path = "a.wasm" }
+            "#,
+        )
+        .unwrap();
+        let levels = oasis_deps
+            .import_levels(oasis_deps.dev_dependencies.clone())
+            .unwrap();
+        assert_eq!(level_names(&levels), vec![vec!["a", "b", "c"]]);
+        assert!(OasisDependencies::default()
+            .import_levels(Dependencies::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dependency_version() {
+        let oasis_deps: OasisDependencies = toml::from_str(
+            r#"
+            [dev-dependencies]
+            a = { path = "a.wasm", version = "^1.2" }
+            b = { url = "file:///b.wasm" }
+            "#,
+        )
+        .unwrap();
+        let a = &oasis_deps.dev_dependencies["a"];
+        assert_eq!(a.location, ImportLocation::Path(PathBuf::from("a.wasm")));
+        assert_eq!(a.version.as_ref().map(String::as_str), Some("^1.2"));
+        assert_eq!(oasis_deps.dev_dependencies["b"].version, None);
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let oasis_deps: OasisDependencies = toml::from_str(
+            r#"
+            [dev-dependencies]
+            a = { path = "a.wasm" }
+
+            [a.dependencies]
+            b = { path = "b.wasm" }
+
+            [b.dependencies]
+            a = { path = "a.wasm" }
+            "#,
+        )
+        .unwrap();
+        let err = oasis_deps
+            .import_levels(oasis_deps.dev_dependencies.clone())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "import cycle: a -> b -> a");
+    }
+
+    #[test]
+    fn test_import_self_cycle() {
+        let oasis_deps: OasisDependencies = toml::from_str(
+            r#"
+            [a.dependencies]
+            a = { path = "a.wasm" }
+            "#,
+        )
+        .unwrap();
+        let err = oasis_deps
+            .import_levels(oasis_deps.service_configs["a"].dependencies.clone())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "import cycle: a -> a");
+    }
+}
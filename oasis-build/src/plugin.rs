@@ -1,9 +1,16 @@
-use std::collections::BTreeSet; // BTree for reproducibility
+// BTree for reproducibility
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
 
 use rustc_data_structures::{fx::FxHashMap, sync::Once};
 use rustc_driver::Compilation;
 use rustc_hir::intravisit::Visitor;
-use rustc_interface::{interface::Compiler, Queries};
+use rustc_interface::{
+    interface::{Compiler, Config},
+    Queries,
+};
 use rustc_span::symbol::Symbol;
 
 use crate::visitor::{
@@ -28,22 +35,29 @@ pub enum BuildTarget {
 
 pub struct BuildPlugin {
     target: BuildTarget,
-    imports: FxHashMap<String, String>, // crate_name -> version
+    imports: FxHashMap<String, (String, Option<String>)>, // crate_name -> (version, version_req)
     service_name: Once<Symbol>,
+    lint_service_name: Arc<Mutex<Option<Symbol>>>, // shared with the lint passes
     event_indexed_fields: FxHashMap<Symbol, Vec<Symbol>>, // event_name -> field_name
+    default_fn_name: Option<Symbol>,
+    rpc_names: Vec<Symbol>, // includes the ctor
     iface: Once<oasis_rpc::Interface>,
 }
 
 impl BuildPlugin {
     pub fn new(
         target: BuildTarget,
-        imports: impl IntoIterator<Item = (String /* name */, String /* semver */)>,
+        // (name, (semver, semver req))
+        imports: impl IntoIterator<Item = (String, (String, Option<String>))>,
     ) -> Self {
         Self {
             target,
             imports: imports.into_iter().collect(),
             service_name: Once::new(),
+            lint_service_name: Default::default(),
             event_indexed_fields: Default::default(),
+            default_fn_name: None,
+            rpc_names: Vec::new(),
             iface: Once::new(),
         }
     }
@@ -64,6 +78,17 @@ macro_rules! ret_err {
 }
 
 impl rustc_driver::Callbacks for BuildPlugin {
+    fn config(&mut self, config: &mut Config) {
+        let service_name = Arc::clone(&self.lint_service_name);
+        config.register_lints = Some(Box::new(move |_sess, lint_store| {
+            lint_store.register_lints(&[crate::lint::NON_PUB_RPC]);
+            let service_name = Arc::clone(&service_name);
+            lint_store.register_early_pass(move || {
+                box crate::lint::NonPubRpc::new(Arc::clone(&service_name))
+            });
+        }));
+    }
+
     fn after_parsing(&mut self, compiler: &Compiler, queries: &Queries) -> Compilation {
         let gen_dir = compiler
             .output_dir()
@@ -105,6 +130,7 @@ impl rustc_driver::Callbacks for BuildPlugin {
         };
         let service_name = main_service.name;
         self.service_name.set(service_name);
+        *self.lint_service_name.lock().unwrap() = Some(service_name);
 
         let mut parsed_rpc_collector = ParsedRpcCollector::new(service_name);
         syntax::visit::walk_crate(&mut parsed_rpc_collector, &parse);
@@ -120,6 +146,8 @@ impl rustc_driver::Callbacks for BuildPlugin {
             }
         };
 
+        let derives_default = parsed_rpc_collector.derives_default();
+        let invariants = parsed_rpc_collector.invariants().to_vec();
         let (rpcs_result, warnings) = parsed_rpc_collector.into_rpcs();
 
         for warning in warnings {
@@ -141,25 +169,44 @@ impl rustc_driver::Callbacks for BuildPlugin {
             .partition(|rpc| rpc.kind == ParsedRpcKind::Ctor);
         if ctors.len() > 1 {
             ret_err!(); // Multiply defined `new` function. Let the compiler catch this.
-        } else if ctors.is_empty() {
+        } else if ctors.is_empty() && !derives_default {
             sess.span_err(
                 struct_span,
-                &format!("Missing definition of `{}::new`.", service_name),
+                &format!(
+                    "Missing definition of `{0}::new`. \
+                     Either define it or derive `Default` for `{0}`.",
+                    service_name
+                ),
             );
             ret_err!();
         }
-        let ctor = ctors.into_iter().next().unwrap();
+        let ctor = ctors.into_iter().next(); // `None` means the service is `Default`-constructed.
 
-        let default_fn_spans = rpcs
+        // An RPC is selected by the first byte of its payload.
+        let max_rpcs = usize::from(u8::max_value()) + 1;
+        if rpcs.len() > max_rpcs {
+            sess.span_err(
+                struct_span,
+                &format!(
+                    "`{}` defines {} RPC methods, but a service may define at most {}",
+                    service_name,
+                    rpcs.len(),
+                    max_rpcs
+                ),
+            );
+            ret_err!();
+        }
+
+        let (default_fn_names, default_fn_spans): (Vec<_>, Vec<_>) = rpcs
             .iter()
             .filter_map(|rpc| {
                 if let ParsedRpcKind::Default(default_span) = rpc.kind {
-                    Some(vec![default_span, rpc.span])
+                    Some((rpc.name, vec![default_span, rpc.span]))
                 } else {
                     None
                 }
             })
-            .collect::<Vec<_>>();
+            .unzip();
         if default_fn_spans.len() > 1 {
             sess.span_err(
                 default_fn_spans.into_iter().flatten().collect::<Vec<_>>(),
@@ -167,6 +214,8 @@ impl rustc_driver::Callbacks for BuildPlugin {
             );
             ret_err!();
         }
+        self.default_fn_name = default_fn_names.into_iter().next();
+        self.rpc_names = ctor.iter().chain(rpcs.iter()).map(|rpc| rpc.name).collect();
 
         let build_context = BuildContext {
             target: self.target,
@@ -177,6 +226,7 @@ impl rustc_driver::Callbacks for BuildPlugin {
             name: service_name,
             ctor,
             rpcs,
+            invariants,
         };
 
         crate::gen::insert_oasis_bindings(build_context, &mut parse, service_def);
@@ -199,7 +249,7 @@ impl rustc_driver::Callbacks for BuildPlugin {
 
         global_ctxt.enter(|tcx| {
             let krate = tcx.hir().krate();
-            let mut rpc_collector = AnalyzedRpcCollector::new(tcx, *service_name);
+            let mut rpc_collector = AnalyzedRpcCollector::new(tcx, *service_name, &self.rpc_names);
             krate.visit_all_item_likes(&mut rpc_collector);
 
             let defined_types = rpc_collector.rpcs().iter().flat_map(|(_, decl, _)| {
@@ -218,14 +268,37 @@ impl rustc_driver::Callbacks for BuildPlugin {
 
             let mut imports = BTreeSet::new();
             let mut local_def_tys = BTreeSet::new();
+            let mut local_def_ty_names = FxHashMap::default();
+            let mut has_name_collision = false;
             for (def_ty, spans) in all_def_tys {
                 if def_ty.adt_def.did.is_local() {
+                    let rust_ty = tcx.mk_adt(def_ty.adt_def, def_ty.substs);
+                    // Unsupported type args are reported when converting the interface.
+                    if let Ok(idl_name) =
+                        crate::rpc::type_def_name(tcx, def_ty.adt_def, def_ty.substs)
+                    {
+                        match local_def_ty_names.get(&idl_name) {
+                            Some(&other_ty) if other_ty != rust_ty => {
+                                let err_msg = format!(
+                                    "`{}` and `{}` are both named `{}` in the RPC interface. \
+                                     Rename one of them.",
+                                    other_ty, rust_ty, idl_name
+                                );
+                                sess.span_err(spans, &err_msg);
+                                has_name_collision = true;
+                            }
+                            Some(_) => (),
+                            None => {
+                                local_def_ty_names.insert(idl_name, rust_ty);
+                            }
+                        }
+                    }
                     local_def_tys.insert(def_ty);
                 } else {
                     let crate_name = tcx.original_crate_name(def_ty.adt_def.did.krate);
                     match self.imports.get(&*crate_name.as_str()) {
-                        Some(version) => {
-                            imports.insert((crate_name, version.to_string()));
+                        Some((version, version_req)) => {
+                            imports.insert((crate_name, version.clone(), version_req.clone()));
                         }
                         None => {
                             let err_msg = format!(
@@ -239,6 +312,10 @@ impl rustc_driver::Callbacks for BuildPlugin {
                 }
             }
 
+            if has_name_collision {
+                return;
+            }
+
             let iface = match crate::rpc::convert_interface(
                 tcx,
                 *service_name,
@@ -246,6 +323,7 @@ impl rustc_driver::Callbacks for BuildPlugin {
                 local_def_tys,
                 &self.event_indexed_fields,
                 rpc_collector.rpcs(),
+                self.default_fn_name,
             ) {
                 Ok(iface) => iface,
                 Err(errs) => {
@@ -256,6 +334,12 @@ impl rustc_driver::Callbacks for BuildPlugin {
                 }
             };
 
+            if log_enabled!(log::Level::Debug) {
+                debug!(
+                    "generated interface:\n{}",
+                    iface.to_string().unwrap_or_default()
+                );
+            }
             self.iface.set(iface);
         });
 
@@ -10,25 +10,30 @@ use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::{self, def_id::DefId, Body, FnDecl};
 use rustc_span::symbol::Symbol;
 
-use crate::{error::UnsupportedTypeError, visitor::hir::DefinedType};
+use crate::{
+    error::UnsupportedTypeError,
+    visitor::hir::{DefinedType, StateAccessCounter},
+};
 
 // faq: why return a vec of errors? so that the user can see and correct them all at once.
 pub fn convert_interface<'tcx>(
     tcx: TyCtxt<'tcx>,
     name: Symbol,
     // the following use BTreeSets to ensure idl is deterministic
-    imports: BTreeSet<(Symbol, String)>, // (name, version)
+    imports: BTreeSet<(Symbol, String, Option<String>)>, // (name, version, version_req)
     def_tys: BTreeSet<DefinedType<'tcx>>,
     event_indices: &FxHashMap<Symbol, Vec<Symbol>>,
-    fns: &[(Symbol, &FnDecl, &Body)],
+    fns: &[(Symbol, &'tcx FnDecl<'tcx>, &'tcx Body<'tcx>)],
+    default_fn: Option<Symbol>,
 ) -> Result<Interface, Vec<UnsupportedTypeError>> {
     let mut errs = Vec::new();
 
     let imports = imports
         .into_iter()
-        .map(|(name, version)| Import {
+        .map(|(name, version, version_req)| Import {
             name: name.to_string(),
             version,
+            version_req,
             registry: None, // TODO
         })
         .collect();
@@ -42,12 +47,9 @@ pub fn convert_interface<'tcx>(
     {
         match convert_type_def(tcx, adt_def, substs, *is_event) {
             Ok(mut event_def) => {
-                if let TypeDef::Event {
-                    name,
-                    ref mut fields,
-                } = &mut event_def
-                {
-                    if let Some(indexed_fields) = event_indices.get(&Symbol::intern(name)) {
+                if let TypeDef::Event { fields, .. } = &mut event_def {
+                    // The IDL name may be monomorphized, so look up the item name.
+                    if let Some(indexed_fields) = event_indices.get(&tcx.item_name(adt_def.did)) {
                         for field in fields.iter_mut() {
                             field.indexed = indexed_fields
                                 .iter()
@@ -86,8 +88,13 @@ pub fn convert_interface<'tcx>(
             version: std::env::var("CARGO_PKG_VERSION").unwrap(),
             imports,
             type_defs,
-            constructor: ctor.unwrap(),
+            // A service without a `new` is constructed using `Default`.
+            constructor: ctor.unwrap_or_else(|| Constructor {
+                inputs: Vec::new(),
+                error: None,
+            }),
             functions,
+            default_function: default_fn.map(|name| name.as_str().to_snake_case()),
             oasis_build_version: Some(env!("CARGO_PKG_VERSION").to_string()),
         })
     }
@@ -98,6 +105,7 @@ fn convert_state_ctor(
     decl: &FnDecl,
     body: &Body,
 ) -> Result<Constructor, Vec<UnsupportedTypeError>> {
+    debug!("converting constructor");
     let mut errs = Vec::new();
 
     let mut inputs = Vec::with_capacity(decl.inputs.len());
@@ -114,9 +122,12 @@ fn convert_state_ctor(
     }
 
     let error = match &decl.output {
-        rustc_hir::FunctionRetTy::Return(ty) => match convert_ty(tcx, &ty) {
-            Ok(Type::Result(_, box error)) => Some(error),
-            _ => None,
+        rustc_hir::FunctionRetTy::Return(ty) => match convert_ctor_error(tcx, &ty) {
+            Ok(error) => error,
+            Err(err) => {
+                errs.push(err);
+                None
+            }
         },
         rustc_hir::FunctionRetTy::DefaultReturn(_) => {
             unreachable!("Syntax pass checks that ctor returns `Self`")
@@ -130,12 +141,48 @@ fn convert_state_ctor(
     }
 }
 
-fn convert_function(
+/// Returns the `E` of a constructor that returns `Result<Self, E>`.
+/// Only the error type is converted since `Self` is not a valid RPC type.
+fn convert_ctor_error(
     tcx: TyCtxt,
+    ty: &rustc_hir::Ty,
+) -> Result<Option<Type>, UnsupportedTypeError> {
+    use rustc_hir::def::{DefKind, Res};
+
+    let path = match &ty.kind {
+        rustc_hir::TyKind::Path(rustc_hir::QPath::Resolved(_, path)) => path,
+        _ => return Ok(None),
+    };
+    let type_args = crate::utils::get_type_args(&path);
+    let is_result = |did| {
+        let (crate_name, def_path_comps) = crate::utils::def_path(tcx, did);
+        crate::utils::is_std(crate_name)
+            && def_path_comps.last().map(String::as_str) == Some("Result")
+    };
+    match path.res {
+        Res::Def(DefKind::Enum, did) if is_result(did) => {
+            type_args.get(1).map(|ty| convert_ty(tcx, ty)).transpose()
+        }
+        Res::Def(DefKind::TyAlias, did) => match tcx.type_of(did).kind {
+            ty::TyKind::Adt(result_def, substs) if is_result(result_def.did) => {
+                Ok(Some(match type_args.get(1) {
+                    Some(ty) => convert_ty(tcx, ty)?,
+                    None => convert_sty(tcx, did, substs.type_at(1))?,
+                }))
+            }
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+fn convert_function<'tcx>(
+    tcx: TyCtxt<'tcx>,
     name: Symbol,
     decl: &FnDecl,
-    body: &Body,
+    body: &'tcx Body<'tcx>,
 ) -> Result<Function, Vec<UnsupportedTypeError>> {
+    debug!("converting RPC `{}`", name);
     let mut errs = Vec::new();
 
     let mutability = match decl.implicit_self {
@@ -177,26 +224,63 @@ fn convert_function(
             mutability,
             inputs,
             output,
+            estimated_gas: Some(estimate_gas(tcx, body)),
         })
     }
 }
 
+/// Returns a rough estimate of the most gas used by an RPC method, as priced by memchain's
+/// metered schedule: the base cost of a transaction plus a storage read and write for each
+/// access of the service's state. Loops and calls are not taken into account.
+fn estimate_gas<'tcx>(tcx: TyCtxt<'tcx>, body: &'tcx Body<'tcx>) -> u64 {
+    use rustc_hir::intravisit::Visitor as _;
+    let mut state_access_counter = StateAccessCounter::new(tcx);
+    state_access_counter.visit_body(body);
+    let schedule = memchain::GasSchedule::METERED;
+    schedule.base
+        + (schedule.storage_read + schedule.storage_write) * state_access_counter.num_accesses()
+}
+
 fn convert_arg(
     tcx: TyCtxt,
     pat: &rustc_hir::Pat,
     ty: &rustc_hir::Ty,
 ) -> Result<Field, UnsupportedTypeError> {
     use rustc_hir::PatKind;
-    convert_ty(tcx, ty).map(|ty| Field {
-        name: match pat.kind {
-            PatKind::Wild => "_".to_string(),
-            PatKind::Binding(_, _, ident, _) => ident.name.as_str().to_snake_case(),
-            _ => unreachable!("arg pattern must be wild or ident"),
+    let name = match pat.kind {
+        PatKind::Wild => "_".to_string(),
+        PatKind::Binding(_, _, ident, _) => ident.name.as_str().to_snake_case(),
+        _ => unreachable!("arg pattern must be wild or ident"),
+    };
+    let ty = convert_ty(tcx, ty)?;
+    debug!("argument `{}` has type {:?}", name, ty);
+    Ok(Field {
+        optional: match ty {
+            Type::Optional(_) => true,
+            _ => false,
         },
+        name,
         ty,
     })
 }
 
+/// Returns whether `ty_str` names a map collection. The hash, B-tree, and `Vec`-backed
+/// (`map_vec`) variants differ only in memory and all have the same wire type.
+fn is_map(ty_str: &str) -> bool {
+    match ty_str {
+        "HashMap" | "BTreeMap" | "Map" => true,
+        _ => false,
+    }
+}
+
+/// Returns whether `ty_str` names a set collection. See `is_map`.
+fn is_set(ty_str: &str) -> bool {
+    match ty_str {
+        "HashSet" | "BTreeSet" | "Set" => true,
+        _ => false,
+    }
+}
+
 // this is a macro because it's difficult to convince rustc that `T` \in {`Ty`, `TyS`}`
 macro_rules! convert_def {
     ($tcx:ident, $did:expr, $owner_did:expr, $arg_at:expr) => {{
@@ -211,20 +295,33 @@ macro_rules! convert_def {
                     Type::U8 => Type::Bytes,
                     ty => Type::List(box ty),
                 }
+            } else if ty_str == "Cow" || ty_str == "Box" {
+                // A `Cow` or `Box` is encoded identically to the type it points to.
+                // A recursive type refers to itself by name, so its def is not expanded.
+                $arg_at(0)?
             } else if ty_str == "Option" {
                 Type::Optional(box $arg_at(0)?)
             } else if ty_str == "Result" {
                 Type::Result(box $arg_at(0)?, box $arg_at(1)?)
-            } else if ty_str.ends_with("Map") {
+            } else if is_map(&ty_str) {
                 Type::Map(box $arg_at(0)?, box $arg_at(1)?)
-            } else if ty_str.ends_with("Set") {
+            } else if is_set(&ty_str) {
                 Type::Set(box $arg_at(0)?)
             } else if ty_str == "Address" {
                 Type::Address
             } else if ty_str == "Balance" {
                 Type::Balance
+            } else if ty_str == "U256" {
+                Type::U256
+            } else if ty_str == "I256" {
+                Type::I256
             } else if ty_str == "RpcError" {
                 Type::RpcError
+            } else if ty_str == "Duration" {
+                Type::Duration
+            } else if ty_str == "StatusCode" {
+                // The status is a `u16` header, so clients need not know about `StatusCode`.
+                Type::U16
             } else {
                 // this branch includes `sync`, among other things
                 return Err(UnsupportedTypeError {
@@ -233,22 +330,81 @@ macro_rules! convert_def {
                 });
             }
         } else {
+            let num_type_args = $tcx.generics_of($did).own_counts().types;
             Type::Defined {
                 namespace: if crate_name == $tcx.crate_name {
                     None
                 } else {
                     Some(crate_name.to_string())
                 },
-                ty: ty_str,
+                ty: monomorphized_name(
+                    ty_str,
+                    &(0..num_type_args)
+                        .map($arg_at)
+                        .collect::<Result<Vec<_>, UnsupportedTypeError>>()?,
+                ),
             }
         })
     }};
 }
 
+/// Returns the name of the defined type `ty_name` instantiated with `type_args`.
+/// The name is formed from the names of the type arguments so that each instantiation
+/// is named deterministically, e.g., `Ledger<Token>` becomes `LedgerToken`.
+fn monomorphized_name(ty_name: String, type_args: &[Type]) -> String {
+    type_args.iter().fold(ty_name, |mut name, ty| {
+        push_type_name(&mut name, ty);
+        name
+    })
+}
+
+fn push_type_name(name: &mut String, ty: &Type) {
+    match ty {
+        Type::Defined { ty, .. } => name.push_str(ty),
+        Type::Tuple(tys) => {
+            name.push_str("Tuple");
+            tys.iter().for_each(|ty| push_type_name(name, ty));
+        }
+        Type::Array(ty, len) => {
+            name.push_str("Array");
+            push_type_name(name, ty);
+            name.push_str(&len.to_string());
+        }
+        Type::List(ty) => {
+            name.push_str("List");
+            push_type_name(name, ty);
+        }
+        Type::Set(ty) => {
+            name.push_str("Set");
+            push_type_name(name, ty);
+        }
+        Type::Map(k, v) => {
+            name.push_str("Map");
+            push_type_name(name, k);
+            push_type_name(name, v);
+        }
+        Type::Optional(ty) => {
+            name.push_str("Optional");
+            push_type_name(name, ty);
+        }
+        Type::Result(ok, err) => {
+            name.push_str("Result");
+            push_type_name(name, ok);
+            push_type_name(name, err);
+        }
+        // The remaining types are named after their variant, e.g., `U64` or `Address`.
+        _ => name.push_str(&format!("{:?}", ty)),
+    }
+}
+
 fn convert_ty(tcx: TyCtxt, ty: &rustc_hir::Ty) -> Result<Type, UnsupportedTypeError> {
     use rustc_hir::TyKind;
     Ok(match &ty.kind {
-        TyKind::Slice(ty) => Type::List(box convert_ty(tcx, &ty)?),
+        TyKind::Slice(ty) => match convert_ty(tcx, &ty)? {
+            // `&[u8]` is encoded identically to `Vec<u8>`.
+            Type::U8 => Type::Bytes,
+            ty => Type::List(box ty),
+        },
         TyKind::Array(ty, len) => {
             let arr_ty = box convert_ty(tcx, &ty)?;
             match tcx.hir().body(len.body).value.kind {
@@ -276,7 +432,14 @@ fn convert_ty(tcx: TyCtxt, ty: &rustc_hir::Ty) -> Result<Type, UnsupportedTypeEr
                     | DefKind::Variant
                     | DefKind::Const => {
                         convert_def!(tcx, did, ty.hir_id.owner_local_def_id().to_def_id(), |i| {
-                            convert_ty(tcx, type_args[i])
+                            // A type argument may be omitted if its parameter has a default.
+                            match type_args.get(i) {
+                                Some(ty) => convert_ty(tcx, ty),
+                                None => Err(UnsupportedTypeError {
+                                    type_name: path.to_string(),
+                                    span: path.span,
+                                }),
+                            }
                         })?
                     }
                     DefKind::TyAlias => {
@@ -329,7 +492,14 @@ fn convert_sty<'tcx>(
     ty: &'tcx TyS,
 ) -> Result<Type, UnsupportedTypeError> {
     convert_sty_with_arg_at(tcx, did, ty, |substs, i| {
-        convert_sty(tcx, did, substs.type_at(i))
+        // Skip lifetime arguments, e.g., the `'a` in `Cow<'a, str>`.
+        match substs.types().nth(i) {
+            Some(arg_ty) => convert_sty(tcx, did, arg_ty),
+            None => Err(UnsupportedTypeError {
+                type_name: ty.to_string(),
+                span: tcx.def_span(did),
+            }),
+        }
     })
 }
 
@@ -348,29 +518,35 @@ fn convert_sty_with_arg_at<'tcx>(
         Float(ty) => convert_float(ty, tcx.def_span(did))?,
         Adt(AdtDef { did, .. }, substs) => convert_def!(tcx, *did, *did, |i| arg_at(substs, i))?,
         Str => Type::String,
-        Array(ty, len) => Type::Array(
-            box convert_sty(tcx, did, ty)?,
+        Array(elem_ty, len) => Type::Array(
+            box convert_sty(tcx, did, elem_ty)?,
             len.val
                 .try_to_scalar()
                 .and_then(|c| c.to_machine_usize(&tcx).ok())
                 .or_else(|| len.try_eval_usize(tcx, ty::ParamEnv::empty()))
                 // The following is a mightily workaround for rustc not evaluating
                 // literal array lengths in structs, for whatever reason.
-                .unwrap_or_else(|| {
+                .or_else(|| {
                     let arr_str = tcx
                         .sess
                         .source_map()
                         .span_to_snippet(tcx.def_span(did))
-                        .unwrap();
+                        .ok()?;
                     arr_str
-                        .get((arr_str.rfind(';').unwrap() + 1)..arr_str.rfind(']').unwrap())
-                        .unwrap()
+                        .get((arr_str.rfind(';')? + 1)..arr_str.rfind(']')?)?
                         .trim()
                         .parse()
-                        .unwrap()
-                }),
+                        .ok()
+                })
+                .ok_or_else(|| UnsupportedTypeError {
+                    type_name: ty.to_string(),
+                    span: tcx.def_span(did),
+                })?,
         ),
-        Slice(ty) => Type::List(box convert_sty(tcx, did, ty)?),
+        Slice(ty) => match convert_sty(tcx, did, ty)? {
+            Type::U8 => Type::Bytes,
+            ty => Type::List(box ty),
+        },
         Ref(_, ty, _) => return convert_sty(tcx, did, ty),
         Tuple(substs) => Type::Tuple(
             substs
@@ -436,20 +612,55 @@ fn convert_float(
     })
 }
 
+/// Returns the number pinned by an item's `#[oasis(<key> = N)]`, if any, e.g., the tag
+/// of an enum variant. The numbers are checked by `#[derive(TaggedEnum)]` and
+/// `#[derive(OrderedStruct)]`.
+fn pinned_index(tcx: TyCtxt, did: DefId, key: &str) -> Option<u8> {
+    tcx.get_attrs(did)
+        .iter()
+        .filter(|attr| attr.check_name(Symbol::intern("oasis")))
+        .filter_map(|attr| attr.meta_item_list())
+        .flatten()
+        .find_map(|nested| match nested.name_value_literal() {
+            Some((
+                name,
+                syntax::ast::Lit {
+                    kind: syntax::ast::LitKind::Int(index, _),
+                    ..
+                },
+            )) if name == Symbol::intern(key) => Some(*index as u8),
+            _ => None,
+        })
+}
+
+/// Returns the name that `def` instantiated with `substs` is given in the interface.
+pub fn type_def_name<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: &AdtDef,
+    substs: SubstsRef<'tcx>,
+) -> Result<String, UnsupportedTypeError> {
+    Ok(monomorphized_name(
+        tcx.def_path(def.did)
+            .data
+            .iter()
+            .last()
+            .unwrap()
+            .data
+            .to_string(),
+        &substs
+            .types()
+            .map(|ty| convert_sty(tcx, def.did, ty))
+            .collect::<Result<Vec<_>, _>>()?,
+    ))
+}
+
 fn convert_type_def<'tcx>(
     tcx: TyCtxt<'tcx>,
     def: &AdtDef,
     substs: SubstsRef<'tcx>,
     is_event: bool,
 ) -> Result<TypeDef, UnsupportedTypeError> {
-    let ty_name = tcx
-        .def_path(def.did)
-        .data
-        .iter()
-        .last()
-        .unwrap()
-        .data
-        .to_string();
+    let ty_name = type_def_name(tcx, def, substs)?;
     if def.is_enum() {
         let variants = def
             .variants
@@ -457,6 +668,7 @@ fn convert_type_def<'tcx>(
             .map(|v| {
                 Ok(EnumVariant {
                     name: v.ident.to_string(),
+                    tag: pinned_index(tcx, v.def_id, "tag"),
                     fields: if v.fields.is_empty() {
                         None
                     } else {
@@ -469,8 +681,14 @@ fn convert_type_def<'tcx>(
                             .iter()
                             .map(|field_def| {
                                 Ok(Field {
+                                    optional: false,
                                     name: field_def.ident.to_string(),
-                                    ty: convert_sty(tcx, field_def.did, field_def.ty(tcx, substs))?,
+                                    ty: convert_field_ty(
+                                        tcx,
+                                        &format!("{}::{}", ty_name, v.ident),
+                                        field_def,
+                                        substs,
+                                    )?,
                                 })
                             })
                             .collect::<Result<_, _>>()?;
@@ -488,12 +706,15 @@ fn convert_type_def<'tcx>(
             variants,
         })
     } else if def.is_struct() {
-        let fields = def
-            .all_fields()
+        // An `OrderedStruct` is encoded in the order of its pinned fields.
+        let mut fields: Vec<_> = def.all_fields().collect();
+        fields.sort_by_key(|f| pinned_index(tcx, f.did, "field"));
+        let fields = fields
+            .into_iter()
             .map(|f| {
                 Ok((
                     f.ident.to_string(),
-                    convert_sty(tcx, f.did, tcx.type_of(f.did))?,
+                    convert_field_ty(tcx, &ty_name, f, substs)?,
                 ))
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -514,7 +735,11 @@ fn convert_type_def<'tcx>(
                 name: ty_name,
                 fields: fields
                     .into_iter()
-                    .map(|(name, ty)| Field { name, ty })
+                    .map(|(name, ty)| Field {
+                        name,
+                        ty,
+                        optional: false,
+                    })
                     .collect(),
             }
         })
@@ -528,3 +753,17 @@ fn convert_type_def<'tcx>(
         unreachable!("AdtDef is a struct, enum, or union");
     }
 }
+
+fn convert_field_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    owner_name: &str,
+    field_def: &ty::FieldDef,
+    substs: SubstsRef<'tcx>,
+) -> Result<Type, UnsupportedTypeError> {
+    let ty = convert_sty(tcx, field_def.did, field_def.ty(tcx, substs))?;
+    debug!(
+        "field `{}::{}` has type {:?}",
+        owner_name, field_def.ident, ty
+    );
+    Ok(ty)
+}
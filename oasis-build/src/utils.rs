@@ -8,6 +8,7 @@ pub fn is_std(crate_name: Symbol) -> bool {
     crate_name == "std"
         || crate_name == "core"
         || crate_name == "alloc"
+        || crate_name == "map_vec"
         || crate_name.starts_with("oasis")
 }
 
@@ -46,6 +47,13 @@ pub fn is_self_ref(ty: &ast::Ty) -> bool {
     }
 }
 
+pub fn is_mut_ref(ty: &ast::Ty) -> bool {
+    match &ty.kind {
+        ast::TyKind::Rptr(_, mut_ty) => mut_ty.mutbl == ast::Mutability::Mut,
+        _ => false,
+    }
+}
+
 pub fn is_context_ref(ty: &ast::Ty) -> bool {
     match &ty.kind {
         ast::TyKind::Rptr(_, mut_ty) => match &mut_ty.ty.kind {
@@ -56,6 +64,27 @@ pub fn is_context_ref(ty: &ast::Ty) -> bool {
     }
 }
 
+/// Returns the borrowed type `B` if `ty` is a `Cow<B>`.
+pub fn cow_borrowed_ty(ty: &ast::Ty) -> Option<&syntax::ptr::P<ast::Ty>> {
+    let path = match &ty.kind {
+        ast::TyKind::Path(None, path) => path,
+        _ => return None,
+    };
+    let last_seg = path.segments.last()?;
+    if last_seg.ident.name != Symbol::intern("Cow") {
+        return None;
+    }
+    match last_seg.args.as_ref().map(|args| &**args) {
+        Some(ast::GenericArgs::AngleBracketed(ast::AngleBracketedArgs { args, .. })) => {
+            args.iter().find_map(|arg| match arg {
+                ast::GenericArg::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
 /// Returns whether `path` ends with `suffix`.
 /// e.g, `path_is_suffix(crate::oasis_std::service, ["oasis_std", "service"]) == true`
 pub fn path_ends_with(path: &ast::Path, suffix: &[&'static str]) -> bool {
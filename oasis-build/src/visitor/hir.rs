@@ -14,12 +14,16 @@ use rustc_hir::{
     hir_id::HirIdSet,
     intravisit,
 };
-use rustc_span::{symbol::Symbol, Span};
+use rustc_span::{
+    symbol::{kw, Symbol},
+    Span,
+};
 
-/// Collects public functions defined in `impl #service_name`.
-pub struct AnalyzedRpcCollector<'tcx> {
+/// Collects the RPCs (and the ctor) defined in `impl #service_name`.
+pub struct AnalyzedRpcCollector<'tcx, 'a> {
     tcx: TyCtxt<'tcx>,
     service_name: Symbol,
+    rpc_names: &'a [Symbol], // as found by `ParsedRpcCollector`
     rpc_impls: HirIdSet,
     rpcs: Vec<(
         Symbol,
@@ -28,11 +32,12 @@ pub struct AnalyzedRpcCollector<'tcx> {
     )>, /* the collected RPC fns */
 }
 
-impl<'tcx> AnalyzedRpcCollector<'tcx> {
-    pub fn new(tcx: TyCtxt<'tcx>, service_name: Symbol) -> Self {
+impl<'tcx, 'a> AnalyzedRpcCollector<'tcx, 'a> {
+    pub fn new(tcx: TyCtxt<'tcx>, service_name: Symbol, rpc_names: &'a [Symbol]) -> Self {
         Self {
             tcx,
             service_name,
+            rpc_names,
             rpc_impls: HirIdSet::default(),
             rpcs: Vec::new(),
         }
@@ -49,7 +54,7 @@ impl<'tcx> AnalyzedRpcCollector<'tcx> {
     }
 }
 
-impl<'tcx> rustc_hir::itemlikevisit::ItemLikeVisitor<'tcx> for AnalyzedRpcCollector<'tcx> {
+impl<'tcx, 'a> rustc_hir::itemlikevisit::ItemLikeVisitor<'tcx> for AnalyzedRpcCollector<'tcx, 'a> {
     fn visit_item(&mut self, item: &'tcx rustc_hir::Item) {
         if let rustc_hir::ItemKind::Impl {
             of_trait: None,
@@ -69,7 +74,9 @@ impl<'tcx> rustc_hir::itemlikevisit::ItemLikeVisitor<'tcx> for AnalyzedRpcCollec
         if let rustc_hir::ImplItemKind::Method(rustc_hir::FnSig { decl, .. }, body_id) =
             &impl_item.kind
         {
-            if impl_item.vis.node.is_pub()
+            // The RPCs were identified from the `#[oasis]` attributes, which are gone by now.
+            // See `ParsedRpc::try_new_maybe`.
+            if self.rpc_names.contains(&impl_item.ident.name)
                 && self
                     .rpc_impls
                     .contains(&self.tcx.hir().get_parent_item(impl_item.hir_id))
@@ -127,7 +134,7 @@ impl<'tcx> DefinedTypeCollector<'tcx> {
                 return;
             }
             for field in adt_def.all_fields() {
-                for inner_ty in self.tcx.type_of(field.did).walk() {
+                for inner_ty in field.ty(self.tcx, substs).walk() {
                     self.visit_sty(inner_ty, self.tcx.def_span(field.did));
                 }
             }
@@ -142,6 +149,13 @@ impl<'tcx> rustc_hir::intravisit::Visitor<'tcx> for DefinedTypeCollector<'tcx> {
         if let rustc_hir::TyKind::Path(rustc_hir::QPath::Resolved(_, path)) = &ty.kind {
             if let Res::Def(kind, id) = path.res {
                 match kind {
+                    DefKind::Struct | DefKind::Union | DefKind::Enum
+                        if !crate::utils::get_type_args(path).is_empty() =>
+                    {
+                        // Resolve the type arguments so that the concrete instantiation
+                        // (e.g., `Ledger<Token>`) is collected instead of `Ledger<T>`.
+                        self.visit_sty(rustc_typeck::hir_ty_to_ty(self.tcx, ty), ty.span);
+                    }
                     DefKind::Struct | DefKind::Union | DefKind::Enum | DefKind::TyAlias => {
                         self.visit_sty(self.tcx.type_of(id), ty.span);
                     }
@@ -159,6 +173,49 @@ impl<'tcx> rustc_hir::intravisit::Visitor<'tcx> for DefinedTypeCollector<'tcx> {
     }
 }
 
+/// Counts the accesses of the service's state (i.e. `self.field`) in an RPC method body,
+/// including in the closures it contains.
+pub struct StateAccessCounter<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    num_accesses: u64,
+}
+
+impl<'tcx> StateAccessCounter<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Self {
+            tcx,
+            num_accesses: 0,
+        }
+    }
+
+    pub fn num_accesses(&self) -> u64 {
+        self.num_accesses
+    }
+}
+
+impl<'tcx> rustc_hir::intravisit::Visitor<'tcx> for StateAccessCounter<'tcx> {
+    type Map = HirMap<'tcx>;
+
+    fn visit_expr(&mut self, expr: &'tcx rustc_hir::Expr) {
+        if let rustc_hir::ExprKind::Field(base, _) = &expr.kind {
+            if let rustc_hir::ExprKind::Path(rustc_hir::QPath::Resolved(None, path)) = &base.kind {
+                if let [seg] = path.segments {
+                    if seg.ident.name == kw::SelfLower {
+                        self.num_accesses += 1;
+                    }
+                }
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn nested_visit_map<'this>(
+        &'this mut self,
+    ) -> intravisit::NestedVisitorMap<'this, HirMap<'tcx>> {
+        intravisit::NestedVisitorMap::OnlyBodies(&self.tcx.hir())
+    }
+}
+
 /// Visits method bodies to find the structs of emitted events.
 /// Visit all methods because events can be emitted from any context (incl. library functions).
 /// The only constraint is that any event must be emitted in the current crate.
@@ -217,21 +274,17 @@ impl<'tcx> rustc_hir::intravisit::Visitor<'tcx> for EventCollector<'tcx> {
                         .push(emit_arg.span)
                 };
             }
-            match emit_arg_ty.ty_adt_def() {
-                Some(adt_def) => insert_def_ty!(adt_def, self.tcx.empty_substs_for_def_id(did)),
-                None => {
-                    if let TyKind::Ref(
-                        _,
-                        TyS {
-                            kind: TyKind::Adt(adt_def, substs),
-                            ..
-                        },
-                        _,
-                    ) = emit_arg_ty.kind
-                    {
-                        insert_def_ty!(adt_def, substs)
-                    }
-                }
+            match emit_arg_ty.kind {
+                TyKind::Adt(adt_def, substs)
+                | TyKind::Ref(
+                    _,
+                    TyS {
+                        kind: TyKind::Adt(adt_def, substs),
+                        ..
+                    },
+                    _,
+                ) => insert_def_ty!(adt_def, substs),
+                _ => (),
             }
         }
         intravisit::walk_expr(self, expr);
@@ -258,13 +311,16 @@ impl<'a> PartialOrd for DefinedType<'a> {
 
 impl<'a> Ord for DefinedType<'a> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.adt_def.cmp(other.adt_def)
+        // Substs are compared by their rendering to keep the IDL deterministic.
+        self.adt_def
+            .cmp(other.adt_def)
+            .then_with(|| format!("{:?}", self.substs).cmp(&format!("{:?}", other.substs)))
     }
 }
 
 impl<'a> PartialEq for DefinedType<'a> {
     fn eq(&self, other: &Self) -> bool {
-        self.adt_def == other.adt_def
+        self.adt_def == other.adt_def && self.substs == other.substs
     }
 }
 
@@ -273,5 +329,6 @@ impl<'a> Eq for DefinedType<'a> {}
 impl<'a> Hash for DefinedType<'a> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.adt_def.hash(state);
+        self.substs.hash(state);
     }
 }
@@ -20,6 +20,17 @@ impl ParsedRpc {
 
         let is_ctor = impl_item.ident.name == Symbol::intern("new");
 
+        // `#[skip]` methods are regular Rust methods that are not exposed as RPCs.
+        // Neither are `#[invariant]` methods, which are called by the dispatcher.
+        let skip_span = attr_span(impl_item, "skip").or_else(|| attr_span(impl_item, "invariant"));
+        match skip_span {
+            Some(skip_span) if is_ctor => {
+                return Some(Err(vec![RpcError::CtorIsSkipped(skip_span)]));
+            }
+            Some(_) => return None,
+            None => (),
+        }
+
         match impl_item.vis.node {
             ast::VisibilityKind::Public => (),
             _ if is_ctor => (),
@@ -64,17 +75,8 @@ impl ParsedRpc {
             }
         }
 
-        let default_span = impl_item.attrs.iter().find_map(|attr| {
-            let attr_path = match &attr.kind {
-                ast::AttrKind::Normal(item) => &item.path,
-                _ => return None,
-            };
-            if crate::utils::path_ends_with(&attr_path, &["oasis_std", "default"]) {
-                Some(attr.span)
-            } else {
-                None
-            }
-        });
+        let default_span = attr_span(impl_item, "default");
+        let view_span = attr_span(impl_item, "view");
 
         let mut args = msig.decl.inputs.iter();
 
@@ -83,9 +85,14 @@ impl ParsedRpc {
                 Some(arg) if !crate::utils::is_self_ref(&arg.ty) => {
                     errors.push(RpcError::MissingSelf(arg.pat.span.to(arg.pat.span)))
                 }
+                Some(arg) if view_span.is_some() && crate::utils::is_mut_ref(&arg.ty) => {
+                    errors.push(RpcError::MutView(arg.span))
+                }
                 None => errors.push(RpcError::MissingSelf(impl_item.ident.span)),
                 _ => (),
             }
+        } else if let Some(view_span) = view_span {
+            errors.push(RpcError::CtorIsView(view_span));
         }
         match args.next() {
             Some(arg) if !crate::utils::is_context_ref(&arg.ty) => {
@@ -119,22 +126,11 @@ impl ParsedRpc {
 
         let ret_ty = ReturnType::new(&msig.decl.output);
 
-        if is_ctor {
-            let mut ret_ty_is_self = false;
-            if let Some(ast::Ty {
-                kind: ast::TyKind::Path(_, path),
-                ..
-            }) = &ret_ty.ty.as_ref().map(|p| &**p)
-            {
-                ret_ty_is_self = path.segments.len() == 1
-                    && path.segments[0].ident.name == Symbol::intern("Self");
-            }
-            if !ret_ty_is_self {
-                errors.push(RpcError::BadCtorReturn {
-                    self_ty: service_ty.clone().into_inner(),
-                    span: msig.decl.output.span(),
-                });
-            }
+        if is_ctor && !ret_ty.is_self_or_wraps_self() {
+            errors.push(RpcError::BadCtorReturn {
+                self_ty: service_ty.clone().into_inner(),
+                span: msig.decl.output.span(),
+            });
         }
 
         Some(if errors.is_empty() {
@@ -186,6 +182,20 @@ impl ParsedRpc {
         })
     }
 
+    /// Returns how each argument, which is decoded into its owned type (c.f. `arg_types`),
+    /// is passed to the RPC.
+    pub fn arg_passings(&self) -> impl Iterator<Item = ArgPassing> + '_ {
+        self.inputs().map(|inp| {
+            if crate::utils::cow_borrowed_ty(&inp.ty).is_some() {
+                ArgPassing::Cow
+            } else if let ast::TyKind::Rptr(..) = inp.ty.kind {
+                ArgPassing::Ref
+            } else {
+                ArgPassing::Value
+            }
+        })
+    }
+
     fn inputs(&self) -> impl Iterator<Item = &ast::Param> {
         self.sig.decl.inputs.iter().skip(match self.kind {
             ParsedRpcKind::Ctor => 1, /* ctx */
@@ -194,6 +204,21 @@ impl ParsedRpc {
     }
 }
 
+/// Returns the span of the `oasis_std` attribute called `name` (e.g., `#[view]`), if any.
+pub fn attr_span(impl_item: &ast::AssocItem, name: &'static str) -> Option<Span> {
+    impl_item.attrs.iter().find_map(|attr| {
+        let attr_path = match &attr.kind {
+            ast::AttrKind::Normal(item) => &item.path,
+            _ => return None,
+        };
+        if crate::utils::path_ends_with(&attr_path, &["oasis_std", name]) {
+            Some(attr.span)
+        } else {
+            None
+        }
+    })
+}
+
 #[derive(PartialEq, Eq)]
 pub enum ParsedRpcKind {
     Ctor,
@@ -201,50 +226,64 @@ pub enum ParsedRpcKind {
     Normal,
 }
 
+/// How a decoded argument is passed to an RPC. Borrowed arguments borrow from the
+/// decoded value rather than copying it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ArgPassing {
+    /// `arg`
+    Value,
+    /// `&arg`
+    Ref,
+    /// `Cow::Owned(arg)`
+    Cow,
+}
+
+/// The return type of an RPC. Whether it is a `Result` is not known until the type
+/// is resolved since `Result` may be aliased (e.g., `type Fallible<T> = Result<T, Error>`).
 pub struct ReturnType {
-    is_result: bool,
     ty: Option<P<ast::Ty>>,
 }
 
 impl ReturnType {
-    /// Returns a new `ReturnType` or `None` if error.
     fn new(ty: &ast::FunctionRetTy) -> Self {
-        let mut ret_ty = Self {
-            is_result: false,
-            ty: None,
+        Self {
+            ty: match ty {
+                ast::FunctionRetTy::Ty(ty) if !ty.kind.is_unit() => Some(ty.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns whether this is `Self` or a generic type (e.g., a `Result`) whose first
+    /// type argument is `Self`. The latter may alias a `Result<Self, E>`, so whether it
+    /// actually is one is checked once types are resolved (see `oasis_std::CtorOutput`).
+    fn is_self_or_wraps_self(&self) -> bool {
+        let path = match self.ty.as_ref().map(|ty| &ty.kind) {
+            Some(ast::TyKind::Path(None, path)) => path,
+            _ => return false,
         };
-        if let ast::FunctionRetTy::Ty(ty) = ty {
-            match &ty.kind {
-                ast::TyKind::Path(_, path) => {
-                    let maybe_result = path.segments.last().unwrap();
-                    ret_ty.is_result = maybe_result.ident.name == Symbol::intern("Result");
-                    if !ret_ty.is_result {
-                        if !ty.kind.is_unit() {
-                            ret_ty.ty = Some(ty.clone());
-                        }
-                        return ret_ty;
-                    }
-                    let result = maybe_result;
-                    if result.args.is_none() {
-                        // Weird. It's a user-defined type named Result.
-                        return Self {
-                            is_result: false,
-                            ty: Some(ty.clone()),
-                        };
-                    }
-                    if let ast::GenericArgs::AngleBracketed(ast::AngleBracketedArgs {
-                        args, ..
-                    }) = &**result.args.as_ref().unwrap()
-                    {
-                        if let ast::GenericArg::Type(p_ty) = &args[0] {
-                            ret_ty.ty = Some(p_ty.clone())
-                        }
-                    }
+        if is_self_path(path) {
+            return true;
+        }
+        match path
+            .segments
+            .last()
+            .unwrap()
+            .args
+            .as_ref()
+            .map(|args| &**args)
+        {
+            Some(ast::GenericArgs::AngleBracketed(ast::AngleBracketedArgs { args, .. })) => {
+                match args.first() {
+                    Some(ast::GenericArg::Type(ty)) => match &ty.kind {
+                        ast::TyKind::Path(None, path) => is_self_path(path),
+                        _ => false,
+                    },
+                    _ => false,
                 }
-                _ => ret_ty.ty = Some(ty.clone()),
             }
-        };
-        ret_ty
+            _ => false,
+        }
     }
 
     pub fn owned_ty(&self) -> Option<P<ast::Ty>> {
@@ -254,8 +293,8 @@ impl ReturnType {
             ty
         })
     }
+}
 
-    pub fn is_result(&self) -> bool {
-        self.is_result
-    }
+fn is_self_path(path: &ast::Path) -> bool {
+    path.segments.len() == 1 && path.segments[0].ident.name == Symbol::intern("Self")
 }
@@ -4,7 +4,7 @@ use syntax::{ast, mut_visit, ptr::P, visit};
 
 use crate::error::{RpcError, RpcWarning};
 
-use super::parsed_rpc::ParsedRpc;
+use super::parsed_rpc::{attr_span, ParsedRpc};
 
 #[derive(Default)]
 pub struct ServiceDefFinder {
@@ -44,7 +44,7 @@ impl<'ast> visit::Visitor<'ast> for ServiceDefFinder {
                     None => continue,
                 };
                 if ident != "Event" {
-                    return;
+                    continue;
                 }
                 if let ast::ItemKind::Struct(variant_data, _) = &item.kind {
                     let indexed_fields = variant_data
@@ -61,6 +61,10 @@ impl<'ast> visit::Visitor<'ast> for ServiceDefFinder {
                                 .and_then(|_| field.ident.map(|ident| ident.name))
                         })
                         .collect();
+                    debug!(
+                        "found event `{}` with indexed fields {:?}",
+                        item.ident, indexed_fields
+                    );
                     self.event_indexed_fields
                         .insert(item.ident.name, indexed_fields);
                 }
@@ -87,6 +91,7 @@ impl<'ast> visit::Visitor<'ast> for ServiceDefFinder {
             .and_then(|tok| tok.ident())
             .map(|(ident, _)| ident)
         {
+            debug!("found service `{}`", ident);
             self.services.push(Service {
                 span: mac.span(),
                 name: ident.name,
@@ -98,8 +103,10 @@ impl<'ast> visit::Visitor<'ast> for ServiceDefFinder {
 pub struct ParsedRpcCollector {
     service_name: Symbol,
     rpcs: Vec<ParsedRpc>,
+    invariants: Vec<Symbol>,
     errors: Vec<RpcError>,
     struct_span: Option<Span>,
+    derives_default: bool,
     println_spans: Vec<Span>,
 }
 
@@ -108,8 +115,10 @@ impl ParsedRpcCollector {
         Self {
             service_name,
             rpcs: Vec::new(),
+            invariants: Vec::new(),
             errors: Vec::new(),
             struct_span: None,
+            derives_default: false,
             println_spans: Vec::new(),
         }
     }
@@ -118,6 +127,18 @@ impl ParsedRpcCollector {
         self.struct_span
     }
 
+    /// Returns whether the state struct derives `Default`, which allows it to be
+    /// constructed without a `new` function.
+    pub fn derives_default(&self) -> bool {
+        self.derives_default
+    }
+
+    /// Returns the names of the `#[invariant]` methods, which are checked after
+    /// each mutating RPC in debug builds.
+    pub fn invariants(&self) -> &[Symbol] {
+        &self.invariants
+    }
+
     pub fn into_rpcs(self) -> (Result<Vec<ParsedRpc>, Vec<RpcError>>, Vec<RpcWarning>) {
         let mut warnings = Vec::new();
         if !self.println_spans.is_empty() {
@@ -137,6 +158,7 @@ impl ParsedRpcCollector {
 
 impl<'ast> visit::Visitor<'ast> for ParsedRpcCollector {
     fn visit_item(&mut self, item: &'ast ast::Item) {
+        debug!("visiting item `{}`", item.ident);
         match &item.kind {
             ast::ItemKind::Struct(_, generics) if item.ident.name == self.service_name => {
                 if !generics.params.is_empty() {
@@ -144,6 +166,16 @@ impl<'ast> visit::Visitor<'ast> for ParsedRpcCollector {
                 }
 
                 self.struct_span = Some(item.span);
+                self.derives_default = item.attrs.iter().any(|attr| match &attr.meta() {
+                    Some(ast::MetaItem {
+                        path,
+                        kind: ast::MetaItemKind::List(metas),
+                        ..
+                    }) if *path == Symbol::intern("derive") => metas.iter().any(|meta| {
+                        meta.ident().map(|ident| ident.name) == Some(Symbol::intern("Default"))
+                    }),
+                    _ => false,
+                });
             }
             ast::ItemKind::Impl {
                 of_trait: None,
@@ -156,9 +188,13 @@ impl<'ast> visit::Visitor<'ast> for ParsedRpcCollector {
             } =>
             {
                 for impl_item in impl_items {
+                    if attr_span(impl_item, "invariant").is_some() {
+                        self.invariants.push(impl_item.ident.name);
+                    }
                     match ParsedRpc::try_new_maybe(&service_ty, impl_item) {
                         None => (),
                         Some(Ok(rpc)) => {
+                            debug!("parsed RPC `{}::{}`", self.service_name, rpc.name);
                             self.rpcs.push(rpc);
 
                             let mut println_finder = PrintlnFinder::default();
@@ -169,7 +205,15 @@ impl<'ast> visit::Visitor<'ast> for ParsedRpcCollector {
                             );
                             self.println_spans.extend(&println_finder.println_spans);
                         }
-                        Some(Err(errs)) => self.errors.extend(errs),
+                        Some(Err(errs)) => {
+                            debug!(
+                                "RPC `{}::{}` has {} error(s)",
+                                self.service_name,
+                                impl_item.ident,
+                                errs.len()
+                            );
+                            self.errors.extend(errs)
+                        }
                     }
                 }
             }
@@ -202,11 +246,23 @@ impl<'ast> visit::Visitor<'ast> for PrintlnFinder {
     }
 }
 
+/// Replaces borrowed types with the owned types from which they can be borrowed,
+/// e.g., `&str` and `Cow<str>` become `String`, and `&[T]` and `Cow<[T]>` become `Vec<T>`.
 #[derive(Default)]
 pub struct Deborrower;
 
 impl mut_visit::MutVisitor for Deborrower {
     fn visit_ty(&mut self, ty: &mut P<ast::Ty>) {
+        if let Some(borrowed_ty) = crate::utils::cow_borrowed_ty(ty) {
+            let borrowed_ty = borrowed_ty.clone();
+            *ty = crate::utils::make_ty(ast::TyKind::Rptr(
+                None,
+                ast::MutTy {
+                    ty: borrowed_ty,
+                    mutbl: ast::Mutability::Not,
+                },
+            ));
+        }
         if let ast::TyKind::Rptr(_, ast::MutTy { ty: refd_ty, .. }) = &ty.kind {
             match &refd_ty.kind {
                 ast::TyKind::Path(None, path) => {
@@ -215,6 +271,8 @@ impl mut_visit::MutVisitor for Deborrower {
                             None,
                             ast::Path::from_ident(ast::Ident::from_str("String")),
                         ))
+                    } else {
+                        *ty = refd_ty.clone();
                     }
                 }
                 ast::TyKind::Slice(slice_ty) => {
@@ -228,7 +286,7 @@ impl mut_visit::MutVisitor for Deborrower {
                     )));
                     *ty = crate::utils::make_ty(ast::TyKind::Path(None, path));
                 }
-                _ => (),
+                _ => *ty = refd_ty.clone(),
             }
         }
         mut_visit::noop_visit_ty(ty, self);
@@ -31,7 +31,7 @@ fn find_deps(names: &[&str]) -> Vec<PathBuf> {
         .collect()
 }
 
-fn run_mode(mode: &'static str) {
+fn rustflags() -> String {
     let deps = &["borsh", "oasis_std", "oasis_macros", "oasis_types", "tests"];
     let externs = deps
         .iter()
@@ -40,17 +40,20 @@ fn run_mode(mode: &'static str) {
         .collect::<Vec<_>>()
         .join(" ");
 
-    let rustflags = format!(
+    format!(
         concat!(
             "--edition=2018 --cfg feature=\"oasis-build-compiletest\" --crate-type dylib {} -L",
             deps_dir!()
         ),
         externs
-    );
+    )
+}
+
+fn run_mode(mode: &'static str) {
     let config = compiletest_rs::Config {
         mode: mode.parse().expect("Invalid mode."),
         src_base: PathBuf::from(format!("tests/{}", mode.replace("-", "_"))),
-        target_rustcflags: Some(rustflags),
+        target_rustcflags: Some(rustflags()),
         rustc_path: PathBuf::from("oasis-build"),
         ..Default::default()
     }
@@ -63,3 +66,35 @@ fn run_mode(mode: &'static str) {
 fn compile_test() {
     run_mode("ui");
 }
+
+#[test]
+fn test_debug_logging() {
+    let out_dir = std::env::temp_dir().join("oasis-build-logging");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let output = std::process::Command::new("oasis-build")
+        .args(rustflags().split_whitespace())
+        .arg("--emit=metadata")
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tests/src/bin/types.rs"
+        ))
+        .env("RUST_LOG", "oasis_build=debug")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let logs = String::from_utf8(output.stderr).unwrap();
+    for line in &[
+        "visiting item `TestService`",
+        "found service `TestService`",
+        "found event `TestEvent` with indexed fields",
+        "parsed RPC `TestService::the`",
+        "argument `tuple_struct` has type Defined { namespace: None, ty: \"TupleStruct\" }",
+        "field `DefTy::f1` has type Optional(I64)",
+        "generated interface:\n{\n  \"name\": \"TestService\",",
+    ] {
+        assert!(logs.contains(line), "missing `{}` in logs:\n{}", line, logs);
+    }
+}
@@ -0,0 +1,26 @@
+use oasis_std::{abi::*, Context};
+
+#[derive(Serialize, Deserialize)]
+pub struct Pair<A, B = u8> {
+    first: A,
+    second: B,
+}
+
+#[derive(oasis_std::Service)]
+pub struct Pairs {
+    pairs: Vec<Pair<u32>>,
+}
+
+impl Pairs {
+    pub fn new(ctx: &Context) -> Self {
+        Self { pairs: Vec::new() }
+    }
+
+    pub fn push(&mut self, ctx: &Context, pair: Pair<u32>) {
+        self.pairs.push(pair);
+    }
+}
+
+fn main() {
+    oasis_std::service!(Pairs);
+}
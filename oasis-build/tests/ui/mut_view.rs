@@ -0,0 +1,21 @@
+use oasis_std::{view, Context, Service};
+
+#[derive(Service)]
+pub struct Counter(u32);
+
+impl Counter {
+    #[view]
+    pub fn new(_ctx: &Context) -> Self {
+        Self(0)
+    }
+
+    #[view]
+    pub fn count(&mut self, _ctx: &Context) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+fn main() {
+    oasis_std::service!(Counter);
+}
@@ -0,0 +1,35 @@
+use oasis_std::Context;
+
+#[derive(oasis_std::Service)]
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn new(ctx: &Context) -> Self {
+        Self { count: 0 }
+    }
+
+    pub fn incr(&mut self, ctx: &Context) -> u32 {
+        self.add(1)
+    }
+
+    fn decr(&mut self, ctx: &Context) -> u32 {
+        self.count -= 1;
+        self.count
+    }
+
+    #[allow(unknown_lints, non_pub_rpc)]
+    fn reset(&mut self, ctx: &Context) {
+        self.count = 0;
+    }
+
+    fn add(&mut self, amount: u32) -> u32 {
+        self.count += amount;
+        self.count
+    }
+}
+
+fn main() {
+    oasis_std::service!(Counter);
+}
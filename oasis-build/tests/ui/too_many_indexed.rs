@@ -0,0 +1,16 @@
+use oasis_std::{abi::*, Event};
+
+#[derive(Serialize, Deserialize, Event)]
+pub struct Overindexed {
+    #[indexed]
+    pub a: u8,
+    #[indexed]
+    pub b: u8,
+    pub c: u8,
+    #[indexed]
+    pub d: u8,
+    #[indexed]
+    pub e: u8,
+}
+
+fn main() {}
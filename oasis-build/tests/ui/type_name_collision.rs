@@ -0,0 +1,33 @@
+use oasis_std::{abi::*, Context};
+
+#[derive(Serialize, Deserialize)]
+pub struct Token(u64);
+
+#[derive(Serialize, Deserialize)]
+pub struct Ledger<T>(Vec<T>);
+
+#[derive(Serialize, Deserialize)]
+pub struct LedgerToken(u64);
+
+#[derive(oasis_std::Service)]
+pub struct Bank {
+    balance: u64,
+}
+
+impl Bank {
+    pub fn new(ctx: &Context) -> Self {
+        Self { balance: 0 }
+    }
+
+    pub fn ledger(&self, ctx: &Context) -> Ledger<Token> {
+        Ledger(Vec::new())
+    }
+
+    pub fn ledger_token(&self, ctx: &Context) -> LedgerToken {
+        LedgerToken(0)
+    }
+}
+
+fn main() {
+    oasis_std::service!(Bank);
+}
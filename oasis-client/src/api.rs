@@ -22,6 +22,24 @@ pub const SERVICE_EXECUTE_API: DeveloperGatewayApi = DeveloperGatewayApi {
     method: RequestMethod::POST,
 };
 
+/// This api call executes a read-only service function without creating a transaction.
+pub const SERVICE_QUERY_API: DeveloperGatewayApi = DeveloperGatewayApi {
+    url: "v0/api/service/query",
+    method: RequestMethod::POST,
+};
+
+/// This api call simulates a service function execution without persisting its effects.
+pub const SERVICE_ESTIMATE_API: DeveloperGatewayApi = DeveloperGatewayApi {
+    url: "v0/api/service/estimate",
+    method: RequestMethod::POST,
+};
+
+/// This api call retrieves the bytecode of a deployed service.
+pub const SERVICE_CODE_API: DeveloperGatewayApi = DeveloperGatewayApi {
+    url: "v0/api/service/code",
+    method: RequestMethod::POST,
+};
+
 /// This api call allows deploying a new service.
 pub const SERVICE_DEPLOY_API: DeveloperGatewayApi = DeveloperGatewayApi {
     url: "v0/api/service/deploy",
@@ -41,6 +59,14 @@ pub enum GatewayRequest {
     Deploy {
         /// Hex-encoded initcode.
         data: String,
+
+        /// The maximum amount of gas that the deployment may consume.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        gas: Option<u64>,
+
+        /// The value to transfer to the new service.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        value: Option<u128>,
     },
 
     ///  Used to trigger a service execution with user provided arguments.
@@ -50,6 +76,46 @@ pub enum GatewayRequest {
 
         /// Hex-encoded data that the user wants to pass to the service.
         data: String,
+
+        /// The maximum amount of gas that the execution may consume.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        gas: Option<u64>,
+
+        /// The value to transfer to the service.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        value: Option<u128>,
+    },
+
+    ///  Used to simulate a service execution. Like `Execute`, but no state is persisted.
+    Estimate {
+        /// Hex-encoded address where the service can be found.
+        address: String,
+
+        /// Hex-encoded data that the user wants to pass to the service.
+        data: String,
+
+        /// The maximum amount of gas that the execution may consume.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        gas: Option<u64>,
+
+        /// The value to transfer to the service.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        value: Option<u128>,
+    },
+
+    ///  Used to call a read-only service function. Queries do not consume gas.
+    Query {
+        /// Hex-encoded address where the service can be found.
+        address: String,
+
+        /// Hex-encoded data that the user wants to pass to the service.
+        data: String,
+    },
+
+    ///  Used to retrieve the bytecode of a deployed service.
+    Code {
+        /// Hex-encoded address where the service can be found.
+        address: String,
     },
 
     /// Request that allows the user to poll for the status asynchronous responses
@@ -110,6 +176,25 @@ pub enum Event {
         description: String,
     },
 
+    /// The event as a result of an asynchronous estimate request that has completed.
+    // This needs to be defined before `ExecuteService` due to field ambiguity.
+    EstimateService {
+        /// Identifier of the asynchronous response.
+        id: u64,
+
+        /// Hex-encoded address of the service that was called.
+        address: String,
+
+        /// Output that the service would generate, or its error output if it would revert.
+        output: String,
+
+        /// The gas that the execution would consume.
+        gas_used: u64,
+
+        /// Whether the execution would revert.
+        reverted: bool,
+    },
+
     /// The event as a result of an asynchronous service request that has succeeded.
     // This needs to be defined before `DeployService` due to field ambiguity.
     ExecuteService {
@@ -123,6 +208,19 @@ pub enum Event {
         output: String,
     },
 
+    /// The event as a result of an asynchronous code request that has succeeded.
+    // This needs to be defined before `DeployService` due to field ambiguity.
+    ServiceCode {
+        /// Identifier of the asynchronous response.
+        id: u64,
+
+        /// Hex-encoded address of the service.
+        address: String,
+
+        /// Hex-encoded bytecode of the service.
+        code: String,
+    },
+
     /// The event as a result of an asynchronous service request that has succeeded.
     DeployService {
         /// Identifier of the asynchronous response.
@@ -0,0 +1,556 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    compat::Future01CompatExt as _,
+    future::{self, BoxFuture, FutureExt as _, TryFutureExt as _},
+};
+use http::header::HeaderMap;
+use oasis_borsh::BorshDeserialize;
+use oasis_types::{Address, RpcError};
+
+use crate::{
+    api::*,
+    gateway::{api_url, fold_events, CallOptions, Estimate, Gateway, PollingParams},
+};
+
+/// The asynchronous counterpart of `Gateway`.
+///
+/// Every `Gateway` is also an `AsyncGateway` whose futures are already complete.
+/// Use a `BlockingGateway` to make an `AsyncGateway` usable where a `Gateway` is expected
+/// (e.g., by generated clients).
+pub trait AsyncGateway {
+    /// Deploys a new service with the provided initcode. @see `Gateway::deploy`.
+    fn deploy<'a>(
+        &'a self,
+        initcode: &'a [u8],
+        options: CallOptions,
+    ) -> BoxFuture<'a, Result<Address, RpcError>>;
+
+    /// Returns the output of calling the service at `address`. @see `Gateway::rpc`.
+    fn rpc<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>>;
+
+    /// Returns the output of a read-only call to the service at `address`.
+    /// @see `Gateway::query`.
+    fn query<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>> {
+        self.rpc(address, payload)
+    }
+
+    /// Simulates calling the service at `address`. @see `Gateway::estimate`.
+    fn estimate<'a>(
+        &'a self,
+        address: Address,
+        _payload: &'a [u8],
+        _options: CallOptions,
+    ) -> BoxFuture<'a, Result<Estimate, RpcError>> {
+        future::err(RpcError::Gateway(anyhow!(
+            "gateway cannot estimate calls to {}",
+            address
+        )))
+        .boxed()
+    }
+
+    /// Returns the bytecode of the service at `address`. @see `Gateway::code`.
+    fn code(&self, address: Address) -> BoxFuture<'_, Result<Vec<u8>, RpcError>> {
+        future::err(RpcError::Gateway(anyhow!(
+            "gateway cannot retrieve the code of {}",
+            address
+        )))
+        .boxed()
+    }
+
+    /// Returns the events emitted by the service at `address`. @see `Gateway::logs`.
+    fn logs(&self, address: Address) -> BoxFuture<'_, Result<Vec<oasis_types::Event>, RpcError>> {
+        future::err(RpcError::Gateway(anyhow!(
+            "gateway cannot retrieve the events of {}",
+            address
+        )))
+        .boxed()
+    }
+
+    /// Reconstructs state from the events emitted by the service at `address`.
+    /// @see `Gateway::replay_events`.
+    fn replay_events<'a, S, E, F>(
+        &'a self,
+        address: Address,
+        topic: [u8; 32],
+        init: S,
+        f: F,
+    ) -> BoxFuture<'a, Result<S, RpcError>>
+    where
+        Self: Sized,
+        S: Send + 'a,
+        E: BorshDeserialize,
+        F: Fn(&mut S, E) + Send + 'a,
+    {
+        self.logs(address)
+            .map(move |events| fold_events(events?, topic, init, f))
+            .boxed()
+    }
+}
+
+impl<G: Gateway + ?Sized> AsyncGateway for G {
+    fn deploy<'a>(
+        &'a self,
+        initcode: &'a [u8],
+        options: CallOptions,
+    ) -> BoxFuture<'a, Result<Address, RpcError>> {
+        future::ready(Gateway::deploy(self, initcode, options)).boxed()
+    }
+
+    fn rpc<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>> {
+        future::ready(Gateway::rpc(self, address, payload)).boxed()
+    }
+
+    fn query<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>> {
+        future::ready(Gateway::query(self, address, payload)).boxed()
+    }
+
+    fn estimate<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+        options: CallOptions,
+    ) -> BoxFuture<'a, Result<Estimate, RpcError>> {
+        future::ready(Gateway::estimate(self, address, payload, options)).boxed()
+    }
+
+    fn code(&self, address: Address) -> BoxFuture<'_, Result<Vec<u8>, RpcError>> {
+        future::ready(Gateway::code(self, address)).boxed()
+    }
+
+    fn logs(&self, address: Address) -> BoxFuture<'_, Result<Vec<oasis_types::Event>, RpcError>> {
+        future::ready(Gateway::logs(self, address)).boxed()
+    }
+}
+
+/// The asynchronous counterpart of `HttpGateway`, which waits for responses without
+/// blocking the current thread. Create one using `HttpGatewayBuilder::build_async`.
+///
+/// Requests are made using `reqwest`'s async client, so the futures must be run by a
+/// `tokio` runtime, as `BlockingGateway` does.
+///
+/// Like `HttpGateway`, it cannot retrieve event logs.
+///
+/// # Example
+///
+/// ```no_run
+/// use oasis_client::{AsyncGateway as _, HttpGatewayBuilder};
+///
+/// # async fn example() {
+/// let gateway = HttpGatewayBuilder::new("https://gateway.devnet.oasiscloud.io").build_async();
+/// let address = gateway
+///     .deploy(b"service Wasm bytecode", Default::default())
+///     .await
+///     .unwrap();
+/// let response = gateway.rpc(address, b"data").await.unwrap();
+/// # }
+/// ```
+pub struct AsyncHttpGateway {
+    pub(crate) url: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) client: reqwest::r#async::Client,
+    pub(crate) polling_params: PollingParams,
+    pub(crate) default_options: CallOptions,
+}
+
+impl AsyncHttpGateway {
+    /// Deploys a new service using the provided `options` in place of the defaults.
+    pub async fn deploy_with_options(
+        &self,
+        initcode: &[u8],
+        options: CallOptions,
+    ) -> Result<Address, RpcError> {
+        let body = GatewayRequest::deploy(initcode, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_DEPLOY_API, body)
+            .await
+            .and_then(Event::into_deployed_address)
+            .map_err(RpcError::Gateway)
+    }
+
+    /// Calls the service at `address` using the provided `options` in place of the defaults.
+    pub async fn rpc_with_options(
+        &self,
+        address: Address,
+        payload: &[u8],
+        options: CallOptions,
+    ) -> Result<Vec<u8>, RpcError> {
+        let body = GatewayRequest::execute(address, payload, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_EXECUTE_API, body)
+            .await
+            .and_then(Event::into_output)
+            .map_err(RpcError::Gateway)
+    }
+
+    /// Submit given request and poll for results.
+    async fn post_and_poll(
+        &self,
+        api: DeveloperGatewayApi<'_>,
+        body: GatewayRequest,
+    ) -> Result<Event> {
+        let response: AsyncResponse = self.request(api.method, api.url, body).await?;
+        self.poll_for_response(response.id).await?.into_result()
+    }
+
+    /// Repeatedly attempts to retrieve the event of the given request id, waiting
+    /// `sleep_duration` between attempts. If polling fails `max_attempts` times an
+    /// error is returned.
+    async fn poll_for_response(&self, request_id: u64) -> Result<Event> {
+        let PollingParams {
+            sleep_duration,
+            max_attempts,
+        } = self.polling_params;
+
+        for attempt in 0..max_attempts {
+            let events: PollEventResponse = self
+                .request(
+                    SERVICE_POLL_API.method,
+                    SERVICE_POLL_API.url,
+                    GatewayRequest::poll(request_id),
+                )
+                .await?;
+
+            // we polled for a singe event so we want the first event in the list, if it exists.
+            if let Some(e) = events.events.into_iter().next() {
+                return Ok(e);
+            }
+
+            info!(
+                "polling... (request id: {}, attempt: {})",
+                request_id, attempt
+            );
+
+            let wake_time =
+                std::time::Instant::now() + std::time::Duration::from_millis(sleep_duration);
+            tokio::timer::Delay::new(wake_time).compat().await?;
+        }
+        Err(anyhow!("Exceeded max polling attempts"))
+    }
+
+    /// Submits a request to the gateway. The body of the request is json-serialized and the
+    /// response is expected to be json-serialized as well.
+    async fn request<Q: serde::de::DeserializeOwned>(
+        &self,
+        method: RequestMethod,
+        url: &str,
+        payload: GatewayRequest,
+    ) -> Result<Q> {
+        let url = api_url(&self.url, url);
+        let builder = match method {
+            RequestMethod::GET => self.client.get(&url),
+            RequestMethod::POST => self.client.post(&url),
+        };
+
+        let mut res = builder
+            .headers(self.headers.clone())
+            .json(&payload)
+            .send()
+            .compat()
+            .await?;
+        if res.status().is_success() {
+            Ok(res.json().compat().await?)
+        } else {
+            Err(anyhow!("gateway returned error: {}", res.status()))
+        }
+    }
+}
+
+impl AsyncGateway for AsyncHttpGateway {
+    fn deploy<'a>(
+        &'a self,
+        initcode: &'a [u8],
+        options: CallOptions,
+    ) -> BoxFuture<'a, Result<Address, RpcError>> {
+        self.deploy_with_options(initcode, options).boxed()
+    }
+
+    fn rpc<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>> {
+        self.rpc_with_options(address, payload, CallOptions::default())
+            .boxed()
+    }
+
+    fn query<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<Vec<u8>, RpcError>> {
+        let body = GatewayRequest::query(address, payload);
+        self.post_and_poll(SERVICE_QUERY_API, body)
+            .map(|event| event?.into_output())
+            .map_err(RpcError::Gateway)
+            .boxed()
+    }
+
+    fn estimate<'a>(
+        &'a self,
+        address: Address,
+        payload: &'a [u8],
+        options: CallOptions,
+    ) -> BoxFuture<'a, Result<Estimate, RpcError>> {
+        let body =
+            GatewayRequest::estimate(address, payload, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_ESTIMATE_API, body)
+            .map(|event| event?.into_estimate())
+            .map_err(RpcError::Gateway)
+            .boxed()
+    }
+
+    fn code(&self, address: Address) -> BoxFuture<'_, Result<Vec<u8>, RpcError>> {
+        self.post_and_poll(SERVICE_CODE_API, GatewayRequest::code(address))
+            .map(|event| event?.into_code())
+            .map_err(RpcError::Gateway)
+            .boxed()
+    }
+}
+
+/// A `Gateway` that blocks the current thread on each request made to an `AsyncGateway`.
+/// Each request is run to completion on its own `tokio` runtime, which drives the
+/// network requests of an `AsyncHttpGateway`. This is useful for scripts and tests.
+///
+/// # Example
+///
+/// ```no_run
+/// # use oasis_client::{BlockingGateway, Gateway as _, HttpGatewayBuilder};
+/// let async_gateway =
+///     HttpGatewayBuilder::new("https://gateway.devnet.oasiscloud.io").build_async();
+/// let gateway = BlockingGateway::new(async_gateway);
+/// let address = gateway
+///     .deploy(b"service Wasm bytecode", Default::default())
+///     .unwrap();
+/// let response = gateway.rpc(address, b"data").unwrap();
+/// ```
+pub struct BlockingGateway<G> {
+    inner: G,
+}
+
+impl<G: AsyncGateway> BlockingGateway<G> {
+    pub fn new(inner: G) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped `AsyncGateway`.
+    pub fn inner(&self) -> &G {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> G {
+        self.inner
+    }
+
+    fn block_on<T>(&self, fut: BoxFuture<'_, Result<T, RpcError>>) -> Result<T, RpcError> {
+        let mut runtime = tokio::runtime::current_thread::Runtime::new()
+            .map_err(|err| RpcError::Gateway(err.into()))?;
+        runtime.block_on(fut.compat())
+    }
+}
+
+impl<G: AsyncGateway> Gateway for BlockingGateway<G> {
+    fn deploy(&self, initcode: &[u8], options: CallOptions) -> Result<Address, RpcError> {
+        self.block_on(self.inner.deploy(initcode, options))
+    }
+
+    fn rpc(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        self.block_on(self.inner.rpc(address, payload))
+    }
+
+    fn query(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        self.block_on(self.inner.query(address, payload))
+    }
+
+    fn estimate(
+        &self,
+        address: Address,
+        payload: &[u8],
+        options: CallOptions,
+    ) -> Result<Estimate, RpcError> {
+        self.block_on(self.inner.estimate(address, payload, options))
+    }
+
+    fn code(&self, address: Address) -> Result<Vec<u8>, RpcError> {
+        self.block_on(self.inner.code(address))
+    }
+
+    fn logs(&self, address: Address) -> Result<Vec<oasis_types::Event>, RpcError> {
+        self.block_on(self.inner.logs(address))
+    }
+}
+
+#[cfg(all(test, not(target_env = "sgx")))]
+mod tests {
+    use super::*;
+
+    use mockito::mock;
+    use serde_json::json;
+
+    use crate::HttpGatewayBuilder;
+
+    const FIXTURE_ADDR: &str = "0xb8b3666d8fea887d97ab54f571b8e5020c5c8b58";
+
+    fn mock_request(path: &str, body: serde_json::Value, poll_id: u64) -> mockito::Mock {
+        mock("POST", path)
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(body))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "id": poll_id }).to_string())
+            .expect(1)
+            .create()
+    }
+
+    fn mock_poll(poll_id: u64, event: serde_json::Value) -> mockito::Mock {
+        mock("POST", "/v0/api/service/poll")
+            .match_body(mockito::Matcher::Json(json!({
+                "offset": poll_id,
+                "count": 1,
+                "discard_previous": true,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "offset": poll_id, "events": [event] }).to_string())
+            .create()
+    }
+
+    #[test]
+    fn test_async_http_gateway() {
+        let fixture_addr: Address = FIXTURE_ADDR[2..].parse().unwrap();
+
+        let m_execute = mock_request(
+            "/v0/api/service/execute",
+            json!({ "address": FIXTURE_ADDR, "data": "0x01", "gas": 100 }),
+            48,
+        );
+        let _m_execute_poll = mock_poll(
+            48,
+            json!({ "id": 48, "address": FIXTURE_ADDR, "output": "0x2a" }),
+        );
+        let m_estimate = mock_request(
+            "/v0/api/service/estimate",
+            json!({ "address": FIXTURE_ADDR, "data": "0x02", "gas": 100 }),
+            49,
+        );
+        let _m_estimate_poll = mock_poll(
+            49,
+            json!({
+                "id": 49,
+                "address": FIXTURE_ADDR,
+                "output": "0x",
+                "gas_used": 2_100,
+                "reverted": false,
+            }),
+        );
+        let m_code = mock_request(
+            "/v0/api/service/code",
+            json!({ "address": FIXTURE_ADDR }),
+            51,
+        );
+        let _m_code_poll = mock_poll(
+            51,
+            json!({ "id": 51, "address": FIXTURE_ADDR, "code": "0x0061736d" }),
+        );
+
+        let gateway = BlockingGateway::new(
+            HttpGatewayBuilder::new(mockito::server_url())
+                .default_gas(100)
+                .build_async(),
+        );
+        assert_eq!(
+            Gateway::rpc(&gateway, fixture_addr, &[1]).unwrap(),
+            vec![42]
+        );
+        assert_eq!(
+            Gateway::estimate(&gateway, fixture_addr, &[2], CallOptions::default()).unwrap(),
+            Estimate {
+                output: Vec::new(),
+                gas_used: 2_100,
+                reverted: false,
+            }
+        );
+        assert_eq!(
+            Gateway::code(&gateway, fixture_addr).unwrap(),
+            b"\0asm".to_vec()
+        );
+        m_execute.assert();
+        m_estimate.assert();
+        m_code.assert();
+    }
+
+    const AMOUNT_TOPIC: [u8; 32] = [1u8; 32];
+
+    struct EventGateway;
+
+    impl Gateway for EventGateway {
+        fn deploy(&self, _initcode: &[u8], _options: CallOptions) -> Result<Address, RpcError> {
+            Err(RpcError::InvalidInput(None))
+        }
+
+        fn rpc(&self, _address: Address, _payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+            Err(RpcError::InvalidCallee)
+        }
+
+        fn code(&self, _address: Address) -> Result<Vec<u8>, RpcError> {
+            Ok(b"code".to_vec())
+        }
+
+        fn logs(&self, address: Address) -> Result<Vec<oasis_types::Event>, RpcError> {
+            let other_event = oasis_types::Event {
+                emitter: address,
+                topics: vec![[2u8; 32]],
+                data: b"not an amount".to_vec(),
+            };
+            Ok((1..=3u32)
+                .map(|amount| oasis_types::Event {
+                    emitter: address,
+                    topics: vec![AMOUNT_TOPIC],
+                    data: amount.to_le_bytes().to_vec(),
+                })
+                .chain(std::iter::once(other_event))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_blocking_gateway_forwards() {
+        let address = Address([1u8; 20]);
+
+        let total = futures::executor::block_on(AsyncGateway::replay_events(
+            &EventGateway,
+            address,
+            AMOUNT_TOPIC,
+            0,
+            |total, amount: u32| *total += amount,
+        ))
+        .unwrap();
+        assert_eq!(total, 6);
+
+        let gateway = BlockingGateway::new(EventGateway);
+        assert_eq!(Gateway::code(&gateway, address).unwrap(), b"code");
+        assert_eq!(Gateway::logs(&gateway, address).unwrap().len(), 4);
+        assert_eq!(
+            Gateway::replay_events(&gateway, address, AMOUNT_TOPIC, 0, |total, amount: u32| {
+                *total += amount
+            })
+            .unwrap(),
+            6
+        );
+        match Gateway::estimate(&gateway, address, &[], CallOptions::default()) {
+            Err(RpcError::Gateway(_)) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
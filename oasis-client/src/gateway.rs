@@ -2,7 +2,8 @@ use std::str::FromStr as _;
 
 use anyhow::{anyhow, Result};
 use http::header::{HeaderMap, HeaderName, HeaderValue};
-use oasis_types::{Address, RpcError};
+use oasis_borsh::BorshDeserialize;
+use oasis_types::{Address, Balance, RpcError};
 use uuid::Uuid;
 
 #[cfg(not(target_env = "sgx"))]
@@ -13,15 +14,140 @@ use crate::api::*;
 pub trait Gateway {
     /// Deploys a new service with the provided initcode.
     /// `initcode` is expected to be the Wasm bytecode concatenated with the the constructor stdin.
+    /// `options` are the gas and value of the deploying `Context`, which gateways that
+    /// configure defaults use in place of their own.
     /// Upon success, returns the address of the new service.
-    fn deploy(&self, initcode: &[u8]) -> Result<Address, RpcError>;
+    fn deploy(&self, initcode: &[u8], options: CallOptions) -> Result<Address, RpcError>;
 
     /// Returns the output of calling the service at `address` with `data` as stdin.
     fn rpc(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError>;
+
+    /// Returns the output of calling a view (`&self`) method of the service at `address`.
+    /// Unlike `rpc`, a query is read-only: it does not create a transaction or cost gas.
+    /// Gateways that cannot make read-only calls send the query as an `rpc`.
+    fn query(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        self.rpc(address, payload)
+    }
+
+    /// Simulates calling the service at `address` with `payload` without persisting any state,
+    /// and returns the would-be output, gas used, and whether the call would revert.
+    /// Gateways that cannot simulate calls return an `RpcError::Gateway`.
+    fn estimate(
+        &self,
+        address: Address,
+        _payload: &[u8],
+        _options: CallOptions,
+    ) -> Result<Estimate, RpcError> {
+        Err(RpcError::Gateway(anyhow!(
+            "gateway cannot estimate calls to {}",
+            address
+        )))
+    }
+
+    /// Returns the bytecode of the service at `address`.
+    /// Gateways that cannot retrieve code return an `RpcError::Gateway`.
+    fn code(&self, address: Address) -> Result<Vec<u8>, RpcError> {
+        Err(RpcError::Gateway(anyhow!(
+            "gateway cannot retrieve the code of {}",
+            address
+        )))
+    }
+
+    /// Returns the events emitted by the service at `address`, oldest first.
+    /// Events emitted by reverted transactions are not included.
+    /// Gateways that cannot retrieve events return an `RpcError::Gateway`.
+    fn logs(&self, address: Address) -> Result<Vec<oasis_types::Event>, RpcError> {
+        Err(RpcError::Gateway(anyhow!(
+            "gateway cannot retrieve the events of {}",
+            address
+        )))
+    }
+
+    /// Reconstructs state from the events emitted by the service at `address` by decoding
+    /// each event as an `E` and folding it, oldest first, into `init` using `f`.
+    /// Only events whose first topic is `topic` are replayed. For events emitted using
+    /// `oasis_std::Event`, this is the `topic_hash` of the event type.
+    /// Returns `RpcError::InvalidOutput` containing the event data if an event is not an `E`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use oasis_borsh::BorshDeserialize;
+    /// # use oasis_client::Gateway;
+    /// #[derive(BorshDeserialize)]
+    /// struct Transferred {
+    ///     amount: u64,
+    /// }
+    ///
+    /// # fn example(gateway: impl Gateway, transferred_topic: [u8; 32]) {
+    /// # let address = oasis_types::Address::default();
+    /// let total_transferred = gateway
+    ///     .replay_events(
+    ///         address,
+    ///         transferred_topic,
+    ///         0,
+    ///         |total, event: Transferred| *total += event.amount,
+    ///     )
+    ///     .unwrap();
+    /// # }
+    /// ```
+    fn replay_events<S, E, F>(
+        &self,
+        address: Address,
+        topic: [u8; 32],
+        init: S,
+        f: F,
+    ) -> Result<S, RpcError>
+    where
+        Self: Sized,
+        E: BorshDeserialize,
+        F: Fn(&mut S, E),
+    {
+        fold_events(self.logs(address)?, topic, init, f)
+    }
+}
+
+/// Decodes each of the `events` whose first topic is `topic` as an `E` and folds it
+/// into `init` using `f`. @see `Gateway::replay_events`.
+pub(crate) fn fold_events<S, E, F>(
+    events: Vec<oasis_types::Event>,
+    topic: [u8; 32],
+    init: S,
+    f: F,
+) -> Result<S, RpcError>
+where
+    E: BorshDeserialize,
+    F: Fn(&mut S, E),
+{
+    events
+        .into_iter()
+        .filter(|event| event.topics.first() == Some(&topic))
+        .try_fold(init, |mut state, event| {
+            let decoded =
+                E::try_from_slice(&event.data).map_err(|_| RpcError::InvalidOutput(event.data))?;
+            f(&mut state, decoded);
+            Ok(state)
+        })
+}
+
+/// The simulated result of calling a service. @see `Gateway::estimate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Estimate {
+    /// The output of the call or, if the call would revert, its error output.
+    pub output: Vec<u8>,
+
+    /// The gas that the call would consume.
+    pub gas_used: u64,
+
+    /// Whether the call would revert.
+    pub reverted: bool,
 }
 
 /// Holds necessary information to make http requests to the gateway.
 ///
+/// The developer gateway does not serve event logs, so `logs` and `replay_events`
+/// return an `RpcError::Gateway`.
+///
 /// # Example
 ///
 /// ```no_run
@@ -32,7 +158,9 @@ pub trait Gateway {
 /// let gateway = oasis_client::HttpGatewayBuilder::new(url)
 ///     .api_key(api_key)
 ///     .build();
-/// let address = gateway.deploy(b"service Wasm bytecode").unwrap();
+/// let address = gateway
+///     .deploy(b"service Wasm bytecode", Default::default())
+///     .unwrap();
 /// let response = gateway.rpc(address, b"data").unwrap();
 /// ```
 pub struct HttpGateway {
@@ -47,6 +175,9 @@ pub struct HttpGateway {
 
     /// A polling service used to receive responses from asynchronous requests made.
     polling_params: PollingParams,
+
+    /// The gas and value used by requests that do not override them.
+    default_options: CallOptions,
 }
 
 #[derive(Clone, Debug)]
@@ -54,7 +185,9 @@ pub struct HttpGatewayBuilder {
     url: String,
     api_key: Option<String>,
     headers: HeaderMap,
+    client: Option<Client>,
     polling_params: PollingParams,
+    default_options: CallOptions,
 }
 
 impl HttpGatewayBuilder {
@@ -86,12 +219,31 @@ impl HttpGatewayBuilder {
         self
     }
 
+    /// Send requests using `client`, e.g., one configured with custom TLS settings or a proxy.
+    /// The builder's headers are still sent with every request.
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
     /// Set the polling parameters for the `HttpGateway`.
     pub fn polling_params(mut self, params: PollingParams) -> Self {
         self.polling_params = params;
         self
     }
 
+    /// Set the gas limit sent with requests that do not specify their own.
+    pub fn default_gas(mut self, gas: u64) -> Self {
+        self.default_options.gas = Some(gas);
+        self
+    }
+
+    /// Set the value sent with requests that do not specify their own.
+    pub fn default_value(mut self, value: Balance) -> Self {
+        self.default_options.value = Some(value);
+        self
+    }
+
     /// Creates a new `HttpGateway` instance that is configured with headers expected by the
     /// Oasis Developer gateway.
     pub fn build(self) -> HttpGateway {
@@ -110,7 +262,26 @@ impl HttpGatewayBuilder {
             HeaderValue::from_str(&session_key).unwrap(),
         );
 
-        HttpGateway::new(self.url, headers, self.polling_params)
+        HttpGateway {
+            url: self.url,
+            headers,
+            client: self.client.unwrap_or_else(Client::new),
+            polling_params: self.polling_params,
+            default_options: self.default_options,
+        }
+    }
+
+    /// Creates a new `AsyncHttpGateway` configured like the one returned by `build`.
+    /// A client set using `with_client` is not used, as it cannot make async requests.
+    pub fn build_async(self) -> crate::AsyncHttpGateway {
+        let gateway = self.build();
+        crate::AsyncHttpGateway {
+            url: gateway.url,
+            headers: gateway.headers,
+            client: reqwest::r#async::Client::new(),
+            polling_params: gateway.polling_params,
+            default_options: gateway.default_options,
+        }
     }
 }
 
@@ -120,11 +291,33 @@ impl Default for HttpGatewayBuilder {
             url: "https://gateway.devnet.oasiscloud.io".to_string(),
             api_key: None,
             headers: HeaderMap::new(),
+            client: None,
             polling_params: PollingParams::default(),
+            default_options: CallOptions::default(),
         }
     }
 }
 
+/// Per-request overrides of the gas and value configured on the `HttpGatewayBuilder`.
+/// Fields that are `None` fall back to the gateway's defaults.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CallOptions {
+    /// The maximum amount of gas that the request may consume.
+    pub gas: Option<u64>,
+
+    /// The value to transfer along with the request.
+    pub value: Option<Balance>,
+}
+
+impl CallOptions {
+    /// Returns the gas and value of a request, preferring those in `options` to these defaults.
+    pub(crate) fn resolve(&self, options: CallOptions) -> (Option<u64>, Option<u128>) {
+        let gas = options.gas.or(self.gas);
+        let value = options.value.or(self.value);
+        (gas, value.map(|value| value.0))
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct PollingParams {
     /// Interval between sending requests in milliseconds.
@@ -151,17 +344,39 @@ impl HttpGateway {
             headers,
             client: Client::new(),
             polling_params,
+            default_options: CallOptions::default(),
         }
     }
 
+    /// Deploys a new service using the provided `options` in place of the defaults.
+    pub fn deploy_with_options(
+        &self,
+        initcode: &[u8],
+        options: CallOptions,
+    ) -> std::result::Result<Address, RpcError> {
+        let body = GatewayRequest::deploy(initcode, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_DEPLOY_API, body)
+            .and_then(Event::into_deployed_address)
+            .map_err(RpcError::Gateway)
+    }
+
+    /// Calls the service at `address` using the provided `options` in place of the defaults.
+    pub fn rpc_with_options(
+        &self,
+        address: Address,
+        payload: &[u8],
+        options: CallOptions,
+    ) -> std::result::Result<Vec<u8>, RpcError> {
+        let body = GatewayRequest::execute(address, payload, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_EXECUTE_API, body)
+            .and_then(Event::into_output)
+            .map_err(RpcError::Gateway)
+    }
+
     /// Submit given request asynchronously and poll for results.
     fn post_and_poll(&self, api: DeveloperGatewayApi, body: GatewayRequest) -> Result<Event> {
         let response: AsyncResponse = self.request(api.method, api.url, body)?;
-
-        match self.poll_for_response(response.id)? {
-            Event::Error { description, .. } => Err(anyhow!("{}", description)),
-            e => Ok(e),
-        }
+        self.poll_for_response(response.id)?.into_result()
     }
 
     /// Synchronous polling. Repeatedly attempts to retrieve the event of the given
@@ -172,11 +387,7 @@ impl HttpGateway {
             max_attempts,
         } = self.polling_params;
 
-        let poll_request = GatewayRequest::Poll {
-            offset: request_id,
-            count: 1, // poll for a single event
-            discard_previous: true,
-        };
+        let poll_request = GatewayRequest::poll(request_id);
 
         for attempt in 0..max_attempts {
             let events: PollEventResponse = self.request(
@@ -220,11 +431,7 @@ impl HttpGateway {
         url: &str,
         payload: P,
     ) -> Result<Q> {
-        let url = if self.url.ends_with('/') {
-            format!("{}{}", self.url, url)
-        } else {
-            format!("{}/{}", self.url, url)
-        };
+        let url = api_url(&self.url, url);
         let builder = match method {
             RequestMethod::GET => self.client.get(&url),
             RequestMethod::POST => self.client.post(&url),
@@ -243,40 +450,165 @@ impl HttpGateway {
 }
 
 impl Gateway for HttpGateway {
-    fn deploy(&self, initcode: &[u8]) -> std::result::Result<Address, RpcError> {
-        let initcode_hex = hex::encode(initcode);
-        info!("deploying service `{}`", &initcode_hex[..32]);
+    fn deploy(
+        &self,
+        initcode: &[u8],
+        options: CallOptions,
+    ) -> std::result::Result<Address, RpcError> {
+        self.deploy_with_options(initcode, options)
+    }
 
-        let body = GatewayRequest::Deploy {
-            data: format!("0x{}", initcode_hex),
-        };
+    fn rpc(&self, address: Address, payload: &[u8]) -> std::result::Result<Vec<u8>, RpcError> {
+        self.rpc_with_options(address, payload, CallOptions::default())
+    }
 
-        self.post_and_poll(SERVICE_DEPLOY_API, body)
-            .and_then(|event| {
-                match event {
-                    Event::DeployService { address, .. } => {
-                        Ok(Address::from_str(&address[2..] /* strip 0x */)?)
-                    }
-                    e => Err(anyhow!("expecting `DeployService` event. got {:?}", e)),
-                }
-            })
+    fn query(&self, address: Address, payload: &[u8]) -> std::result::Result<Vec<u8>, RpcError> {
+        let body = GatewayRequest::query(address, payload);
+        self.post_and_poll(SERVICE_QUERY_API, body)
+            .and_then(Event::into_output)
             .map_err(RpcError::Gateway)
     }
 
-    fn rpc(&self, address: Address, payload: &[u8]) -> std::result::Result<Vec<u8>, RpcError> {
+    fn estimate(
+        &self,
+        address: Address,
+        payload: &[u8],
+        options: CallOptions,
+    ) -> std::result::Result<Estimate, RpcError> {
+        let body =
+            GatewayRequest::estimate(address, payload, self.default_options.resolve(options));
+        self.post_and_poll(SERVICE_ESTIMATE_API, body)
+            .and_then(Event::into_estimate)
+            .map_err(RpcError::Gateway)
+    }
+
+    fn code(&self, address: Address) -> std::result::Result<Vec<u8>, RpcError> {
+        self.post_and_poll(SERVICE_CODE_API, GatewayRequest::code(address))
+            .and_then(Event::into_code)
+            .map_err(RpcError::Gateway)
+    }
+}
+
+/// Returns the url of the `api` endpoint of the gateway at `gateway_url`.
+pub(crate) fn api_url(gateway_url: &str, api: &str) -> String {
+    if gateway_url.ends_with('/') {
+        format!("{}{}", gateway_url, api)
+    } else {
+        format!("{}/{}", gateway_url, api)
+    }
+}
+
+// The requests and responses of the gateway's api are shared by `HttpGateway` and
+// `AsyncHttpGateway`, which differ only in how they are sent.
+impl GatewayRequest {
+    pub(crate) fn deploy(initcode: &[u8], (gas, value): (Option<u64>, Option<u128>)) -> Self {
+        let initcode_hex = hex::encode(initcode);
+        info!("deploying service `{}`", &initcode_hex[..32]);
+        GatewayRequest::Deploy {
+            data: format!("0x{}", initcode_hex),
+            gas,
+            value,
+        }
+    }
+
+    pub(crate) fn execute(
+        address: Address,
+        payload: &[u8],
+        (gas, value): (Option<u64>, Option<u128>),
+    ) -> Self {
         info!("making RPC to {}", address);
+        GatewayRequest::Execute {
+            address: address.to_string(),
+            data: format!("0x{}", hex::encode(payload)),
+            gas,
+            value,
+        }
+    }
 
-        let body = GatewayRequest::Execute {
+    pub(crate) fn query(address: Address, payload: &[u8]) -> Self {
+        info!("making query to {}", address);
+        GatewayRequest::Query {
             address: address.to_string(),
             data: format!("0x{}", hex::encode(payload)),
-        };
+        }
+    }
 
-        self.post_and_poll(SERVICE_EXECUTE_API, body)
-            .and_then(|event| match event {
-                Event::ExecuteService { output, .. } => Ok(dbg!(hex::decode(&output[2..])?)),
-                e => Err(anyhow!("expecting `ExecuteService` event. got {:?}", e)),
-            })
-            .map_err(RpcError::Gateway)
+    pub(crate) fn estimate(
+        address: Address,
+        payload: &[u8],
+        (gas, value): (Option<u64>, Option<u128>),
+    ) -> Self {
+        info!("estimating RPC to {}", address);
+        GatewayRequest::Estimate {
+            address: address.to_string(),
+            data: format!("0x{}", hex::encode(payload)),
+            gas,
+            value,
+        }
+    }
+
+    pub(crate) fn code(address: Address) -> Self {
+        info!("retrieving code of {}", address);
+        GatewayRequest::Code {
+            address: address.to_string(),
+        }
+    }
+
+    pub(crate) fn poll(request_id: u64) -> Self {
+        GatewayRequest::Poll {
+            offset: request_id,
+            count: 1, // poll for a single event
+            discard_previous: true,
+        }
+    }
+}
+
+impl Event {
+    /// Returns the event of a completed request, or the error of a failed one.
+    pub(crate) fn into_result(self) -> Result<Event> {
+        match self {
+            Event::Error { description, .. } => Err(anyhow!("{}", description)),
+            e => Ok(e),
+        }
+    }
+
+    pub(crate) fn into_deployed_address(self) -> Result<Address> {
+        match self {
+            Event::DeployService { address, .. } => {
+                Ok(Address::from_str(&address[2..] /* strip 0x */)?)
+            }
+            e => Err(anyhow!("expecting `DeployService` event. got {:?}", e)),
+        }
+    }
+
+    pub(crate) fn into_output(self) -> Result<Vec<u8>> {
+        match self {
+            Event::ExecuteService { output, .. } => Ok(hex::decode(&output[2..])?),
+            e => Err(anyhow!("expecting `ExecuteService` event. got {:?}", e)),
+        }
+    }
+
+    pub(crate) fn into_estimate(self) -> Result<Estimate> {
+        match self {
+            Event::EstimateService {
+                output,
+                gas_used,
+                reverted,
+                ..
+            } => Ok(Estimate {
+                output: hex::decode(&output[2..])?,
+                gas_used,
+                reverted,
+            }),
+            e => Err(anyhow!("expecting `EstimateService` event. got {:?}", e)),
+        }
+    }
+
+    pub(crate) fn into_code(self) -> Result<Vec<u8>> {
+        match self {
+            Event::ServiceCode { code, .. } => Ok(hex::decode(&code[2..])?),
+            e => Err(anyhow!("expecting `ServiceCode` event. got {:?}", e)),
+        }
     }
 }
 
@@ -329,7 +661,10 @@ mod tests {
             .api_key(API_KEY)
             .build();
         let addr = gateway
-            .deploy(&hex::decode(&PAYLOAD_HEX[2..]).unwrap())
+            .deploy(
+                &hex::decode(&PAYLOAD_HEX[2..]).unwrap(),
+                CallOptions::default(),
+            )
             .unwrap();
 
         assert_eq!(addr, fixture_addr);
@@ -426,4 +761,271 @@ mod tests {
 
         assert!(err_output.to_string().contains(err_msg))
     }
+
+    #[test]
+    fn test_rpc_gas() {
+        let fixture_addr = Address::from_str(&FIXTURE_ADDR[2..]).unwrap();
+        let default_gas = 100_000;
+        let override_gas = 5_000;
+
+        let mock_execute = |gas: u64, poll_id: u64| {
+            mock("POST", "/v0/api/service/execute")
+                .match_header("content-type", "application/json")
+                .match_body(mockito::Matcher::Json(json!({
+                    "address": FIXTURE_ADDR,
+                    "data": PAYLOAD_HEX,
+                    "gas": gas,
+                    "value": 7,
+                })))
+                .with_header("content-type", "text/json")
+                .with_body(json!({ "id": poll_id }).to_string())
+                .expect(1)
+                .create()
+        };
+        let mock_poll = |poll_id: u64| {
+            mock("POST", "/v0/api/service/poll")
+                .match_header("content-type", "application/json")
+                .match_body(mockito::Matcher::Json(json!({
+                    "offset": poll_id,
+                    "count": 1,
+                    "discard_previous": true,
+                })))
+                .with_header("content-type", "text/json")
+                .with_body(
+                    json!({
+                        "offset": poll_id,
+                        "events": [
+                            { "id": poll_id, "address": FIXTURE_ADDR, "output": "0x" }
+                        ]
+                    })
+                    .to_string(),
+                )
+                .create()
+        };
+
+        let m_default = mock_execute(default_gas, 43);
+        let _m_default_poll = mock_poll(43);
+        let m_override = mock_execute(override_gas, 44);
+        let _m_override_poll = mock_poll(44);
+
+        let gateway = HttpGatewayBuilder::new(mockito::server_url())
+            .default_gas(default_gas)
+            .default_value(Balance(7))
+            .build();
+        let payload = hex::decode(&PAYLOAD_HEX[2..]).unwrap();
+
+        gateway.rpc(fixture_addr, &payload).unwrap();
+        m_default.assert();
+
+        gateway
+            .rpc_with_options(
+                fixture_addr,
+                &payload,
+                CallOptions {
+                    gas: Some(override_gas),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        m_override.assert();
+    }
+
+    #[test]
+    fn test_query() {
+        let fixture_addr = Address::from_str(&FIXTURE_ADDR[2..]).unwrap();
+        let poll_id = 45;
+
+        let m_query = mock("POST", "/v0/api/service/query")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({
+                "address": FIXTURE_ADDR,
+                "data": PAYLOAD_HEX,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "id": poll_id }).to_string())
+            .expect(1)
+            .create();
+        let _m_poll = mock("POST", "/v0/api/service/poll")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({
+                "offset": poll_id,
+                "count": 1,
+                "discard_previous": true,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(
+                json!({
+                    "offset": poll_id,
+                    "events": [
+                        { "id": poll_id, "address": FIXTURE_ADDR, "output": "0x2a" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let gateway = HttpGatewayBuilder::new(mockito::server_url())
+            .default_gas(100_000)
+            .build();
+        let output = gateway
+            .query(fixture_addr, &hex::decode(&PAYLOAD_HEX[2..]).unwrap())
+            .unwrap();
+
+        assert_eq!(output, vec![42]);
+        m_query.assert();
+    }
+
+    #[test]
+    fn test_estimate() {
+        let fixture_addr = Address::from_str(&FIXTURE_ADDR[2..]).unwrap();
+        let poll_id = 46;
+
+        let m_estimate = mock("POST", "/v0/api/service/estimate")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({
+                "address": FIXTURE_ADDR,
+                "data": PAYLOAD_HEX,
+                "gas": 5_000,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "id": poll_id }).to_string())
+            .expect(1)
+            .create();
+        let _m_poll = mock("POST", "/v0/api/service/poll")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({
+                "offset": poll_id,
+                "count": 1,
+                "discard_previous": true,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(
+                json!({
+                    "offset": poll_id,
+                    "events": [{
+                        "id": poll_id,
+                        "address": FIXTURE_ADDR,
+                        "output": "0x2a",
+                        "gas_used": 2_100,
+                        "reverted": true,
+                    }]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let gateway = HttpGatewayBuilder::new(mockito::server_url()).build();
+        let estimate = gateway
+            .estimate(
+                fixture_addr,
+                &hex::decode(&PAYLOAD_HEX[2..]).unwrap(),
+                CallOptions {
+                    gas: Some(5_000),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            estimate,
+            Estimate {
+                output: vec![42],
+                gas_used: 2_100,
+                reverted: true,
+            }
+        );
+        m_estimate.assert();
+    }
+
+    #[test]
+    fn test_code() {
+        let fixture_addr = Address::from_str(&FIXTURE_ADDR[2..]).unwrap();
+        let poll_id = 50;
+
+        let m_code = mock("POST", "/v0/api/service/code")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({ "address": FIXTURE_ADDR })))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "id": poll_id }).to_string())
+            .expect(1)
+            .create();
+        let _m_poll = mock("POST", "/v0/api/service/poll")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({
+                "offset": poll_id,
+                "count": 1,
+                "discard_previous": true,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(
+                json!({
+                    "offset": poll_id,
+                    "events": [
+                        { "id": poll_id, "address": FIXTURE_ADDR, "code": PAYLOAD_HEX }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let gateway = HttpGatewayBuilder::new(mockito::server_url()).build();
+        let code = gateway.code(fixture_addr).unwrap();
+
+        assert_eq!(code, hex::decode(&PAYLOAD_HEX[2..]).unwrap());
+        m_code.assert();
+    }
+
+    #[test]
+    fn test_custom_header_and_client() {
+        let fixture_addr = Address::from_str(&FIXTURE_ADDR[2..]).unwrap();
+        let poll_id = 47;
+        let auth = "Bearer hunter2";
+
+        let m_execute = mock("POST", "/v0/api/service/execute")
+            .match_header("authorization", auth)
+            .match_body(mockito::Matcher::Json(json!({
+                "address": FIXTURE_ADDR,
+                "data": PAYLOAD_HEX,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(json!({ "id": poll_id }).to_string())
+            .expect(1)
+            .create();
+        let m_poll = mock("POST", "/v0/api/service/poll")
+            .match_header("authorization", auth)
+            .match_body(mockito::Matcher::Json(json!({
+                "offset": poll_id,
+                "count": 1,
+                "discard_previous": true,
+            })))
+            .with_header("content-type", "text/json")
+            .with_body(
+                json!({
+                    "offset": poll_id,
+                    "events": [
+                        { "id": poll_id, "address": FIXTURE_ADDR, "output": "0x2a" }
+                    ]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let gateway = HttpGatewayBuilder::new(mockito::server_url())
+            .header("Authorization", auth)
+            .unwrap()
+            .with_client(client)
+            .build();
+        let output = gateway
+            .rpc(fixture_addr, &hex::decode(&PAYLOAD_HEX[2..]).unwrap())
+            .unwrap();
+
+        assert_eq!(output, vec![42]);
+        m_execute.assert();
+        m_poll.assert();
+    }
 }
@@ -2,6 +2,12 @@
 extern crate log;
 
 pub mod api;
+mod async_gateway;
 pub mod gateway;
+#[cfg(feature = "memchain-gateway")]
+mod memchain_gateway;
 
-pub use gateway::{Gateway, HttpGateway, HttpGatewayBuilder};
+pub use async_gateway::{AsyncGateway, AsyncHttpGateway, BlockingGateway};
+pub use gateway::{CallOptions, Estimate, Gateway, HttpGateway, HttpGatewayBuilder};
+#[cfg(feature = "memchain-gateway")]
+pub use memchain_gateway::MemchainGateway;
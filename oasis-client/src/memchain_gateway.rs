@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+
+use blockchain_traits::{Block as _, Blockchain as _, Receipt, TransactionOutcome};
+use memchain::{AccountMain, Deployment, Memchain};
+use oasis_types::{Address, Event, RpcError};
+
+use crate::gateway::{CallOptions, Estimate, Gateway};
+
+/// The gas limit of transactions sent by a `MemchainGateway`, by default.
+const DEFAULT_GAS: u64 = 1_000_000;
+
+/// A `Gateway` that executes deploys and RPCs against an in-process `Memchain`.
+/// This allows exercising generated clients without a network.
+///
+/// A service is made deployable by registering its bytecode along with the native
+/// entrypoints that the Wasm `_oasis_deploy` and `main` exports would provide.
+/// For a service built by `oasis-build`, these are its `__deploy_raw` and `__call_raw`
+/// functions run in the transaction by `oasis_test::run_in_transaction`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use oasis_client::{Gateway as _, MemchainGateway};
+/// # extern "C" fn deploy_main(_ptx: memchain::PtxPtr) -> u16 { 0 }
+/// # extern "C" fn rpc_main(_ptx: memchain::PtxPtr) -> u16 { 0 }
+/// # let memchain = memchain::Memchain::new("testnet", Default::default(), 0u64);
+/// # let sender = oasis_types::Address::default();
+/// let mut gateway = MemchainGateway::new(memchain, sender);
+/// gateway.register(b"service Wasm bytecode", deploy_main, rpc_main);
+/// let address = gateway
+///     .deploy(b"service Wasm bytecode", Default::default())
+///     .unwrap();
+/// let response = gateway.rpc(address, b"data").unwrap();
+/// ```
+pub struct MemchainGateway<'bc> {
+    memchain: RefCell<Memchain<'bc>>,
+
+    /// The account that sends (and pays for) all transactions.
+    sender: Address,
+
+    /// The gas limit of each transaction.
+    gas: u64,
+
+    services: Vec<RegisteredService>,
+}
+
+struct RegisteredService {
+    bytecode: Vec<u8>,
+    deploy_main: AccountMain,
+    rpc_main: AccountMain,
+}
+
+impl<'bc> MemchainGateway<'bc> {
+    /// Creates a new `MemchainGateway` that sends transactions from `sender`,
+    /// which must be an account in the last block of `memchain`.
+    pub fn new(memchain: Memchain<'bc>, sender: Address) -> Self {
+        Self {
+            memchain: RefCell::new(memchain),
+            sender,
+            gas: DEFAULT_GAS,
+            services: Vec::new(),
+        }
+    }
+
+    /// Sets the gas limit of each transaction.
+    pub fn gas(mut self, gas: u64) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Makes the service with `bytecode` deployable. `deploy_main` is called with the
+    /// constructor input (the initcode following `bytecode`) and `rpc_main` handles the
+    /// RPCs made to each deployed instance.
+    pub fn register(&mut self, bytecode: &[u8], deploy_main: AccountMain, rpc_main: AccountMain) {
+        self.services.push(RegisteredService {
+            bytecode: bytecode.to_vec(),
+            deploy_main,
+            rpc_main,
+        });
+    }
+
+    /// Returns the underlying `Memchain`, e.g., for inspecting account state.
+    pub fn memchain(&self) -> std::cell::Ref<Memchain<'bc>> {
+        self.memchain.borrow()
+    }
+
+    fn transact(
+        &self,
+        memchain: &mut Memchain<'bc>,
+        callee: Address,
+        input: &[u8],
+    ) -> Result<Vec<u8>, RpcError> {
+        let receipt = self.transact_with_options(memchain, callee, input, CallOptions::default());
+        Self::receipt_output(&*receipt)
+    }
+
+    fn transact_with_options(
+        &self,
+        memchain: &mut Memchain<'bc>,
+        callee: Address,
+        input: &[u8],
+        options: CallOptions,
+    ) -> Box<dyn Receipt> {
+        memchain.last_block_mut().transact(
+            self.sender,
+            callee,
+            self.sender, /* payer */
+            options.value.map(|value| value.0).unwrap_or_default(),
+            input,
+            options.gas.unwrap_or(self.gas),
+            0, /* gas price */
+        )
+    }
+
+    /// Executes a transaction against the last block without committing or logging it.
+    fn simulate(&self, callee: Address, input: &[u8], options: CallOptions) -> Box<dyn Receipt> {
+        let mut memchain = self.memchain.borrow_mut();
+        memchain.blocks.last_mut().unwrap().simulate(
+            self.sender,
+            callee,
+            self.sender, /* payer */
+            options.value.map(|value| value.0).unwrap_or_default(),
+            input,
+            options.gas.unwrap_or(self.gas),
+            0, /* gas price */
+        )
+    }
+
+    fn receipt_output(receipt: &dyn Receipt) -> Result<Vec<u8>, RpcError> {
+        match receipt.outcome() {
+            TransactionOutcome::Success => Ok(receipt.output().to_vec()),
+            TransactionOutcome::InsufficientFunds => Err(RpcError::InsufficientFunds),
+            TransactionOutcome::InsufficientGas => Err(RpcError::InsufficientGas),
+            TransactionOutcome::InvalidInput => Err(RpcError::invalid_input(receipt.output())),
+            TransactionOutcome::InvalidCallee => Err(RpcError::InvalidCallee),
+            _ => Err(RpcError::Execution(receipt.output().to_vec())),
+        }
+    }
+}
+
+impl<'bc> Gateway for MemchainGateway<'bc> {
+    fn deploy(&self, initcode: &[u8], options: CallOptions) -> Result<Address, RpcError> {
+        let service = self
+            .services
+            .iter()
+            .find(|service| initcode.starts_with(&service.bytecode))
+            .ok_or(RpcError::InvalidInput(None))?;
+        let ctor_input = &initcode[service.bytecode.len()..];
+
+        let mut memchain = self.memchain.borrow_mut();
+        let block = memchain.blocks.last_mut().unwrap();
+        let address = new_address(&service.bytecode, block.state.len());
+        let deployment = Deployment {
+            code: service.bytecode.clone(),
+            ctor: service.deploy_main,
+            main: service.rpc_main,
+        };
+        let receipt = block.deploy(
+            self.sender,
+            address,
+            deployment,
+            options.value.map(|value| value.0).unwrap_or_default(),
+            ctor_input,
+            options.gas.unwrap_or(self.gas),
+            0, /* gas price */
+        );
+        Self::receipt_output(&*receipt).map(|_| address)
+    }
+
+    fn rpc(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        let mut memchain = self.memchain.borrow_mut();
+        self.transact(&mut memchain, address, payload)
+    }
+
+    fn query(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        // Queries are read-only, so the transaction is rolled back.
+        let receipt = self.simulate(address, payload, CallOptions::default());
+        Self::receipt_output(&*receipt)
+    }
+
+    fn estimate(
+        &self,
+        address: Address,
+        payload: &[u8],
+        options: CallOptions,
+    ) -> Result<Estimate, RpcError> {
+        let receipt = self.simulate(address, payload, options);
+        match receipt.outcome() {
+            TransactionOutcome::InsufficientFunds => Err(RpcError::InsufficientFunds),
+            TransactionOutcome::InvalidCallee => Err(RpcError::InvalidCallee),
+            _ => Ok(Estimate {
+                output: receipt.output().to_vec(),
+                gas_used: receipt.gas_used(),
+                reverted: receipt.reverted(),
+            }),
+        }
+    }
+
+    fn code(&self, address: Address) -> Result<Vec<u8>, RpcError> {
+        self.memchain()
+            .last_block()
+            .code_at(&address)
+            .map(<[u8]>::to_vec)
+            .ok_or(RpcError::InvalidCallee)
+    }
+
+    fn logs(&self, address: Address) -> Result<Vec<Event>, RpcError> {
+        Ok(self
+            .memchain()
+            .blocks
+            .iter()
+            .flat_map(|block| block.receipts())
+            .filter(|receipt| !receipt.reverted())
+            .flat_map(|receipt| receipt.events())
+            .filter(|event| event.emitter == address)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Derives an address for the `nonce`-th account from the deployed bytecode.
+fn new_address(bytecode: &[u8], nonce: usize) -> Address {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::hash::Hash::hash(&(bytecode, nonce), &mut hasher);
+    let hash = std::hash::Hasher::finish(&hasher).to_le_bytes();
+    let mut address = Address::default();
+    address.0[..hash.len()].copy_from_slice(&hash);
+    address.0[hash.len()..hash.len() + 8].copy_from_slice(&(nonce as u64).to_le_bytes());
+    address
+}
@@ -1,10 +1,14 @@
-#[proc_macro_derive(Event, attributes(indexed))]
+/// The number of topics available to `#[indexed]` fields. The first of the four topics
+/// supported by the host is reserved for the event's name.
+const MAX_INDEXED_FIELDS: usize = 3;
+
+#[proc_macro_derive(Event, attributes(event, indexed))]
 pub fn event_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
     let event_name = &input.ident;
     let generics = &input.generics;
 
-    let fields = match input.data {
+    let fields = match &input.data {
         syn::Data::Struct(syn::DataStruct { fields, .. }) => fields,
         _ => {
             err!(input: "an `Event` must be a struct.");
@@ -16,6 +20,16 @@ pub fn event_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         field.attrs.iter().any(|attr| attr.path.is_ident("indexed"))
     }
 
+    let topic0 = match get_topic0(&input) {
+        Ok(topic0) => topic0,
+        Err(()) => return proc_macro::TokenStream::new(),
+    };
+
+    if let Some(field) = fields.iter().filter(|f| is_indexed(f)).nth(MAX_INDEXED_FIELDS) {
+        err!(field: "an `Event` may have at most {} `#[indexed]` fields.", MAX_INDEXED_FIELDS);
+        return proc_macro::TokenStream::new();
+    }
+
     let indexed_field_idents = match fields {
         syn::Fields::Named(syn::FieldsNamed { named, .. }) => named
             .iter()
@@ -44,6 +58,11 @@ pub fn event_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         syn::Fields::Unit => Vec::new(),
     };
 
+    let topic_hash = match topic0 {
+        Some(topic0) => quote!([#(#topic0),*]),
+        None => quote!(encode_event_topic(&stringify!(#event_name))),
+    };
+
     let impl_wrapper_ident = format_ident!("_IMPL_EVENT_FOR_{}", event_name);
 
     proc_macro::TokenStream::from(quote! {
@@ -52,11 +71,17 @@ pub fn event_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             use oasis_std::{abi::*, exe::{encode_event_topic, Event}};
 
             impl#generics Event for #event_name#generics  {
+                fn topic_hash() -> [u8; 32] {
+                    #topic_hash
+                }
+
+                fn indexed_topics(&self) -> Vec<[u8; 32]> {
+                    vec![#(encode_event_topic(&self.#indexed_field_idents)),*]
+                }
+
                 fn emit(&self) {
-                    let topics: &[[u8; 32]] = &[
-                        encode_event_topic(&stringify!(#event_name)),
-                        #(encode_event_topic(&self.#indexed_field_idents)),*
-                    ];
+                    let mut topics = vec![Self::topic_hash()];
+                    topics.append(&mut self.indexed_topics());
                     let topic_refs: Vec<&[u8]> = topics.iter().map(|t| t.as_ref()).collect();
                     oasis_std::backend::emit(&topic_refs, &self.try_to_vec().unwrap());
                 }
@@ -64,3 +89,58 @@ pub fn event_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         };
     })
 }
+
+/// Returns the topic set by `#[event(topic0 = "0x...")]`, if any. The topic must be
+/// exactly 32 hex-encoded bytes, which replace the topic computed from the event's name.
+fn get_topic0(input: &syn::DeriveInput) -> Result<Option<Vec<u8>>, ()> {
+    let mut topic0 = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("event")) {
+        let nested = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list.nested,
+            _ => {
+                err!(attr: "expected `#[event(topic0 = \"0x...\")]`.");
+                return Err(());
+            }
+        };
+        for meta in nested.iter() {
+            let lit = match meta {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(lit),
+                    ..
+                })) if path.is_ident("topic0") => lit,
+                _ => {
+                    err!(meta: "expected `topic0 = \"0x...\"`.");
+                    return Err(());
+                }
+            };
+            if topic0.is_some() {
+                err!(meta: "the `topic0` of an `Event` may only be specified once.");
+                return Err(());
+            }
+            let value = lit.value();
+            let hex = if value.starts_with("0x") {
+                &value[2..]
+            } else {
+                &value
+            };
+            let bytes = if hex.len() == 64 && hex.is_ascii() {
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .ok()
+            } else {
+                None
+            };
+            match bytes {
+                Some(bytes) => topic0 = Some(bytes),
+                None => {
+                    err!(lit: "`topic0` must be 32 hex-encoded bytes, but was `{}`.", value);
+                    return Err(());
+                }
+            }
+        }
+    }
+    Ok(topic0)
+}
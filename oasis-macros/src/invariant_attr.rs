@@ -0,0 +1,11 @@
+/// Marks a method that takes `&self` and returns whether the service's state is consistent.
+/// It is not an RPC method, and debug builds (including tests) panic if it returns `false`
+/// after a mutating RPC.
+#[proc_macro_attribute]
+pub fn invariant(
+    _args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::ImplItemMethod);
+    proc_macro::TokenStream::from(quote!(#input))
+}
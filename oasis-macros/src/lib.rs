@@ -18,4 +18,9 @@ macro_rules! err {
 // per rustc: "functions tagged with `#[proc_macro]` must currently reside in the root of the crate"
 include!("default_attr.rs");
 include!("event_derive.rs");
+include!("invariant_attr.rs");
+include!("ordered_struct_derive.rs");
 include!("service_derive.rs");
+include!("skip_attr.rs");
+include!("tagged_enum_derive.rs");
+include!("view_attr.rs");
@@ -0,0 +1,88 @@
+/// Derives `Serialize` and `Deserialize` for a struct whose fields are encoded in the order
+/// set by `#[oasis(field = N)]` rather than their order in the source, so that the fields
+/// may be reordered without changing the encoding of stored values.
+#[proc_macro_derive(OrderedStruct, attributes(oasis))]
+pub fn ordered_struct_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    let struct_name = &input.ident;
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields, .. }) => fields,
+        _ => {
+            err!(input: "`#[derive(OrderedStruct)]` can only be applied to structs.");
+            return proc_macro::TokenStream::new();
+        }
+    };
+
+    if input.generics.type_params().count() > 0 {
+        err!(input.generics: "`#[derive(OrderedStruct)]` does not support generic structs.");
+        return proc_macro::TokenStream::new();
+    }
+
+    let mut pinned_fields = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let index = match get_pinned_index(&field.attrs, "field") {
+            Ok(Some(index)) => index,
+            Ok(None) => {
+                err!(field: "every field of an `OrderedStruct` must have an `#[oasis(field = N)]`.");
+                return proc_macro::TokenStream::new();
+            }
+            Err(()) => return proc_macro::TokenStream::new(),
+        };
+        if pinned_fields
+            .iter()
+            .any(|(other_index, _)| *other_index == index)
+        {
+            err!(field: "the index `{}` is used by more than one field.", index);
+            return proc_macro::TokenStream::new();
+        }
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index {
+                index: i as u32,
+                span: proc_macro2::Span::call_site(),
+            }),
+        };
+        pinned_fields.push((index, member));
+    }
+
+    // The fields are deserialized in pinned order into bindings that are then
+    // moved into the struct in source order.
+    let bindings: Vec<_> = (0..fields.len()).map(|i| format_ident!("f{}", i)).collect();
+    let mut encoding_order: Vec<usize> = (0..fields.len()).collect();
+    encoding_order.sort_by_key(|&i| pinned_fields[i].0);
+    let encoded_members = encoding_order.iter().map(|&i| &pinned_fields[i].1);
+    let encoded_bindings = encoding_order.iter().map(|&i| &bindings[i]);
+
+    let construct = match fields {
+        syn::Fields::Named(_) => {
+            let members = pinned_fields.iter().map(|(_, member)| member);
+            quote!(Self { #(#members: #bindings),* })
+        }
+        syn::Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        syn::Fields::Unit => quote!(Self),
+    };
+
+    let impl_wrapper_ident = format_ident!("_IMPL_ORDERED_STRUCT_FOR_{}", struct_name);
+
+    proc_macro::TokenStream::from(quote! {
+        #[allow(non_upper_case_globals, unused_variables)] // unit structs ignore the reader
+        const #impl_wrapper_ident: () = {
+            use oasis_std::abi::*;
+
+            impl Serialize for #struct_name {
+                fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+                    #(self.#encoded_members.serialize(writer)?;)*
+                    Ok(())
+                }
+            }
+
+            impl Deserialize for #struct_name {
+                fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+                    #(let #encoded_bindings = Deserialize::deserialize(reader)?;)*
+                    Ok(#construct)
+                }
+            }
+        };
+    })
+}
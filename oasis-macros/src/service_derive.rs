@@ -1,4 +1,4 @@
-#[proc_macro_derive(Service)]
+#[proc_macro_derive(Service, attributes(oasis))]
 pub fn service_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     if std::env::var("OASIS_BUILD_NO_SERVICE_DERIVE").is_ok() {
         return proc_macro::TokenStream::new();
@@ -39,6 +39,8 @@ fn get_serde(
         return None;
     }
 
+    let format = get_storage_format(input)?;
+
     let (named, fields) = match &input.data {
         syn::Data::Struct(s) => {
             let named = match &s.fields {
@@ -69,7 +71,24 @@ fn get_serde(
                     proc_macro2::Literal::string(&index.to_string()),
                 ),
             };
-            let (ser, de) = get_type_serde(&field.ty, struct_idx, key);
+            let (ser, de) = if is_slotted(field) {
+                // The field is assigned the slot numbered by its index, which it must use.
+                let slot = index as u32;
+                (
+                    quote! {
+                        assert_eq!(
+                            oasis_std::collections::Slotted::slot(&service.#struct_idx),
+                            #slot,
+                            "`{}` must be stored in slot {}",
+                            #key,
+                            #slot
+                        )
+                    },
+                    quote!(oasis_std::collections::Slotted::at_slot(#slot)),
+                )
+            } else {
+                get_type_serde(&field.ty, struct_idx, key, format)
+            };
             let de = match &field.ident {
                 Some(ident) => quote! { #ident: #de },
                 None => de,
@@ -89,29 +108,113 @@ fn get_serde(
     Some((ser, de))
 }
 
+/// The encoding used to persist service state.
+#[derive(Clone, Copy)]
+enum StorageFormat {
+    Borsh,
+    Cbor,
+}
+
+/// Returns the storage format set by `#[oasis(storage = "...")]`, or borsh if unset.
+fn get_storage_format(input: &syn::DeriveInput) -> Option<StorageFormat> {
+    let mut format = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("oasis")) {
+        let nested = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list.nested,
+            _ => {
+                err!(attr: "expected `#[oasis(storage = \"...\")]`.");
+                return None;
+            }
+        };
+        for meta in nested.iter() {
+            let lit = match meta {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(lit),
+                    ..
+                })) if path.is_ident("storage") => lit,
+                _ => {
+                    err!(meta: "expected `storage = \"...\"`.");
+                    return None;
+                }
+            };
+            if format.is_some() {
+                err!(meta: "the storage format of a service may only be specified once.");
+                return None;
+            }
+            format = Some(match lit.value().as_str() {
+                "borsh" => StorageFormat::Borsh,
+                "cbor" => StorageFormat::Cbor,
+                other => {
+                    err!(lit: "unknown storage format `{}`. Expected `borsh` or `cbor`.", other);
+                    return None;
+                }
+            });
+        }
+    }
+    Some(format.unwrap_or(StorageFormat::Borsh))
+}
+
+/// Returns whether a field is marked `#[oasis(slot)]`, which stores it in its own storage slot.
+fn is_slotted(field: &syn::Field) -> bool {
+    let mut slotted = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("oasis")) {
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => {
+                for meta in list.nested.iter() {
+                    match meta {
+                        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("slot") => {
+                            slotted = true
+                        }
+                        _ => err!(meta: "expected `slot`."),
+                    }
+                }
+            }
+            _ => err!(attr: "expected `#[oasis(slot)]`."),
+        }
+    }
+    slotted
+}
+
 /// Returns the serializer and deserializer for a Type.
 fn get_type_serde(
     ty: &syn::Type,
     struct_idx: syn::Member,
     key: proc_macro2::Literal,
+    format: StorageFormat,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     use syn::Type::*;
     match ty {
-        Group(g) => get_type_serde(&*g.elem, struct_idx, key),
-        Paren(p) => get_type_serde(&*p.elem, struct_idx, key),
-        Array(_) | Tuple(_) | Path(_) => (
-            quote! {
-                oasis_std::backend::write(
-                    #key.as_bytes(),
-                    &service.#struct_idx.try_to_vec().unwrap()
-                )
-            },
-            quote! {
-                <_>::try_from_slice(
-                    &oasis_std::backend::read(#key.as_bytes())
-                ).unwrap()
-            },
-        ),
+        Group(g) => get_type_serde(&*g.elem, struct_idx, key, format),
+        Paren(p) => get_type_serde(&*p.elem, struct_idx, key, format),
+        Array(_) | Tuple(_) | Path(_) => match format {
+            StorageFormat::Borsh => (
+                quote! {
+                    oasis_std::backend::write(
+                        #key.as_bytes(),
+                        &service.#struct_idx.try_to_vec().unwrap()
+                    )
+                },
+                quote! {
+                    <_>::try_from_slice(
+                        &oasis_std::backend::read(#key.as_bytes())
+                    ).unwrap()
+                },
+            ),
+            StorageFormat::Cbor => (
+                quote! {
+                    oasis_std::backend::write(
+                        #key.as_bytes(),
+                        &oasis_std::reexports::serde_cbor::to_vec(&service.#struct_idx).unwrap()
+                    )
+                },
+                quote! {
+                    oasis_std::reexports::serde_cbor::from_slice(
+                        &oasis_std::backend::read(#key.as_bytes())
+                    ).unwrap()
+                },
+            ),
+        },
         ty => {
             err!(ty: "Service field must be a POD type.");
             (quote!(unreachable!()), quote!(unreachable!()))
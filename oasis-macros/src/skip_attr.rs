@@ -0,0 +1,10 @@
+/// Excludes a `pub` method from the service's RPC interface, so that it is a regular
+/// method that can only be called from Rust.
+#[proc_macro_attribute]
+pub fn skip(
+    _args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::ImplItemMethod);
+    proc_macro::TokenStream::from(quote!(#input))
+}
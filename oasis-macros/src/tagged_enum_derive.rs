@@ -0,0 +1,145 @@
+/// Derives `Serialize` and `Deserialize` for an enum whose variants are encoded using the
+/// tags set by `#[oasis(tag = N)]` rather than their positions in the source, so that
+/// the variants may be reordered without changing the encoding of stored values.
+#[proc_macro_derive(TaggedEnum, attributes(oasis))]
+pub fn tagged_enum_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    let enum_name = &input.ident;
+
+    let variants = match &input.data {
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => variants,
+        _ => {
+            err!(input: "`#[derive(TaggedEnum)]` can only be applied to enums.");
+            return proc_macro::TokenStream::new();
+        }
+    };
+
+    if input.generics.type_params().count() > 0 {
+        err!(input.generics: "`#[derive(TaggedEnum)]` does not support generic enums.");
+        return proc_macro::TokenStream::new();
+    }
+
+    let mut tags: Vec<u8> = Vec::with_capacity(variants.len());
+    for variant in variants.iter() {
+        let tag = match get_pinned_index(&variant.attrs, "tag") {
+            Ok(Some(tag)) => tag,
+            Ok(None) => {
+                err!(variant: "every variant of a `TaggedEnum` must have an `#[oasis(tag = N)]`.");
+                return proc_macro::TokenStream::new();
+            }
+            Err(()) => return proc_macro::TokenStream::new(),
+        };
+        if tags.contains(&tag) {
+            err!(variant: "the tag `{}` is used by more than one variant.", tag);
+            return proc_macro::TokenStream::new();
+        }
+        tags.push(tag);
+    }
+
+    let (sers, des): (Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>) = variants
+        .iter()
+        .zip(tags.iter())
+        .map(|(variant, tag)| {
+            let variant_name = &variant.ident;
+            let bindings: Vec<_> = (0..variant.fields.len())
+                .map(|i| format_ident!("f{}", i))
+                .collect();
+            let (pat, de) = match &variant.fields {
+                syn::Fields::Named(syn::FieldsNamed { named, .. }) => {
+                    let field_names: Vec<_> = named.iter().map(|f| &f.ident).collect();
+                    (
+                        quote!({ #(#field_names: #bindings),* }),
+                        quote!({ #(#field_names: Deserialize::deserialize(reader)?),* }),
+                    )
+                }
+                syn::Fields::Unnamed(_) => {
+                    let des = bindings
+                        .iter()
+                        .map(|_| quote!(Deserialize::deserialize(reader)?));
+                    (quote!((#(#bindings),*)), quote!((#(#des),*)))
+                }
+                syn::Fields::Unit => (quote!(), quote!()),
+            };
+            (
+                quote! {
+                    #enum_name::#variant_name#pat => {
+                        #tag.serialize(writer)?;
+                        #(#bindings.serialize(writer)?;)*
+                    }
+                },
+                quote!(#tag => #enum_name::#variant_name#de),
+            )
+        })
+        .unzip();
+
+    let impl_wrapper_ident = format_ident!("_IMPL_TAGGED_ENUM_FOR_{}", enum_name);
+
+    proc_macro::TokenStream::from(quote! {
+        #[allow(non_upper_case_globals)]
+        const #impl_wrapper_ident: () = {
+            use oasis_std::abi::*;
+
+            impl Serialize for #enum_name {
+                fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+                    match self {
+                        #(#sers),*
+                    }
+                    Ok(())
+                }
+            }
+
+            impl Deserialize for #enum_name {
+                fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+                    Ok(match u8::deserialize(reader)? {
+                        #(#des,)*
+                        tag => {
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::InvalidInput,
+                                format!("unknown `{}` tag: {}", stringify!(#enum_name), tag),
+                            ))
+                        }
+                    })
+                }
+            }
+        };
+    })
+}
+
+/// Returns the number set by `#[oasis(<key> = N)]`, e.g., the tag of an enum variant.
+fn get_pinned_index(attrs: &[syn::Attribute], key: &str) -> Result<Option<u8>, ()> {
+    let mut index = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("oasis")) {
+        let nested = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list.nested,
+            _ => {
+                err!(attr: "expected `#[oasis({} = N)]`.", key);
+                return Err(());
+            }
+        };
+        for meta in nested.iter() {
+            let lit = match meta {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Int(lit),
+                    ..
+                })) if path.is_ident(key) => lit,
+                _ => {
+                    err!(meta: "expected `{} = N`.", key);
+                    return Err(());
+                }
+            };
+            if index.is_some() {
+                err!(meta: "`{}` may only be specified once.", key);
+                return Err(());
+            }
+            match lit.base10_parse::<u8>() {
+                Ok(value) => index = Some(value),
+                Err(_) => {
+                    err!(lit: "`{}` must be an integer between 0 and 255.", key);
+                    return Err(());
+                }
+            }
+        }
+    }
+    Ok(index)
+}
@@ -0,0 +1,10 @@
+/// Asserts that an RPC method does not modify the service's state, so that clients send it
+/// as a read-only query. `oasis-build` checks that the method takes `&self`.
+#[proc_macro_attribute]
+pub fn view(
+    _args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::ImplItemMethod);
+    proc_macro::TokenStream::from(quote!(#input))
+}
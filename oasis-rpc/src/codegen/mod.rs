@@ -0,0 +1,5 @@
+//! Generates client bindings for other languages from an `Interface`.
+
+mod python;
+
+pub use python::python;
@@ -0,0 +1,239 @@
+# This file was generated by oasis-rpc. Do not edit it by hand.
+
+import json
+import struct
+import time
+import urllib.request
+import uuid
+from dataclasses import dataclass
+from datetime import timedelta
+from typing import Dict, List, Optional, Set, Tuple, Union
+
+
+class RpcError(Exception):
+    """Raised when a request to a service fails."""
+
+
+class Gateway:
+    """Sends requests to an Oasis Developer Gateway."""
+
+    def __init__(self, url, api_key=None, poll_interval=0.5, max_attempts=20):
+        self.url = url.rstrip("/")
+        self.poll_interval = poll_interval
+        self.max_attempts = max_attempts
+        self.headers = {
+            "Content-Type": "application/json",
+            "X-OASIS-INSECURE-AUTH": "1",
+            "X-OASIS-SESSION-KEY": str(uuid.uuid4()),
+        }
+        if api_key is not None:
+            self.headers["X-OASIS-LOGIN-TOKEN"] = api_key
+
+    def deploy(self, initcode: bytes) -> bytes:
+        event = self._post_and_poll("v0/api/service/deploy", {"data": "0x" + initcode.hex()})
+        return bytes.fromhex(event["address"][2:])
+
+    def rpc(self, address: bytes, payload: bytes) -> bytes:
+        event = self._post_and_poll(
+            "v0/api/service/execute",
+            {"address": "0x" + address.hex(), "data": "0x" + payload.hex()},
+        )
+        return bytes.fromhex(event["output"][2:])
+
+    def _post_and_poll(self, api, body):
+        request_id = self._request(api, body)["id"]
+        poll = {"offset": request_id, "count": 1, "discard_previous": True}
+        for _ in range(self.max_attempts):
+            events = self._request("v0/api/service/poll", poll)["events"]
+            if events:
+                event = events[0]
+                if "error_code" in event:
+                    raise RpcError(event["description"])
+                return event
+            time.sleep(self.poll_interval)
+        raise RpcError("exceeded max polling attempts")
+
+    def _request(self, api, body):
+        request = urllib.request.Request(
+            self.url + "/" + api,
+            data=json.dumps(body).encode("utf-8"),
+            headers=self.headers,
+            method="POST",
+        )
+        with urllib.request.urlopen(request) as response:
+            return json.loads(response.read())
+
+
+_NUMS = {
+    "u8": "<B",
+    "i8": "<b",
+    "u16": "<H",
+    "i16": "<h",
+    "u32": "<I",
+    "i32": "<i",
+    "u64": "<Q",
+    "i64": "<q",
+    "f32": "<f",
+    "f64": "<d",
+}
+
+
+class _Reader:
+    def __init__(self, buf):
+        self.buf = buf
+        self.pos = 0
+
+    def take(self, n):
+        if self.pos + n > len(self.buf):
+            raise ValueError("unexpected end of output")
+        taken = self.buf[self.pos:self.pos + n]
+        self.pos += n
+        return taken
+
+    def unpack(self, fmt):
+        return struct.unpack(fmt, self.take(struct.calcsize(fmt)))[0]
+
+
+def _encode(schema, value, buf):
+    """Appends the borsh encoding of `value` to `buf`."""
+    if isinstance(schema, str):
+        if schema == "bool":
+            buf.append(1 if value else 0)
+        elif schema in _NUMS:
+            buf += struct.pack(_NUMS[schema], value)
+        elif schema == "balance":
+            buf += value.to_bytes(16, "little")
+        elif schema == "u256":
+            buf += value.to_bytes(32, "little")
+        elif schema == "i256":
+            buf += value.to_bytes(32, "little", signed=True)
+        elif schema == "duration":
+            _encode("balance", value // timedelta(microseconds=1) * 1000, buf)
+        elif schema == "bytes":
+            buf += struct.pack("<I", len(value))
+            buf += value
+        elif schema == "string":
+            _encode("bytes", value.encode("utf-8"), buf)
+        elif schema == "address":
+            if len(value) != 20:
+                raise ValueError("addresses must be 20 bytes")
+            buf += value
+        else:
+            raise TypeError("cannot encode `{}`".format(schema))
+        return
+    kind = schema[0]
+    if kind == "tuple":
+        for item_schema, item in zip(schema[1], value):
+            _encode(item_schema, item, buf)
+    elif kind == "array":
+        if len(value) != schema[2]:
+            raise ValueError("expected {} items".format(schema[2]))
+        for item in value:
+            _encode(schema[1], item, buf)
+    elif kind in ("list", "set"):
+        buf += struct.pack("<I", len(value))
+        for item in value:
+            _encode(schema[1], item, buf)
+    elif kind == "map":
+        buf += struct.pack("<I", len(value))
+        for k, v in value.items():
+            _encode(schema[1], k, buf)
+            _encode(schema[2], v, buf)
+    elif kind == "optional":
+        if value is None:
+            buf.append(0)
+        else:
+            buf.append(1)
+            _encode(schema[1], value, buf)
+    elif kind == "defined":
+        _encode_def(value, buf)
+    else:
+        raise TypeError("cannot encode `{}`".format(kind))
+
+
+def _encode_def(value, buf):
+    if hasattr(value, "_VARIANTS"):
+        names = [name for name, _ in value._VARIANTS]
+        index = names.index(value.variant)
+        buf.append(value._TAGS[index] if hasattr(value, "_TAGS") else index)
+        fields = value._VARIANTS[index][1]
+        if fields is None:
+            return
+        if fields[0] == "tuple":
+            for item_schema, item in zip(fields[1], value.fields):
+                _encode(item_schema, item, buf)
+        else:
+            for name, field_schema in fields[1]:
+                _encode(field_schema, value.fields[name], buf)
+    else:
+        for name, field_schema in value._FIELDS:
+            _encode(field_schema, getattr(value, name), buf)
+
+
+def _decode(schema, reader):
+    """Decodes a value of type `schema` from `reader`."""
+    if isinstance(schema, str):
+        if schema == "bool":
+            return reader.take(1)[0] != 0
+        elif schema in _NUMS:
+            return reader.unpack(_NUMS[schema])
+        elif schema == "balance":
+            return int.from_bytes(reader.take(16), "little")
+        elif schema == "u256":
+            return int.from_bytes(reader.take(32), "little")
+        elif schema == "i256":
+            return int.from_bytes(reader.take(32), "little", signed=True)
+        elif schema == "duration":
+            # `timedelta` has microsecond precision.
+            return timedelta(microseconds=_decode("balance", reader) // 1000)
+        elif schema == "bytes":
+            return bytes(reader.take(reader.unpack("<I")))
+        elif schema == "string":
+            return _decode("bytes", reader).decode("utf-8")
+        elif schema == "address":
+            return bytes(reader.take(20))
+        raise TypeError("cannot decode `{}`".format(schema))
+    kind = schema[0]
+    if kind == "tuple":
+        return tuple(_decode(item_schema, reader) for item_schema in schema[1])
+    elif kind == "array":
+        return [_decode(schema[1], reader) for _ in range(schema[2])]
+    elif kind == "list":
+        return [_decode(schema[1], reader) for _ in range(reader.unpack("<I"))]
+    elif kind == "set":
+        return {_decode(schema[1], reader) for _ in range(reader.unpack("<I"))}
+    elif kind == "map":
+        entries = {}
+        for _ in range(reader.unpack("<I")):
+            k = _decode(schema[1], reader)
+            entries[k] = _decode(schema[2], reader)
+        return entries
+    elif kind == "optional":
+        return _decode(schema[1], reader) if reader.take(1)[0] else None
+    elif kind == "result":
+        if reader.take(1)[0]:
+            return _decode(schema[1], reader)
+        raise RpcError(_decode(schema[2], reader))
+    elif kind == "defined":
+        return _decode_def(globals()[schema[1]], reader)
+    raise TypeError("cannot decode `{}`".format(kind))
+
+
+def _decode_def(cls, reader):
+    if hasattr(cls, "_VARIANTS"):
+        tag = reader.take(1)[0]
+        name, fields = cls._VARIANTS[cls._TAGS.index(tag) if hasattr(cls, "_TAGS") else tag]
+        if fields is None:
+            return cls(name)
+        if fields[0] == "tuple":
+            return cls(name, [_decode(item_schema, reader) for item_schema in fields[1]])
+        return cls(name, {field: _decode(field_schema, reader) for field, field_schema in fields[1]})
+    return cls(**{name: _decode(field_schema, reader) for name, field_schema in cls._FIELDS})
+
+
+def _decode_output(schema, output):
+    reader = _Reader(output)
+    value = _decode(schema, reader)
+    if reader.pos != len(output):
+        raise ValueError("{} trailing bytes in output".format(len(output) - reader.pos))
+    return value
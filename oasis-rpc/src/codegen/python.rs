@@ -0,0 +1,454 @@
+use std::fmt::Write as _;
+
+use crate::{EnumFields, Field, Function, Interface, Type, TypeDef};
+
+/// The encoding helpers and gateway client shared by all generated modules.
+const PRELUDE: &str = include_str!("prelude.py");
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Generates a Python module containing a `dataclass` for each of the interface's type defs
+/// and a client class with one method per function.
+/// Values are borsh-encoded and sent to the service through an Oasis Developer Gateway.
+/// Functions returning `Result` raise `RpcError` on `Err`.
+pub fn python(iface: &Interface) -> String {
+    let mut py = PRELUDE.to_string();
+    for def in iface.type_defs.iter() {
+        py.push_str("\n\n");
+        write_type_def(&mut py, def);
+    }
+    py.push_str("\n\n");
+    write_client(&mut py, iface);
+    py
+}
+
+fn write_type_def(py: &mut String, def: &TypeDef) {
+    py.push_str("@dataclass\n");
+    writeln!(py, "class {}:", def.name()).unwrap();
+    match def {
+        TypeDef::Struct { fields, .. } => {
+            let fields: Vec<_> = fields.iter().map(|f| (f.name.as_str(), &f.ty)).collect();
+            write_struct_body(py, &fields);
+        }
+        TypeDef::Event { fields, .. } => {
+            let fields: Vec<_> = fields.iter().map(|f| (f.name.as_str(), &f.ty)).collect();
+            write_struct_body(py, &fields);
+        }
+        TypeDef::Enum { variants, .. } => {
+            py.push_str("    variant: str\n");
+            py.push_str("    fields: Union[None, list, dict] = None\n");
+            py.push_str("\n");
+            let variants_tagged = variants.iter().any(|v| v.tag.is_some());
+            let tags: Vec<u8> = variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v.tag.unwrap_or(i as u8))
+                .collect();
+            let variants: Vec<_> = variants
+                .iter()
+                .map(|v| {
+                    let fields = match &v.fields {
+                        None => "None".to_string(),
+                        Some(EnumFields::Tuple(tys)) => format!("(\"tuple\", [{}])", schemas(tys)),
+                        Some(EnumFields::Named(fields)) => {
+                            format!("(\"named\", [{}])", field_schemas(fields))
+                        }
+                    };
+                    format!("(\"{}\", {})", v.name, fields)
+                })
+                .collect();
+            writeln!(py, "    _VARIANTS = [{}]", variants.join(", ")).unwrap();
+            if variants_tagged {
+                let tags: Vec<_> = tags.iter().map(|tag| tag.to_string()).collect();
+                writeln!(py, "    _TAGS = [{}]", tags.join(", ")).unwrap();
+            }
+        }
+    }
+}
+
+fn write_struct_body(py: &mut String, fields: &[(&str, &Type)]) {
+    for (name, ty) in fields {
+        writeln!(py, "    {}: {}", py_ident(name), annotation(ty)).unwrap();
+    }
+    if !fields.is_empty() {
+        py.push_str("\n");
+    }
+    let schemas: Vec<_> = fields
+        .iter()
+        .map(|(name, ty)| format!("(\"{}\", {})", py_ident(name), schema(ty)))
+        .collect();
+    writeln!(py, "    _FIELDS = [{}]", schemas.join(", ")).unwrap();
+}
+
+fn write_client(py: &mut String, iface: &Interface) {
+    let client_name = format!("{}Client", iface.name);
+    writeln!(py, "class {}:", client_name).unwrap();
+    writeln!(
+        py,
+        "    \"\"\"A client for the `{}` service.\"\"\"",
+        iface.name
+    )
+    .unwrap();
+    py.push_str("\n");
+    py.push_str("    def __init__(self, gateway: Gateway, address: bytes):\n");
+    py.push_str("        self.gateway = gateway\n");
+    py.push_str("        self.address = address\n");
+
+    py.push_str("\n");
+    py.push_str("    @classmethod\n");
+    writeln!(
+        py,
+        "    def deploy(cls, gateway: Gateway, bytecode: bytes{}) -> \"{}\":",
+        params(&iface.constructor.inputs),
+        client_name
+    )
+    .unwrap();
+    py.push_str("        buf = bytearray(bytecode)\n");
+    write_encode_args(py, &iface.constructor.inputs);
+    py.push_str("        return cls(gateway, gateway.deploy(bytes(buf)))\n");
+
+    for (i, func) in iface.functions.iter().enumerate() {
+        py.push_str("\n");
+        write_function(py, iface, i, func);
+    }
+}
+
+fn write_function(py: &mut String, iface: &Interface, index: usize, func: &Function) {
+    // The service returns only the `Ok` value. An `Err` fails the request.
+    let output = match &func.output {
+        Some(Type::Result(ok_ty, _)) => Some(&**ok_ty),
+        output => output.as_ref(),
+    }
+    .filter(|ty| **ty != Type::Tuple(Vec::new()));
+
+    writeln!(
+        py,
+        "    def {}(self{}) -> {}:",
+        py_ident(&func.name),
+        params(&func.inputs),
+        output.map(annotation).unwrap_or_else(|| "None".to_string())
+    )
+    .unwrap();
+    if iface.default_function.as_ref() == Some(&func.name) {
+        py.push_str("        buf = bytearray()\n");
+    } else {
+        writeln!(py, "        buf = bytearray([{}])", index).unwrap();
+    }
+    write_encode_args(py, &func.inputs);
+    match output {
+        Some(ty) => {
+            py.push_str("        output = self.gateway.rpc(self.address, bytes(buf))\n");
+            writeln!(py, "        return _decode_output({}, output)", schema(ty)).unwrap();
+        }
+        None => py.push_str("        self.gateway.rpc(self.address, bytes(buf))\n"),
+    }
+}
+
+fn write_encode_args(py: &mut String, inputs: &[Field]) {
+    for input in inputs {
+        writeln!(
+            py,
+            "        _encode({}, {}, buf)",
+            schema(&input.ty),
+            py_ident(&input.name)
+        )
+        .unwrap();
+    }
+}
+
+/// Returns the parameters of a method taking `inputs`. Trailing optional inputs default
+/// to `None` so that callers may omit them.
+fn params(inputs: &[Field]) -> String {
+    let num_required = inputs
+        .iter()
+        .rposition(|input| !input.optional)
+        .map(|i| i + 1)
+        .unwrap_or_default();
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            format!(
+                ", {}: {}{}",
+                py_ident(&input.name),
+                annotation(&input.ty),
+                if i >= num_required { " = None" } else { "" }
+            )
+        })
+        .collect()
+}
+
+/// Returns a Python identifier that does not collide with keywords or start with a digit.
+fn py_ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else if name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the Python type hint of `ty`.
+fn annotation(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8
+        | Type::I8
+        | Type::U16
+        | Type::I16
+        | Type::U32
+        | Type::I32
+        | Type::U64
+        | Type::I64
+        | Type::Balance
+        | Type::U256
+        | Type::I256 => "int".to_string(),
+        Type::F32 | Type::F64 => "float".to_string(),
+        Type::Bytes | Type::Address => "bytes".to_string(),
+        Type::String => "str".to_string(),
+        Type::RpcError => "object".to_string(),
+        Type::Duration => "timedelta".to_string(),
+        Type::Defined { ty, .. } => format!("\"{}\"", ty),
+        Type::Tuple(tys) if tys.is_empty() => "None".to_string(),
+        Type::Tuple(tys) => format!(
+            "Tuple[{}]",
+            tys.iter().map(annotation).collect::<Vec<_>>().join(", ")
+        ),
+        Type::Array(ty, _) | Type::List(ty) => format!("List[{}]", annotation(ty)),
+        Type::Set(ty) => format!("Set[{}]", annotation(ty)),
+        Type::Map(k, v) => format!("Dict[{}, {}]", annotation(k), annotation(v)),
+        Type::Optional(ty) => format!("Optional[{}]", annotation(ty)),
+        Type::Result(ok_ty, _) => annotation(ok_ty),
+    }
+}
+
+/// Returns the Python literal that describes the encoding of `ty` to the prelude's
+/// `_encode` and `_decode`.
+fn schema(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "\"bool\"".to_string(),
+        Type::U8 => "\"u8\"".to_string(),
+        Type::I8 => "\"i8\"".to_string(),
+        Type::U16 => "\"u16\"".to_string(),
+        Type::I16 => "\"i16\"".to_string(),
+        Type::U32 => "\"u32\"".to_string(),
+        Type::I32 => "\"i32\"".to_string(),
+        Type::U64 => "\"u64\"".to_string(),
+        Type::I64 => "\"i64\"".to_string(),
+        Type::F32 => "\"f32\"".to_string(),
+        Type::F64 => "\"f64\"".to_string(),
+        Type::Bytes => "\"bytes\"".to_string(),
+        Type::String => "\"string\"".to_string(),
+        Type::Address => "\"address\"".to_string(),
+        Type::Balance => "\"balance\"".to_string(),
+        Type::U256 => "\"u256\"".to_string(),
+        Type::I256 => "\"i256\"".to_string(),
+        Type::RpcError => "\"rpc_error\"".to_string(),
+        Type::Duration => "\"duration\"".to_string(),
+        Type::Defined { ty, .. } => format!("(\"defined\", \"{}\")", ty),
+        Type::Tuple(tys) => format!("(\"tuple\", [{}])", schemas(tys)),
+        Type::Array(ty, len) => format!("(\"array\", {}, {})", schema(ty), len),
+        Type::List(ty) => format!("(\"list\", {})", schema(ty)),
+        Type::Set(ty) => format!("(\"set\", {})", schema(ty)),
+        Type::Map(k, v) => format!("(\"map\", {}, {})", schema(k), schema(v)),
+        Type::Optional(ty) => format!("(\"optional\", {})", schema(ty)),
+        Type::Result(ok_ty, err_ty) => {
+            format!("(\"result\", {}, {})", schema(ok_ty), schema(err_ty))
+        }
+    }
+}
+
+fn schemas(tys: &[Type]) -> String {
+    tys.iter().map(schema).collect::<Vec<_>>().join(", ")
+}
+
+fn field_schemas(fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("(\"{}\", {})", f.name, schema(&f.ty)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Constructor, EnumVariant, StateMutability};
+
+    fn field(name: &str, ty: Type) -> Field {
+        Field {
+            name: name.to_string(),
+            ty,
+            optional: false,
+        }
+    }
+
+    fn string_result(ok_ty: Type) -> Option<Type> {
+        Some(Type::Result(box ok_ty, box Type::String))
+    }
+
+    /// The interface of `examples/ballot`.
+    fn ballot_interface() -> Interface {
+        Interface {
+            name: "Ballot".to_string(),
+            namespace: "ballot".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: Vec::new(),
+            constructor: Constructor {
+                inputs: vec![
+                    field("description", Type::String),
+                    field("candidates", Type::List(box Type::String)),
+                ],
+                error: Some(Type::String),
+            },
+            functions: vec![
+                Function {
+                    name: "description".to_string(),
+                    mutability: StateMutability::Immutable,
+                    inputs: Vec::new(),
+                    output: string_result(Type::String),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "candidates".to_string(),
+                    mutability: StateMutability::Immutable,
+                    inputs: Vec::new(),
+                    output: string_result(Type::List(box Type::String)),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "vote".to_string(),
+                    mutability: StateMutability::Mutable,
+                    inputs: vec![field("candidate_num", Type::U32)],
+                    output: string_result(Type::Tuple(Vec::new())),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "close".to_string(),
+                    mutability: StateMutability::Mutable,
+                    inputs: Vec::new(),
+                    output: string_result(Type::Tuple(Vec::new())),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "winner".to_string(),
+                    mutability: StateMutability::Immutable,
+                    inputs: Vec::new(),
+                    output: string_result(Type::U32),
+                    estimated_gas: None,
+                },
+            ],
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    #[test]
+    fn test_ballot_snapshot() {
+        assert_eq!(
+            python(&ballot_interface()),
+            format!("{}\n\n{}", PRELUDE, include_str!("snapshots/ballot.py"))
+        );
+    }
+
+    #[test]
+    fn test_type_defs() {
+        let mut iface = ballot_interface();
+        iface.type_defs = vec![
+            TypeDef::Struct {
+                name: "Stats".to_string(),
+                fields: vec![
+                    field("votes", Type::Map(box Type::Address, box Type::U32)),
+                    field("winner", Type::Optional(box Type::U32)),
+                ],
+            },
+            TypeDef::Enum {
+                name: "Choice".to_string(),
+                variants: vec![
+                    EnumVariant {
+                        name: "Abstain".to_string(),
+                        tag: None,
+                        fields: None,
+                    },
+                    EnumVariant {
+                        name: "Candidate".to_string(),
+                        tag: None,
+                        fields: Some(EnumFields::Tuple(vec![Type::U32])),
+                    },
+                ],
+            },
+        ];
+        let py = python(&iface);
+        assert!(py.contains(
+            "@dataclass\n\
+             class Stats:\n    \
+                 votes: Dict[bytes, int]\n    \
+                 winner: Optional[int]\n\
+             \n    \
+                 _FIELDS = [(\"votes\", (\"map\", \"address\", \"u32\")), \
+                            (\"winner\", (\"optional\", \"u32\"))]\n"
+        ));
+        assert!(py.contains(
+            "@dataclass\n\
+             class Choice:\n    \
+                 variant: str\n    \
+                 fields: Union[None, list, dict] = None\n\
+             \n    \
+                 _VARIANTS = [(\"Abstain\", None), (\"Candidate\", (\"tuple\", [\"u32\"]))]\n"
+        ));
+    }
+
+    #[test]
+    fn test_tagged_enum() {
+        let mut iface = ballot_interface();
+        iface.type_defs = vec![TypeDef::Enum {
+            name: "Choice".to_string(),
+            variants: vec![
+                EnumVariant {
+                    name: "Candidate".to_string(),
+                    tag: Some(1),
+                    fields: Some(EnumFields::Tuple(vec![Type::U32])),
+                },
+                EnumVariant {
+                    name: "Abstain".to_string(),
+                    tag: Some(0),
+                    fields: None,
+                },
+            ],
+        }];
+        let py = python(&iface);
+        assert!(py.contains(
+            "    _VARIANTS = [(\"Candidate\", (\"tuple\", [\"u32\"])), (\"Abstain\", None)]\n    \
+                 _TAGS = [1, 0]\n"
+        ));
+    }
+
+    #[test]
+    fn test_optional_args() {
+        let optional = |name: &str| Field {
+            optional: true,
+            ..field(name, Type::Optional(box Type::U32))
+        };
+        let mut iface = ballot_interface();
+        iface.constructor.inputs.push(optional("limit"));
+        iface.functions[2].inputs = vec![optional("since"), field("count", Type::U32)];
+        iface.functions[3].inputs = vec![field("count", Type::U32), optional("since")];
+
+        let py = python(&iface);
+        assert!(py.contains(
+            "def deploy(cls, gateway: Gateway, bytecode: bytes, description: str, \
+             candidates: List[str], limit: Optional[int] = None) -> \"BallotClient\":"
+        ));
+        // Only trailing optional args can be omitted.
+        assert!(py.contains("def vote(self, since: Optional[int], count: int) -> None:"));
+        assert!(py.contains("def close(self, count: int, since: Optional[int] = None) -> None:"));
+    }
+}
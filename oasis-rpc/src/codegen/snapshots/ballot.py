@@ -0,0 +1,37 @@
+class BallotClient:
+    """A client for the `Ballot` service."""
+
+    def __init__(self, gateway: Gateway, address: bytes):
+        self.gateway = gateway
+        self.address = address
+
+    @classmethod
+    def deploy(cls, gateway: Gateway, bytecode: bytes, description: str, candidates: List[str]) -> "BallotClient":
+        buf = bytearray(bytecode)
+        _encode("string", description, buf)
+        _encode(("list", "string"), candidates, buf)
+        return cls(gateway, gateway.deploy(bytes(buf)))
+
+    def description(self) -> str:
+        buf = bytearray([0])
+        output = self.gateway.rpc(self.address, bytes(buf))
+        return _decode_output("string", output)
+
+    def candidates(self) -> List[str]:
+        buf = bytearray([1])
+        output = self.gateway.rpc(self.address, bytes(buf))
+        return _decode_output(("list", "string"), output)
+
+    def vote(self, candidate_num: int) -> None:
+        buf = bytearray([2])
+        _encode("u32", candidate_num, buf)
+        self.gateway.rpc(self.address, bytes(buf))
+
+    def close(self) -> None:
+        buf = bytearray([3])
+        self.gateway.rpc(self.address, bytes(buf))
+
+    def winner(self) -> int:
+        buf = bytearray([4])
+        output = self.gateway.rpc(self.address, bytes(buf))
+        return _decode_output("u32", output)
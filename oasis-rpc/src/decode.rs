@@ -0,0 +1,411 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Map, Value};
+
+use crate::{EnumFields, Interface, Type, TypeDef};
+
+impl Interface {
+    /// Decodes an RPC payload into the name of the called function and its arguments.
+    /// Each argument is decoded according to its IDL `Type` into a JSON `Value`.
+    /// This is the inverse of the encoding done by generated clients.
+    pub fn decode_call(&self, payload: &[u8]) -> Result<(String, Vec<Value>)> {
+        let mut decoder = Decoder {
+            iface: self,
+            buf: payload,
+        };
+        let func_idx = decoder.decode_tag()? as usize;
+        let func = self
+            .functions
+            .get(func_idx)
+            .ok_or_else(|| anyhow!("unknown function index: {}", func_idx))?;
+        let args = func
+            .inputs
+            .iter()
+            .map(|field| decoder.decode(&field.ty))
+            .collect::<Result<Vec<_>>>()?;
+        if !decoder.buf.is_empty() {
+            bail!("{} trailing bytes in payload", decoder.buf.len());
+        }
+        Ok((func.name.clone(), args))
+    }
+}
+
+struct Decoder<'a> {
+    iface: &'a Interface,
+    buf: &'a [u8],
+}
+
+macro_rules! decode_num {
+    ($self:ident, $ty:ty) => {{
+        let mut bytes = [0u8; std::mem::size_of::<$ty>()];
+        bytes.copy_from_slice($self.take(std::mem::size_of::<$ty>())?);
+        <$ty>::from_le_bytes(bytes)
+    }};
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            bail!("unexpected end of payload");
+        }
+        let (taken, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(taken)
+    }
+
+    fn decode_len(&mut self) -> Result<usize> {
+        Ok(decode_num!(self, u32) as usize)
+    }
+
+    fn decode_tag(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn decode(&mut self, ty: &Type) -> Result<Value> {
+        Ok(match ty {
+            Type::Bool => match self.decode_tag()? {
+                0 => json!(false),
+                1 => json!(true),
+                b => bail!("invalid bool: {}", b),
+            },
+            Type::U8 => json!(decode_num!(self, u8)),
+            Type::I8 => json!(decode_num!(self, i8)),
+            Type::U16 => json!(decode_num!(self, u16)),
+            Type::I16 => json!(decode_num!(self, i16)),
+            Type::U32 => json!(decode_num!(self, u32)),
+            Type::I32 => json!(decode_num!(self, i32)),
+            Type::U64 => json!(decode_num!(self, u64)),
+            Type::I64 => json!(decode_num!(self, i64)),
+            Type::F32 => json!(f32::from_bits(decode_num!(self, u32))),
+            Type::F64 => json!(f64::from_bits(decode_num!(self, u64))),
+            Type::Balance | Type::Duration => json!(decode_num!(self, u128).to_string()),
+            Type::U256 => json!(le_to_decimal(self.take(32)?)),
+            Type::I256 => json!(le_to_signed_decimal(self.take(32)?)),
+            Type::Bytes => {
+                let len = self.decode_len()?;
+                json!(to_hex(self.take(len)?))
+            }
+            Type::String => {
+                let len = self.decode_len()?;
+                json!(std::str::from_utf8(self.take(len)?)?)
+            }
+            Type::Address => json!(to_hex(self.take(20)?)),
+            Type::RpcError => match self.decode_tag()? {
+                0 => json!("InvalidCallee"),
+                1 => json!("InsufficientFunds"),
+                2 => json!("InsufficientGas"),
+                3 => json!({ "InvalidInput": self.decode(&Type::Optional(Box::new(Type::U32)))? }),
+                4 => json!({ "InvalidOutput": self.decode(&Type::Bytes)? }),
+                5 => json!({ "Execution": self.decode(&Type::Bytes)? }),
+                6 => json!({ "Gateway": self.decode(&Type::String)? }),
+                tag => bail!("invalid `RpcError` variant: {}", tag),
+            },
+            Type::Defined { namespace, ty } => {
+                if namespace.is_some() {
+                    bail!("cannot decode imported type `{}`", ty);
+                }
+                let def = self
+                    .iface
+                    .type_defs
+                    .iter()
+                    .find(|def| def.name() == ty)
+                    .ok_or_else(|| anyhow!("undefined type `{}`", ty))?;
+                self.decode_def(def)?
+            }
+            Type::Tuple(tys) => Value::Array(self.decode_seq(tys.iter())?),
+            Type::Array(ty, len) => {
+                Value::Array(self.decode_seq(std::iter::repeat(&**ty).take(*len as usize))?)
+            }
+            Type::List(ty) | Type::Set(ty) => {
+                let len = self.decode_len()?;
+                Value::Array(self.decode_seq(std::iter::repeat(&**ty).take(len))?)
+            }
+            Type::Map(k, v) => {
+                let len = self.decode_len()?;
+                let entries = (0..len)
+                    .map(|_| Ok(json!([self.decode(k)?, self.decode(v)?])))
+                    .collect::<Result<Vec<_>>>()?;
+                Value::Array(entries)
+            }
+            Type::Optional(ty) => match self.decode_tag()? {
+                0 => Value::Null,
+                1 => self.decode(ty)?,
+                tag => bail!("invalid `Option` tag: {}", tag),
+            },
+            Type::Result(ok_ty, err_ty) => match self.decode_tag()? {
+                0 => json!({ "Err": self.decode(err_ty)? }),
+                1 => json!({ "Ok": self.decode(ok_ty)? }),
+                tag => bail!("invalid `Result` tag: {}", tag),
+            },
+        })
+    }
+
+    fn decode_seq<'t>(&mut self, tys: impl Iterator<Item = &'t Type>) -> Result<Vec<Value>> {
+        tys.map(|ty| self.decode(ty)).collect()
+    }
+
+    fn decode_def(&mut self, def: &TypeDef) -> Result<Value> {
+        Ok(match def {
+            TypeDef::Struct { fields, .. } => {
+                if fields
+                    .iter()
+                    .enumerate()
+                    .all(|(i, f)| f.name == i.to_string())
+                {
+                    Value::Array(self.decode_seq(fields.iter().map(|f| &f.ty))?)
+                } else {
+                    let mut obj = Map::new();
+                    for field in fields {
+                        obj.insert(field.name.clone(), self.decode(&field.ty)?);
+                    }
+                    Value::Object(obj)
+                }
+            }
+            TypeDef::Event { fields, .. } => {
+                let mut obj = Map::new();
+                for field in fields {
+                    obj.insert(field.name.clone(), self.decode(&field.ty)?);
+                }
+                Value::Object(obj)
+            }
+            TypeDef::Enum { variants, .. } => {
+                let tag = self.decode_tag()?;
+                let variant = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(i, v)| v.tag.unwrap_or(*i as u8) == tag)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| anyhow!("invalid `{}` variant: {}", def.name(), tag))?;
+                match &variant.fields {
+                    None => json!(variant.name),
+                    Some(EnumFields::Tuple(tys)) => {
+                        json!({ variant.name.clone(): self.decode_seq(tys.iter())? })
+                    }
+                    Some(EnumFields::Named(fields)) => {
+                        let mut obj = Map::new();
+                        for field in fields {
+                            obj.insert(field.name.clone(), self.decode(&field.ty)?);
+                        }
+                        json!({ variant.name.clone(): obj })
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for b in bytes {
+        hex.push_str(&format!("{:02x}", b));
+    }
+    hex
+}
+
+/// Formats a little-endian unsigned integer of any width as a decimal string.
+fn le_to_decimal(le_bytes: &[u8]) -> String {
+    let mut be_digits: Vec<u8> = le_bytes.iter().rev().copied().collect(); // base 256
+    let mut decimal = Vec::new();
+    while be_digits.iter().any(|&d| d != 0) {
+        let mut rem = 0u32;
+        for digit in be_digits.iter_mut() {
+            let acc = (rem << 8) | u32::from(*digit);
+            *digit = (acc / 10) as u8;
+            rem = acc % 10;
+        }
+        decimal.push(b'0' + rem as u8);
+    }
+    if decimal.is_empty() {
+        decimal.push(b'0');
+    }
+    decimal.reverse();
+    String::from_utf8(decimal).unwrap()
+}
+
+/// Formats a little-endian two's complement integer of any width as a decimal string.
+fn le_to_signed_decimal(le_bytes: &[u8]) -> String {
+    if le_bytes.last().map(|b| b & 0x80 == 0).unwrap_or(true) {
+        return le_to_decimal(le_bytes);
+    }
+    let mut magnitude: Vec<u8> = le_bytes.iter().map(|b| !b).collect();
+    for digit in magnitude.iter_mut() {
+        let (sum, carry) = digit.overflowing_add(1);
+        *digit = sum;
+        if !carry {
+            break;
+        }
+    }
+    format!("-{}", le_to_decimal(&magnitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Constructor, EnumVariant, Field, Function, StateMutability};
+
+    fn erc20_interface() -> Interface {
+        Interface {
+            name: "ERC20Token".to_string(),
+            namespace: "erc20".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: Vec::new(),
+            constructor: Constructor {
+                inputs: vec![Field {
+                    name: "total_supply".to_string(),
+                    ty: Type::U64,
+                    optional: false,
+                }],
+                error: None,
+            },
+            functions: vec![
+                Function {
+                    name: "balance_of".to_string(),
+                    mutability: StateMutability::Immutable,
+                    inputs: Vec::new(),
+                    output: Some(Type::U64),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "transfer".to_string(),
+                    mutability: StateMutability::Mutable,
+                    inputs: vec![
+                        Field {
+                            name: "to".to_string(),
+                            ty: Type::Address,
+                            optional: false,
+                        },
+                        Field {
+                            name: "amount".to_string(),
+                            ty: Type::U64,
+                            optional: false,
+                        },
+                    ],
+                    output: None,
+                    estimated_gas: None,
+                },
+            ],
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_transfer() {
+        let mut payload = vec![1u8]; // `transfer`
+        payload.extend_from_slice(&[0xab; 20]);
+        payload.extend_from_slice(&500u64.to_le_bytes());
+
+        let (func, args) = erc20_interface().decode_call(&payload).unwrap();
+        assert_eq!(func, "transfer");
+        assert_eq!(
+            args,
+            vec![json!(format!("0x{}", "ab".repeat(20))), json!(500)]
+        );
+    }
+
+    #[test]
+    fn test_decode_list_of_tuples() {
+        let iface = erc20_interface();
+        let mut encoded = 2u32.to_le_bytes().to_vec();
+        encoded.extend_from_slice(&[0xab; 20]);
+        encoded.extend_from_slice(&500u64.to_le_bytes());
+        encoded.extend_from_slice(&[0xcd; 20]);
+        encoded.extend_from_slice(&7u64.to_le_bytes());
+
+        let mut decoder = Decoder {
+            iface: &iface,
+            buf: &encoded,
+        };
+        let entries = decoder
+            .decode(&Type::List(box Type::Tuple(vec![Type::Address, Type::U64])))
+            .unwrap();
+        assert!(decoder.buf.is_empty());
+        assert_eq!(
+            entries,
+            json!([
+                [format!("0x{}", "ab".repeat(20)), 500],
+                [format!("0x{}", "cd".repeat(20)), 7]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_tagged_enum() {
+        let mut iface = erc20_interface();
+        iface.type_defs.push(TypeDef::Enum {
+            name: "Status".to_string(),
+            variants: vec![
+                EnumVariant {
+                    name: "Frozen".to_string(),
+                    tag: Some(1),
+                    fields: Some(EnumFields::Tuple(vec![Type::U8])),
+                },
+                EnumVariant {
+                    name: "Active".to_string(),
+                    tag: Some(0),
+                    fields: None,
+                },
+            ],
+        });
+        let status_ty = Type::Defined {
+            namespace: None,
+            ty: "Status".to_string(),
+        };
+        let decode = |encoded: &[u8]| {
+            Decoder {
+                iface: &iface,
+                buf: encoded,
+            }
+            .decode(&status_ty)
+        };
+        assert_eq!(decode(&[0]).unwrap(), json!("Active"));
+        assert_eq!(decode(&[1, 7]).unwrap(), json!({ "Frozen": [7] }));
+        assert!(decode(&[2]).is_err());
+    }
+
+    #[test]
+    fn test_le_to_decimal() {
+        assert_eq!(le_to_decimal(&[0; 32]), "0");
+        assert_eq!(le_to_decimal(&1234u64.to_le_bytes()), "1234");
+        let mut u128_max_plus_one = [0u8; 32];
+        u128_max_plus_one[16] = 1;
+        assert_eq!(
+            le_to_decimal(&u128_max_plus_one),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            le_to_decimal(&[0xff; 32]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn test_le_to_signed_decimal() {
+        assert_eq!(le_to_signed_decimal(&[0; 32]), "0");
+        assert_eq!(le_to_signed_decimal(&[0xff; 32]), "-1");
+        assert_eq!(le_to_signed_decimal(&(-1234i64).to_le_bytes()), "-1234");
+        let mut i256_min = [0u8; 32];
+        i256_min[31] = 0x80;
+        assert_eq!(
+            le_to_signed_decimal(&i256_min),
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+        );
+        let mut i256_max = [0xffu8; 32];
+        i256_max[31] = 0x7f;
+        assert_eq!(
+            le_to_signed_decimal(&i256_max),
+            "57896044618658097711785492504343953926634992332820282019728792003956564819967"
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        let iface = erc20_interface();
+        assert!(iface.decode_call(&[]).is_err());
+        assert!(iface.decode_call(&[2]).is_err()); // no such function
+        assert!(iface.decode_call(&[1, 0xab]).is_err()); // truncated
+        assert!(iface.decode_call(&[0, 0]).is_err()); // trailing bytes
+    }
+}
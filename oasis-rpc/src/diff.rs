@@ -0,0 +1,365 @@
+use std::fmt;
+
+use colored::Colorize as _;
+
+use crate::{EnumFields, Field, Function, Import, Interface, StateMutability, Type, TypeDef};
+
+impl Interface {
+    /// Returns the functions, type defs, and imports that were added, removed, or changed
+    /// between `self` (the old interface) and `other` (the new interface).
+    /// Items are matched by name. The advisory `estimated_gas` of functions is ignored.
+    pub fn diff(&self, other: &Interface) -> InterfaceDiff {
+        let without_gas = |functions: &[Function]| -> Vec<Function> {
+            functions
+                .iter()
+                .cloned()
+                .map(|f| Function {
+                    estimated_gas: None,
+                    ..f
+                })
+                .collect()
+        };
+        InterfaceDiff {
+            functions: diff_items(
+                &without_gas(&self.functions),
+                &without_gas(&other.functions),
+                |f| &f.name,
+            ),
+            type_defs: diff_items(&self.type_defs, &other.type_defs, |td| td.name()),
+            imports: diff_items(&self.imports, &other.imports, |imp| &imp.name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceDiff {
+    pub functions: Vec<Change<Function>>,
+    pub type_defs: Vec<Change<TypeDef>>,
+    pub imports: Vec<Change<Import>>,
+}
+
+impl InterfaceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.type_defs.is_empty() && self.imports.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    Changed { old: T, new: T },
+}
+
+fn diff_items<T: Clone + PartialEq>(
+    old: &[T],
+    new: &[T],
+    name: impl Fn(&T) -> &str,
+) -> Vec<Change<T>> {
+    let mut changes = Vec::new();
+    for old_item in old {
+        match new.iter().find(|new_item| name(new_item) == name(old_item)) {
+            Some(new_item) if new_item != old_item => changes.push(Change::Changed {
+                old: old_item.clone(),
+                new: new_item.clone(),
+            }),
+            Some(_) => (),
+            None => changes.push(Change::Removed(old_item.clone())),
+        }
+    }
+    for new_item in new {
+        if !old.iter().any(|old_item| name(old_item) == name(new_item)) {
+            changes.push(Change::Added(new_item.clone()));
+        }
+    }
+    changes
+}
+
+impl fmt::Display for InterfaceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_section(f, "functions", &self.functions)?;
+        fmt_section(f, "type defs", &self.type_defs)?;
+        fmt_section(f, "imports", &self.imports)
+    }
+}
+
+fn fmt_section<T>(f: &mut fmt::Formatter, title: &str, changes: &[Change<T>]) -> fmt::Result
+where
+    for<'a> Sig<'a, T>: fmt::Display,
+{
+    if changes.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{}:", title.bold())?;
+    for change in changes {
+        match change {
+            Change::Added(item) => writeln!(f, "{}", format!("+ {}", Sig(item)).green())?,
+            Change::Removed(item) => writeln!(f, "{}", format!("- {}", Sig(item)).red())?,
+            Change::Changed { old, new } => {
+                writeln!(f, "{}", format!("- {}", Sig(old)).red())?;
+                writeln!(f, "{}", format!("+ {}", Sig(new)).green())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders an IDL item using Rust-like syntax.
+struct Sig<'a, T>(&'a T);
+
+impl fmt::Display for Sig<'_, Function> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let func = self.0;
+        let receiver = match func.mutability {
+            StateMutability::Immutable => "&self",
+            StateMutability::Mutable => "&mut self",
+        };
+        write!(f, "fn {}({}", func.name, receiver)?;
+        for input in func.inputs.iter() {
+            write!(f, ", {}", Sig(input))?;
+        }
+        write!(f, ")")?;
+        if let Some(output) = &func.output {
+            write!(f, " -> {}", Sig(output))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Sig<'_, TypeDef> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            TypeDef::Struct { name, fields } => {
+                write!(f, "struct {} {{ ", name)?;
+                fmt_list(f, fields.iter().map(Sig))?;
+                write!(f, " }}")
+            }
+            TypeDef::Enum { name, variants } => {
+                write!(f, "enum {} {{ ", name)?;
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if let Some(tag) = variant.tag {
+                        write!(f, "#[oasis(tag = {})] ", tag)?;
+                    }
+                    write!(f, "{}", variant.name)?;
+                    match &variant.fields {
+                        Some(EnumFields::Named(fields)) => {
+                            write!(f, " {{ ")?;
+                            fmt_list(f, fields.iter().map(Sig))?;
+                            write!(f, " }}")?;
+                        }
+                        Some(EnumFields::Tuple(tys)) => {
+                            write!(f, "(")?;
+                            fmt_list(f, tys.iter().map(Sig))?;
+                            write!(f, ")")?;
+                        }
+                        None => (),
+                    }
+                }
+                write!(f, " }}")
+            }
+            TypeDef::Event { name, fields } => {
+                write!(f, "event {} {{ ", name)?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if field.indexed {
+                        write!(f, "#[indexed] ")?;
+                    }
+                    write!(f, "{}: {}", field.name, Sig(&field.ty))?;
+                }
+                write!(f, " }}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Sig<'_, Import> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let import = self.0;
+        write!(f, "{} {}", import.name, import.version)?;
+        if let Some(registry) = &import.registry {
+            write!(f, " ({})", registry)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Sig<'_, Field> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.0.name, Sig(&self.0.ty))
+    }
+}
+
+impl fmt::Display for Sig<'_, Type> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Type::Bool => write!(f, "bool"),
+            Type::U8 => write!(f, "u8"),
+            Type::I8 => write!(f, "i8"),
+            Type::U16 => write!(f, "u16"),
+            Type::I16 => write!(f, "i16"),
+            Type::U32 => write!(f, "u32"),
+            Type::I32 => write!(f, "i32"),
+            Type::U64 => write!(f, "u64"),
+            Type::I64 => write!(f, "i64"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::Bytes => write!(f, "Vec<u8>"),
+            Type::String => write!(f, "String"),
+            Type::Address => write!(f, "Address"),
+            Type::Balance => write!(f, "Balance"),
+            Type::U256 => write!(f, "U256"),
+            Type::I256 => write!(f, "I256"),
+            Type::RpcError => write!(f, "RpcError"),
+            Type::Duration => write!(f, "Duration"),
+            Type::Defined {
+                namespace: Some(namespace),
+                ty,
+            } => write!(f, "{}::{}", namespace, ty),
+            Type::Defined {
+                namespace: None,
+                ty,
+            } => write!(f, "{}", ty),
+            Type::Tuple(tys) => {
+                write!(f, "(")?;
+                fmt_list(f, tys.iter().map(Sig))?;
+                write!(f, ")")
+            }
+            Type::Array(ty, len) => write!(f, "[{}; {}]", Sig(&**ty), len),
+            Type::List(ty) => write!(f, "Vec<{}>", Sig(&**ty)),
+            Type::Set(ty) => write!(f, "Set<{}>", Sig(&**ty)),
+            Type::Map(k, v) => write!(f, "Map<{}, {}>", Sig(&**k), Sig(&**v)),
+            Type::Optional(ty) => write!(f, "Option<{}>", Sig(&**ty)),
+            Type::Result(ok, err) => write!(f, "Result<{}, {}>", Sig(&**ok), Sig(&**err)),
+        }
+    }
+}
+
+fn fmt_list<D: fmt::Display>(
+    f: &mut fmt::Formatter,
+    items: impl Iterator<Item = D>,
+) -> fmt::Result {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Constructor;
+
+    fn field(name: &str, ty: Type) -> Field {
+        Field {
+            name: name.to_string(),
+            ty,
+            optional: false,
+        }
+    }
+
+    fn base_interface() -> Interface {
+        Interface {
+            name: "Counter".to_string(),
+            namespace: "counter".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: vec![TypeDef::Struct {
+                name: "Stats".to_string(),
+                fields: vec![field("count", Type::U64)],
+            }],
+            constructor: Constructor {
+                inputs: Vec::new(),
+                error: None,
+            },
+            functions: vec![
+                Function {
+                    name: "get".to_string(),
+                    mutability: StateMutability::Immutable,
+                    inputs: Vec::new(),
+                    output: Some(Type::U64),
+                    estimated_gas: None,
+                },
+                Function {
+                    name: "incr".to_string(),
+                    mutability: StateMutability::Mutable,
+                    inputs: vec![field("by", Type::U32)],
+                    output: None,
+                    estimated_gas: None,
+                },
+            ],
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let iface = base_interface();
+        assert!(iface.diff(&iface).is_empty());
+        assert_eq!(iface.diff(&iface).to_string(), "");
+    }
+
+    #[test]
+    fn test_diff_ignores_estimated_gas() {
+        let old = base_interface();
+        let mut new = base_interface();
+        new.functions[1].estimated_gas = Some(12_000);
+        assert!(old.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_function_signature() {
+        colored::control::set_override(false);
+
+        let old = base_interface();
+        let mut new = base_interface();
+        new.functions[1].inputs[0].ty = Type::U64;
+        new.functions[1].output = Some(Type::Result(box Type::U64, box Type::String));
+
+        assert_eq!(
+            old.diff(&new).to_string(),
+            "functions:\n\
+             - fn incr(&mut self, by: u32)\n\
+             + fn incr(&mut self, by: u64) -> Result<u64, String>\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_struct_field() {
+        colored::control::set_override(false);
+
+        let old = base_interface();
+        let mut new = base_interface();
+        new.type_defs[0] = TypeDef::Struct {
+            name: "Stats".to_string(),
+            fields: vec![
+                field("count", Type::U64),
+                field("last", Type::Optional(box Type::Address)),
+            ],
+        };
+        new.imports.push(Import {
+            name: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+            version_req: None,
+            registry: None,
+        });
+
+        assert_eq!(
+            old.diff(&new).to_string(),
+            "type defs:\n\
+             - struct Stats { count: u64 }\n\
+             + struct Stats { count: u64, last: Option<Address> }\n\
+             imports:\n\
+             + oracle 1.0.0\n"
+        );
+    }
+}
@@ -10,6 +10,9 @@ pub struct Interface {
     pub constructor: Constructor,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub functions: Vec<Function>,
+    /// The name of the function (in `functions`) that is called when the input is empty.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default_function: Option<Ident>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub oasis_build_version: Option<String>,
 }
@@ -24,6 +27,9 @@ pub struct Function {
     pub inputs: Vec<Field>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub output: Option<Type>,
+    /// An advisory estimate of the most gas that a call to the function will use.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub estimated_gas: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
@@ -58,11 +64,19 @@ pub struct Field {
     pub name: Ident,
     #[serde(rename = "type")]
     pub ty: Type,
+    /// Whether this is an `Option` argument that clients may omit, which passes `None`.
+    /// Only function and constructor inputs are optional.
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub optional: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
 pub struct EnumVariant {
     pub name: Ident,
+    /// The tag that encodes this variant, if pinned using `#[oasis(tag = N)]`.
+    /// Variants without a tag are encoded using their index.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tag: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub fields: Option<EnumFields>,
 }
@@ -86,7 +100,11 @@ pub struct IndexedField {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum StateMutability {
+    /// The RPC takes `&self`. Clients send it as a read-only query that costs no gas.
     Immutable,
+
+    /// The RPC takes `&mut self`. Clients send it as a transaction, even if the RPC
+    /// never actually modifies state.
     Mutable,
 }
 
@@ -94,6 +112,9 @@ pub enum StateMutability {
 pub struct Import {
     pub name: Ident,
     pub version: String,
+    /// The Cargo-style version requirement (e.g., `^1.2`) that `version` was checked against.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version_req: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub registry: Option<String>,
 }
@@ -122,7 +143,10 @@ pub enum Type {
     String,
     Address,
     Balance,
+    U256, // encoded as 32 little-endian bytes
+    I256, // encoded as 32 little-endian bytes in two's complement
     RpcError,
+    Duration, // encoded as a `u128` number of nanoseconds
     Defined {
         #[serde(skip_serializing_if = "Option::is_none", default)]
         namespace: Option<Ident>, // `None` if local, otherwise refers to an entry in `Imports`
@@ -14,12 +14,58 @@ impl ImporterBackend for FileImporter {
     }
 
     fn import_all(&self) -> Result<Vec<ImportedService>, ImportError> {
-        let bytecode = std::fs::read(&self.path)
+        let bytes = std::fs::read(&self.path)
             .map_err(|err| ImportError::Io(self.path.display().to_string(), err))?;
-        let interface = Interface::from_wasm_bytecode(&bytecode).map_err(ImportError::Importer)?;
+        // A JSON file contains only the interface, so the service cannot be deployed.
+        if self.path.extension() == Some(std::ffi::OsStr::new("json")) {
+            let interface =
+                serde_json::from_slice(&bytes).map_err(|err| ImportError::Importer(err.into()))?;
+            return Ok(vec![ImportedService {
+                bytecode: Vec::new(),
+                interface,
+            }]);
+        }
+        let interface = Interface::from_wasm_bytecode(&bytes).map_err(ImportError::Importer)?;
         Ok(vec![ImportedService {
-            bytecode,
+            bytecode: bytes,
             interface,
         }])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_json() {
+        let iface = Interface {
+            name: "Counter".to_string(),
+            namespace: "counter".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: Vec::new(),
+            constructor: crate::Constructor {
+                inputs: Vec::new(),
+                error: None,
+            },
+            functions: Vec::new(),
+            default_function: None,
+            oasis_build_version: None,
+        };
+        let path = std::env::temp_dir().join("oasis-rpc-test-import-json.json");
+        std::fs::write(&path, serde_json::to_vec(&iface).unwrap()).unwrap();
+
+        let importer = FileImporter { path };
+        let service = importer.import("counter").unwrap();
+        assert_eq!(service.interface, iface);
+        assert!(service.bytecode.is_empty());
+
+        std::fs::write(&importer.path, b"{}").unwrap();
+        match importer.import("counter") {
+            Err(ImportError::Importer(_)) => (),
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
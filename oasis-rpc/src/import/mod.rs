@@ -39,6 +39,19 @@ impl Importer {
         }
     }
 
+    /// Returns the interface of the service deployed at `address`, which is read from
+    /// the `oasis-interface` section of the service's bytecode.
+    #[cfg(feature = "gateway")]
+    pub fn from_gateway(
+        gateway: &dyn oasis_client::Gateway,
+        address: oasis_types::Address,
+    ) -> Result<crate::Interface, ImportError> {
+        let bytecode = gateway
+            .code(address)
+            .map_err(|err| ImportError::Importer(err.into()))?;
+        crate::Interface::from_wasm_bytecode(&bytecode).map_err(ImportError::Importer)
+    }
+
     pub fn import(&self, name: &str) -> Result<ImportedService, ImportError> {
         self.backend.import(name)
     }
@@ -114,3 +127,75 @@ pub enum ImportError {
     #[error("importer error: {0}")]
     Importer(anyhow::Error),
 }
+
+#[cfg(all(test, feature = "gateway"))]
+mod tests {
+    use super::*;
+
+    use oasis_client::{Gateway as _, MemchainGateway};
+    use oasis_types::Address;
+
+    extern "C" fn nop_main(_ptx: memchain::PtxPtr) -> u16 {
+        0
+    }
+
+    /// Returns an empty Wasm module with an `oasis-interface` custom section.
+    fn service_bytecode(iface: &crate::Interface) -> Vec<u8> {
+        fn push_leb128(buf: &mut Vec<u8>, mut n: usize) {
+            loop {
+                let byte = (n & 0x7f) as u8;
+                n >>= 7;
+                if n == 0 {
+                    buf.push(byte);
+                    break;
+                }
+                buf.push(byte | 0x80);
+            }
+        }
+
+        let section_name = b"oasis-interface";
+        let mut section = Vec::new();
+        push_leb128(&mut section, section_name.len());
+        section.extend_from_slice(section_name);
+        section.extend_from_slice(&iface.to_vec().unwrap());
+
+        let mut bytecode = b"\0asm\x01\0\0\0".to_vec();
+        bytecode.push(0); // custom section id
+        push_leb128(&mut bytecode, section.len());
+        bytecode.append(&mut section);
+        bytecode
+    }
+
+    #[test]
+    fn test_from_gateway() {
+        let iface = crate::Interface {
+            name: "Counter".to_string(),
+            namespace: "counter".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: Vec::new(),
+            constructor: crate::Constructor {
+                inputs: Vec::new(),
+                error: None,
+            },
+            functions: Vec::new(),
+            default_function: None,
+            oasis_build_version: None,
+        };
+        let bytecode = service_bytecode(&iface);
+
+        let sender = Address([1u8; 20]);
+        let mut genesis_state = std::collections::HashMap::new();
+        genesis_state.insert(sender, Default::default());
+        let memchain = memchain::Memchain::new("testnet", genesis_state, 0u64);
+        let mut gateway = MemchainGateway::new(memchain, sender);
+        gateway.register(&bytecode, nop_main, nop_main);
+        let address = gateway.deploy(&bytecode, Default::default()).unwrap();
+
+        assert_eq!(Importer::from_gateway(&gateway, address).unwrap(), iface);
+        match Importer::from_gateway(&gateway, Address([9u8; 20])) {
+            Err(ImportError::Importer(_)) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
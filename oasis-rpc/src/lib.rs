@@ -4,7 +4,16 @@
 extern crate serde;
 
 mod idl;
+mod merge;
+mod validate;
 
+#[cfg(feature = "saveload")]
+mod decode;
+#[cfg(feature = "diff")]
+mod diff;
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
 #[cfg(feature = "import")]
 pub mod import;
 #[cfg(feature = "visitor")]
@@ -13,12 +22,43 @@ pub mod visitor;
 #[cfg(feature = "saveload")]
 use anyhow::{anyhow, Result};
 
+#[cfg(feature = "diff")]
+pub use diff::{Change, InterfaceDiff};
 pub use idl::*;
+pub use merge::MergeError;
+pub use validate::ValidationError;
+
+/// How a packed interface is compressed.
+#[cfg(feature = "saveload")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Deflated JSON.
+    Deflate,
+
+    /// Uncompressed JSON prefixed by `RAW_JSON_MAGIC`.
+    None,
+}
+
+#[cfg(feature = "saveload")]
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Deflate
+    }
+}
+
+/// Marks an uncompressed interface. No deflate stream begins with this byte
+/// because it specifies the reserved block type `0b11`.
+#[cfg(feature = "saveload")]
+const RAW_JSON_MAGIC: u8 = 0xff;
 
 #[cfg(feature = "saveload")]
 impl Interface {
+    /// Decodes an interface packed by `to_vec_with` using either compression.
     pub fn from_slice(sl: &[u8]) -> Result<crate::Interface> {
         use std::io::Read as _;
+        if let Some((&RAW_JSON_MAGIC, json)) = sl.split_first() {
+            return Ok(serde_json::from_slice(json)?);
+        }
         let mut decoder = libflate::deflate::Decoder::new(sl);
         let mut inflated = Vec::new();
         decoder.read_to_end(&mut inflated)?;
@@ -26,9 +66,35 @@ impl Interface {
     }
 
     pub fn to_vec(&self) -> Result<Vec<u8>> {
-        let mut encoder = libflate::deflate::Encoder::new(Vec::new());
-        serde_json::to_writer(&mut encoder, self)?;
-        Ok(encoder.finish().into_result()?)
+        self.to_vec_with(Compression::default())
+    }
+
+    pub fn to_vec_with(&self, compression: Compression) -> Result<Vec<u8>> {
+        match compression {
+            Compression::Deflate => {
+                let mut encoder = libflate::deflate::Encoder::new(Vec::new());
+                serde_json::to_writer(&mut encoder, self)?;
+                Ok(encoder.finish().into_result()?)
+            }
+            Compression::None => {
+                let mut packed = vec![RAW_JSON_MAGIC];
+                serde_json::to_writer(&mut packed, self)?;
+                Ok(packed)
+            }
+        }
+    }
+
+    /// Returns the keccak256 hash of the interface with its imports, type defs, and
+    /// functions sorted by name, so that the hash does not depend on declaration order.
+    ///
+    /// **Note**: the selector of an RPC is the index of its function, so interfaces
+    /// with the same hash are not necessarily compatible on the wire.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut canonical = self.clone();
+        canonical.imports.sort_by(|a, b| a.name.cmp(&b.name));
+        canonical.type_defs.sort_by(|a, b| a.name().cmp(b.name()));
+        canonical.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        tiny_keccak::keccak256(&serde_json::to_vec(&canonical).unwrap())
     }
 
     pub fn to_string(&self) -> Result<String> {
@@ -59,3 +125,93 @@ impl Interface {
             .and_then(Self::from_slice)
     }
 }
+
+#[cfg(all(test, feature = "saveload"))]
+mod tests {
+    use super::*;
+
+    fn test_interface() -> Interface {
+        Interface {
+            name: "Counter".to_string(),
+            namespace: "counter".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs: Vec::new(),
+            constructor: Constructor {
+                inputs: Vec::new(),
+                error: None,
+            },
+            functions: vec![Function {
+                name: "incr".to_string(),
+                mutability: StateMutability::Mutable,
+                inputs: vec![Field {
+                    name: "by".to_string(),
+                    ty: Type::U32,
+                    optional: false,
+                }],
+                output: Some(Type::U64),
+                estimated_gas: None,
+            }],
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_deflate() {
+        let iface = test_interface();
+        let packed = iface.to_vec_with(Compression::Deflate).unwrap();
+        assert_eq!(packed, iface.to_vec().unwrap());
+        assert_ne!(packed[0], RAW_JSON_MAGIC);
+        assert_eq!(Interface::from_slice(&packed).unwrap(), iface);
+    }
+
+    #[test]
+    fn test_roundtrip_raw() {
+        let iface = test_interface();
+        let packed = iface.to_vec_with(Compression::None).unwrap();
+        assert_eq!(packed[0], RAW_JSON_MAGIC);
+        assert_eq!(
+            serde_json::from_slice::<Interface>(&packed[1..]).unwrap(),
+            iface
+        );
+        assert_eq!(Interface::from_slice(&packed).unwrap(), iface);
+    }
+
+    #[test]
+    fn test_content_hash_ignores_order() {
+        let iface = test_interface();
+        let mut reordered = test_interface();
+        reordered.functions.insert(
+            0,
+            Function {
+                name: "get".to_string(),
+                mutability: StateMutability::Immutable,
+                inputs: Vec::new(),
+                output: Some(Type::U64),
+                estimated_gas: None,
+            },
+        );
+        let mut appended = test_interface();
+        appended.functions.push(reordered.functions[0].clone());
+
+        assert_ne!(reordered, appended);
+        assert_eq!(reordered.content_hash(), appended.content_hash());
+        assert_ne!(iface.content_hash(), appended.content_hash());
+
+        let mut changed = test_interface();
+        changed.functions[0].output = Some(Type::U32);
+        assert_ne!(iface.content_hash(), changed.content_hash());
+    }
+
+    #[test]
+    fn test_detect_compression() {
+        let iface = test_interface();
+        let raw = iface.to_vec_with(Compression::None).unwrap();
+        // Raw JSON is not mistaken for a deflate stream, nor vice versa.
+        assert!(Interface::from_slice(&raw[1..]).is_err());
+        let mut deflated = vec![RAW_JSON_MAGIC];
+        deflated.extend(iface.to_vec_with(Compression::Deflate).unwrap());
+        assert!(Interface::from_slice(&deflated).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::Interface;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum MergeError {
+    #[error("function `{0}` is defined by both interfaces")]
+    DuplicateFunction(String),
+
+    #[error("type `{0}` is defined differently by each interface")]
+    ConflictingTypeDef(String),
+
+    #[error("`{0}` is imported differently by each interface")]
+    ConflictingImport(String),
+}
+
+impl Interface {
+    /// Adds the functions, type defs, and imports of `other` to this interface, e.g., to
+    /// build the interface of a service whose RPCs are split across several facets.
+    /// The name, constructor, and default function of `self` are kept.
+    ///
+    /// Type defs and imports that are identical in both interfaces are merged. Any other
+    /// item that appears in both is a conflict, in which case `self` is left unchanged.
+    ///
+    /// **Note**: the selector of an RPC is the index of its function, so the functions of
+    /// `other` are appended to preserve the selectors of the functions of `self`.
+    pub fn merge(&mut self, other: Interface) -> Result<(), MergeError> {
+        if let Some(f) = other.functions.iter().find(|f| {
+            self.functions
+                .iter()
+                .any(|existing| existing.name == f.name)
+        }) {
+            return Err(MergeError::DuplicateFunction(f.name.clone()));
+        }
+        if let Some(td) = other.type_defs.iter().find(|td| {
+            self.type_defs
+                .iter()
+                .any(|existing| existing.name() == td.name() && existing != *td)
+        }) {
+            return Err(MergeError::ConflictingTypeDef(td.name().to_string()));
+        }
+        if let Some(imp) = other.imports.iter().find(|imp| {
+            self.imports
+                .iter()
+                .any(|existing| existing.name == imp.name && existing != *imp)
+        }) {
+            return Err(MergeError::ConflictingImport(imp.name.clone()));
+        }
+
+        self.functions.extend(other.functions);
+        for td in other.type_defs {
+            if !self.type_defs.contains(&td) {
+                self.type_defs.push(td);
+            }
+        }
+        for imp in other.imports {
+            if !self.imports.contains(&imp) {
+                self.imports.push(imp);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Constructor, Field, Function, StateMutability, Type, TypeDef};
+
+    fn function(name: &str, output: Type) -> Function {
+        Function {
+            name: name.to_string(),
+            mutability: StateMutability::Immutable,
+            inputs: Vec::new(),
+            output: Some(output),
+            estimated_gas: None,
+        }
+    }
+
+    fn point(coord_ty: Type) -> TypeDef {
+        TypeDef::Struct {
+            name: "Point".to_string(),
+            fields: vec!["x", "y"]
+                .into_iter()
+                .map(|name| Field {
+                    name: name.to_string(),
+                    ty: coord_ty.clone(),
+                    optional: false,
+                })
+                .collect(),
+        }
+    }
+
+    fn facet(functions: Vec<Function>, type_defs: Vec<TypeDef>) -> Interface {
+        Interface {
+            name: "Canvas".to_string(),
+            namespace: "canvas".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs,
+            constructor: Constructor {
+                inputs: Vec::new(),
+                error: None,
+            },
+            functions,
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    fn point_ty() -> Type {
+        Type::Defined {
+            namespace: None,
+            ty: "Point".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut iface = facet(vec![function("origin", point_ty())], vec![point(Type::I32)]);
+        let other = facet(
+            vec![function("cursor", point_ty()), function("zoom", Type::U8)],
+            vec![point(Type::I32)],
+        );
+        iface.merge(other).unwrap();
+
+        let fn_names: Vec<_> = iface.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fn_names, vec!["origin", "cursor", "zoom"]);
+        assert_eq!(iface.type_defs, vec![point(Type::I32)]);
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let iface = facet(vec![function("origin", point_ty())], vec![point(Type::I32)]);
+
+        let mut merged = iface.clone();
+        assert_eq!(
+            merged.merge(facet(vec![function("origin", Type::U8)], Vec::new())),
+            Err(MergeError::DuplicateFunction("origin".to_string()))
+        );
+        assert_eq!(merged, iface);
+
+        assert_eq!(
+            merged.merge(facet(
+                vec![function("cursor", point_ty())],
+                vec![point(Type::F64)]
+            )),
+            Err(MergeError::ConflictingTypeDef("Point".to_string()))
+        );
+        assert_eq!(merged, iface);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{EnumFields, Interface, Type, TypeDef};
+
+/// The maximum number of indexed fields of an event. The first of the four topics
+/// supported by the host is reserved for the event's name.
+const MAX_INDEXED_FIELDS: usize = 3;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("function `{0}` is defined more than once")]
+    DuplicateFunction(String),
+
+    #[error("type `{0}` is defined more than once")]
+    DuplicateTypeDef(String),
+
+    #[error("type `{0}` is not defined")]
+    UndefinedType(String),
+
+    #[error("type `{ty}` refers to `{namespace}`, which is not imported")]
+    UnknownImport { namespace: String, ty: String },
+
+    #[error("event `{event}` has {num_indexed} indexed fields, but at most 3 are allowed")]
+    TooManyIndexedFields { event: String, num_indexed: usize },
+
+    #[error("the default function `{0}` is not defined")]
+    UndefinedDefaultFunction(String),
+}
+
+impl Interface {
+    /// Checks that the interface is well-formed, e.g., before it is packed. All of the
+    /// problems found are returned.
+    ///
+    /// The functions and type defs must have unique names, every `Type::Defined` must
+    /// refer to a type def or import, the default function must exist, and events may
+    /// have at most three indexed fields.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        for (i, f) in self.functions.iter().enumerate() {
+            if self.functions[..i].iter().any(|prev| prev.name == f.name) {
+                errors.push(ValidationError::DuplicateFunction(f.name.clone()));
+            }
+        }
+        for (i, def) in self.type_defs.iter().enumerate() {
+            if self.type_defs[..i]
+                .iter()
+                .any(|prev| prev.name() == def.name())
+            {
+                errors.push(ValidationError::DuplicateTypeDef(def.name().to_string()));
+            }
+        }
+
+        if let Some(default_fn) = &self.default_function {
+            if !self.functions.iter().any(|f| &f.name == default_fn) {
+                errors.push(ValidationError::UndefinedDefaultFunction(
+                    default_fn.clone(),
+                ));
+            }
+        }
+
+        for def in self.type_defs.iter() {
+            if let TypeDef::Event { name, fields } = def {
+                let num_indexed = fields.iter().filter(|f| f.indexed).count();
+                if num_indexed > MAX_INDEXED_FIELDS {
+                    errors.push(ValidationError::TooManyIndexedFields {
+                        event: name.clone(),
+                        num_indexed,
+                    });
+                }
+            }
+        }
+
+        let mut tys = Vec::new();
+        for def in self.type_defs.iter() {
+            match def {
+                TypeDef::Struct { fields, .. } => tys.extend(fields.iter().map(|f| &f.ty)),
+                TypeDef::Event { fields, .. } => tys.extend(fields.iter().map(|f| &f.ty)),
+                TypeDef::Enum { variants, .. } => {
+                    for variant in variants.iter() {
+                        match &variant.fields {
+                            Some(EnumFields::Named(fields)) => {
+                                tys.extend(fields.iter().map(|f| &f.ty))
+                            }
+                            Some(EnumFields::Tuple(variant_tys)) => tys.extend(variant_tys),
+                            None => (),
+                        }
+                    }
+                }
+            }
+        }
+        tys.extend(self.constructor.inputs.iter().map(|f| &f.ty));
+        tys.extend(self.constructor.error.iter());
+        for f in self.functions.iter() {
+            tys.extend(f.inputs.iter().map(|f| &f.ty));
+            tys.extend(f.output.iter());
+        }
+        for ty in tys {
+            self.check_defined(ty, &mut errors);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_defined(&self, ty: &Type, errors: &mut Vec<ValidationError>) {
+        match ty {
+            Type::Defined {
+                namespace: None,
+                ty,
+            } => {
+                let is_defined = self.type_defs.iter().any(|def| def.name() == ty);
+                let error = ValidationError::UndefinedType(ty.clone());
+                if !is_defined && !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+            Type::Defined {
+                namespace: Some(namespace),
+                ty,
+            } => {
+                let is_imported = self.imports.iter().any(|imp| &imp.name == namespace);
+                let error = ValidationError::UnknownImport {
+                    namespace: namespace.clone(),
+                    ty: ty.clone(),
+                };
+                if !is_imported && !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+            Type::Tuple(tys) => tys.iter().for_each(|ty| self.check_defined(ty, errors)),
+            Type::Array(ty, _) | Type::List(ty) | Type::Set(ty) | Type::Optional(ty) => {
+                self.check_defined(ty, errors)
+            }
+            Type::Map(ty0, ty1) | Type::Result(ty0, ty1) => {
+                self.check_defined(ty0, errors);
+                self.check_defined(ty1, errors);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Constructor, Field, Function, IndexedField, StateMutability};
+
+    fn function(name: &str, output: Type) -> Function {
+        Function {
+            name: name.to_string(),
+            mutability: StateMutability::Immutable,
+            inputs: Vec::new(),
+            output: Some(output),
+            estimated_gas: None,
+        }
+    }
+
+    fn defined(ty: &str) -> Type {
+        Type::Defined {
+            namespace: None,
+            ty: ty.to_string(),
+        }
+    }
+
+    fn transfer_event(num_indexed: usize) -> TypeDef {
+        TypeDef::Event {
+            name: "Transfer".to_string(),
+            fields: vec!["from", "to", "token", "amount"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| IndexedField {
+                    name: name.to_string(),
+                    ty: Type::U64,
+                    indexed: i < num_indexed,
+                })
+                .collect(),
+        }
+    }
+
+    fn interface(functions: Vec<Function>, type_defs: Vec<TypeDef>) -> Interface {
+        Interface {
+            name: "Market".to_string(),
+            namespace: "market".to_string(),
+            version: "0.1.0".to_string(),
+            imports: Vec::new(),
+            type_defs,
+            constructor: Constructor {
+                inputs: vec![Field {
+                    name: "listings".to_string(),
+                    ty: Type::List(box defined("Listing")),
+                    optional: false,
+                }],
+                error: None,
+            },
+            functions,
+            default_function: None,
+            oasis_build_version: None,
+        }
+    }
+
+    fn listing() -> TypeDef {
+        TypeDef::Struct {
+            name: "Listing".to_string(),
+            fields: vec![Field {
+                name: "price".to_string(),
+                ty: Type::U64,
+                optional: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        let iface = interface(
+            vec![function("listing", Type::Optional(box defined("Listing")))],
+            vec![listing(), transfer_event(3)],
+        );
+        assert_eq!(iface.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_undefined_type() {
+        let iface = interface(
+            vec![
+                function("listing", Type::Optional(box defined("Listing"))),
+                function("offers", Type::Map(box Type::U64, box defined("Offer"))),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(
+            iface.validate(),
+            Err(vec![
+                // `Listing` is reported once despite being used twice.
+                ValidationError::UndefinedType("Listing".to_string()),
+                ValidationError::UndefinedType("Offer".to_string()),
+            ])
+        );
+
+        let mut iface = interface(Vec::new(), vec![listing()]);
+        iface.functions.push(function(
+            "price",
+            Type::Defined {
+                namespace: Some("oracle".to_string()),
+                ty: "Price".to_string(),
+            },
+        ));
+        assert_eq!(
+            iface.validate(),
+            Err(vec![ValidationError::UnknownImport {
+                namespace: "oracle".to_string(),
+                ty: "Price".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_function() {
+        let mut iface = interface(
+            vec![function("price", Type::U64), function("price", Type::U32)],
+            vec![listing()],
+        );
+        iface.default_function = Some("buy".to_string());
+        assert_eq!(
+            iface.validate(),
+            Err(vec![
+                ValidationError::DuplicateFunction("price".to_string()),
+                ValidationError::UndefinedDefaultFunction("buy".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_too_many_indexed_fields() {
+        let iface = interface(Vec::new(), vec![listing(), transfer_event(4)]);
+        assert_eq!(
+            iface.validate(),
+            Err(vec![ValidationError::TooManyIndexedFields {
+                event: "Transfer".to_string(),
+                num_indexed: 4,
+            }])
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! The canonical encoding of floating-point numbers.
+//!
+//! A float is encoded as its IEEE 754 bits, so values that compare equal can have
+//! different encodings, and NaN does not even compare equal to itself. For every node
+//! to agree on the encoding of a value, RPC arguments and outputs are canonicalized
+//! before they are used or encoded:
+//!
+//! * NaN is rejected with `RpcError::InvalidInput`.
+//! * `-0.0` is normalized to `0.0`.
+//!
+//! Floats are canonicalized wherever they occur in an argument or output of a standard
+//! type, e.g., in an `Option`, `Result`, `Vec`, tuple, array, or the values of a map.
+//! Floats within a struct or enum are encoded as-is.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    hash::BuildHasher,
+};
+
+use oasis_types::RpcError;
+
+pub trait Canonicalize: Sized {
+    /// Returns the canonical representation of `self`, or an error if it has none.
+    fn canonicalize(mut self) -> Result<Self, RpcError> {
+        self.canonicalize_mut()?;
+        Ok(self)
+    }
+
+    /// Replaces `self` with its canonical representation, or returns an error if it has none.
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError>;
+}
+
+impl<T> Canonicalize for T {
+    default fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_canonicalize_float {
+    ($($float:ty),+) => {
+        $(
+            impl Canonicalize for $float {
+                fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+                    if self.is_nan() {
+                        return Err(RpcError::InvalidInput(None));
+                    } else if *self == 0.0 {
+                        *self = 0.0; // `-0.0 == 0.0`
+                    }
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_canonicalize_float!(f32, f64);
+
+impl<T> Canonicalize for Option<T> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        self.as_mut().map(T::canonicalize_mut).transpose()?;
+        Ok(())
+    }
+}
+
+impl<T, E> Canonicalize for Result<T, E> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        match self {
+            Ok(ok) => ok.canonicalize_mut(),
+            Err(err) => err.canonicalize_mut(),
+        }
+    }
+}
+
+impl<T> Canonicalize for Box<T> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        (**self).canonicalize_mut()
+    }
+}
+
+/// Canonicalizes each of the items yielded by `iter`.
+fn canonicalize_all<'a, T: 'a>(iter: impl Iterator<Item = &'a mut T>) -> Result<(), RpcError> {
+    for item in iter {
+        item.canonicalize_mut()?;
+    }
+    Ok(())
+}
+
+impl<T> Canonicalize for Vec<T> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        canonicalize_all(self.iter_mut())
+    }
+}
+
+impl<T> Canonicalize for VecDeque<T> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        canonicalize_all(self.iter_mut())
+    }
+}
+
+// Floats are neither `Ord` nor `Hash`, so only the values of a map can be floats.
+
+impl<K, V> Canonicalize for BTreeMap<K, V> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        canonicalize_all(self.values_mut())
+    }
+}
+
+impl<K, V, S: BuildHasher> Canonicalize for HashMap<K, V, S> {
+    fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+        canonicalize_all(self.values_mut())
+    }
+}
+
+macro_rules! impl_canonicalize_array {
+    ($($len:literal),+) => {
+        $(
+            impl<T> Canonicalize for [T; $len] {
+                fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+                    canonicalize_all(self.iter_mut())
+                }
+            }
+        )+
+    };
+}
+
+impl_canonicalize_array!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32
+);
+
+macro_rules! impl_canonicalize_tuple {
+    ($(($($ty:ident $idx:tt),+)),+) => {
+        $(
+            impl<$($ty),+> Canonicalize for ($($ty,)+) {
+                fn canonicalize_mut(&mut self) -> Result<(), RpcError> {
+                    $(self.$idx.canonicalize_mut()?;)+
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_canonicalize_tuple!(
+    (T0 0),
+    (T0 0, T1 1),
+    (T0 0, T1 1, T2 2),
+    (T0 0, T1 1, T2 2, T3 3),
+    (T0 0, T1 1, T2 2, T3 3, T4 4),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_borsh::BorshSerialize as _;
+
+    #[test]
+    fn test_reject_nan() {
+        assert!(matches!(
+            std::f64::NAN.canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+        assert!(matches!(
+            (-std::f32::NAN).canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+        assert!(matches!(
+            Some(std::f64::NAN).canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+        assert!(matches!(
+            Result::<u8, f32>::Err(std::f32::NAN).canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+    }
+
+    #[test]
+    fn test_reject_nested_nan() {
+        assert!(matches!(
+            vec![Some(1f64), Some(std::f64::NAN)].canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+        assert!(matches!(
+            ("name", [0f32, std::f32::NAN]).canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+        let mut map = HashMap::new();
+        map.insert("nan", vec![std::f64::NAN]);
+        assert!(matches!(
+            map.canonicalize(),
+            Err(RpcError::InvalidInput(None))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_zero() {
+        let zero = 0f64.try_to_vec().unwrap();
+        assert_ne!((-0f64).try_to_vec().unwrap(), zero);
+        assert_eq!((-0f64).canonicalize().unwrap().try_to_vec().unwrap(), zero);
+        assert_eq!(0f64.canonicalize().unwrap().try_to_vec().unwrap(), zero);
+        assert!((-0f32).canonicalize().unwrap().is_sign_positive());
+        assert!(Some(-0f64)
+            .canonicalize()
+            .unwrap()
+            .unwrap()
+            .is_sign_positive());
+    }
+
+    #[test]
+    fn test_normalize_nested_zero() {
+        let value = vec![(1u8, Box::new(-0f64)), (2u8, Box::new(-1f64))];
+        let canonical = (vec![(1u8, Box::new(0f64)), (2u8, Box::new(-1f64))])
+            .try_to_vec()
+            .unwrap();
+        assert_ne!(value.try_to_vec().unwrap(), canonical);
+        assert_eq!(
+            value.canonicalize().unwrap().try_to_vec().unwrap(),
+            canonical
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("zero", VecDeque::from(vec![-0f32]));
+        let map = map.canonicalize().unwrap();
+        assert!(map["zero"][0].is_sign_positive());
+    }
+
+    #[test]
+    fn test_other_values_unchanged() {
+        assert_eq!(
+            (-1.5f64).canonicalize().unwrap().to_bits(),
+            (-1.5f64).to_bits()
+        );
+        assert_eq!(
+            std::f32::INFINITY.canonicalize().unwrap().to_bits(),
+            std::f32::INFINITY.to_bits()
+        );
+        assert_eq!("nan".canonicalize().unwrap(), "nan");
+        assert_eq!(
+            Some((-2f64, "-0.0")).canonicalize().unwrap(),
+            Some((-2f64, "-0.0"))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! The global allocator used when the `wee-alloc` feature is enabled.
+//!
+//! `wee_alloc` trades allocation speed for a much smaller code size than the
+//! target's default allocator, which makes it a good fit for size-constrained services.
+//! Without the feature, services use the default allocator of the target.
+
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_alloc_and_free() {
+        let mut bufs: Vec<Vec<u8>> = (0..64u8).map(|i| vec![i; 1 << (i % 12)]).collect();
+        bufs.retain(|buf| buf[0] % 2 == 0); // frees half of the buffers
+        bufs.extend((0..32u8).map(|i| vec![i; 3000]));
+        bufs.iter_mut().for_each(|buf| buf.shrink_to_fit());
+        assert_eq!(bufs.len(), 64);
+        assert!(bufs[..32]
+            .iter()
+            .all(|buf| buf.iter().all(|&b| b == buf[0] && b % 2 == 0)));
+        assert!(bufs[32..].iter().all(|buf| buf.len() == 3000));
+    }
+}
@@ -36,9 +36,11 @@ extern "C" {
 
     pub fn oasis_address(addr: *mut Address) -> u32;
     pub fn oasis_sender(addr: *mut Address) -> u32;
+    pub fn oasis_origin(addr: *mut Address) -> u32;
     pub fn oasis_payer(addr: *mut Address) -> u32;
     #[allow(improper_ctypes)] // u128 is just 2 u64s
     pub fn oasis_value(value: *mut u128) -> u32;
+    pub fn oasis_timestamp(timestamp: *mut u64) -> u32;
 
     pub fn oasis_read(key: *const u8, key_len: u32, value: *mut u8) -> u32;
     pub fn oasis_read_len(key: *const u8, key_len: u32, value_len: *mut u32) -> u32;
@@ -57,7 +59,7 @@ fn unpack_rpc_error(status: u32) -> RpcError {
     match ExtStatusCode::from_u32(status) {
         Some(ExtStatusCode::Success) => unreachable!(),
         Some(ExtStatusCode::InsufficientFunds) => RpcError::InsufficientFunds,
-        Some(ExtStatusCode::InvalidInput) => RpcError::InvalidInput,
+        Some(ExtStatusCode::InvalidInput) => RpcError::invalid_input(&fetch_err()),
         Some(ExtStatusCode::NoAccount) => RpcError::InvalidCallee,
         Some(_) | None => RpcError::Execution(fetch_err()),
     }
@@ -86,6 +88,12 @@ pub fn sender() -> Address {
     addr
 }
 
+pub fn origin() -> Address {
+    let mut addr = Address::default();
+    ext!(oasis_origin(&mut addr as *mut _)).unwrap();
+    addr
+}
+
 pub fn payer() -> Address {
     let mut addr = Address::default();
     ext!(oasis_payer(&mut addr as *mut _)).unwrap();
@@ -109,6 +117,12 @@ pub fn value() -> Balance {
     Balance(value)
 }
 
+pub fn timestamp() -> u64 {
+    let mut timestamp = 0;
+    ext!(oasis_timestamp(&mut timestamp as *mut _)).unwrap();
+    timestamp
+}
+
 pub fn balance(addr: &Address) -> Option<Balance> {
     let mut balance = 0;
     ext!(oasis_balance(addr as *const _, &mut balance as *mut _))
@@ -118,15 +132,15 @@ pub fn balance(addr: &Address) -> Option<Balance> {
 
 pub fn code(addr: &Address) -> Option<Vec<u8>> {
     let mut code_len = 0u32;
-    let mut code = Vec::with_capacity(
-        match ext!(oasis_code_len(
-            addr as *const Address,
-            &mut code_len as *mut _
-        )) {
-            Ok(_) => code_len as usize,
-            Err(_) => return None,
-        },
-    );
+    ext!(oasis_code_len(
+        addr as *const Address,
+        &mut code_len as *mut _
+    ))
+    .ok()?;
+
+    let mut code = Vec::with_capacity(code_len as usize);
+    unsafe { code.set_len(code_len as usize) };
+
     ext!(oasis_code(addr as *const Address, code.as_mut_ptr()))
         .ok()
         .map(|_| code)
@@ -137,7 +151,7 @@ pub fn create(value: Balance, code: &[u8]) -> Result<Address, RpcError> {
         &value.0 as *const u128,
         code.as_ptr(),
         if code.len() > u32::max_value() as usize {
-            return Err(RpcError::InvalidInput);
+            return Err(RpcError::InvalidInput(None));
         } else {
             code.len() as u32
         },
@@ -169,7 +183,7 @@ pub fn transact(callee: &Address, value: Balance, input: &[u8]) -> Result<Vec<u8
         &value.0 as *const u128,
         input.as_ptr(),
         if input.len() > u32::max_value() as usize {
-            return Err(RpcError::InvalidInput);
+            return Err(RpcError::InvalidInput(None));
         } else {
             input.len() as u32
         },
@@ -208,6 +222,15 @@ pub fn err(err: &[u8]) -> ! {
     std::process::abort();
 }
 
+/// Exits with the status of `RpcError::InvalidInput`, which the host reports to the caller
+/// as `ExtStatusCode::InvalidInput`, so that the caller can tell it apart from an
+/// application error.
+pub fn invalid_input(method: Option<u32>) -> ! {
+    let err = super::invalid_input_output(method);
+    ext!(oasis_err(err.as_ptr(), err.len() as u32)).unwrap();
+    std::process::exit(RpcError::InvalidInput(method).code().into());
+}
+
 pub fn fetch_err() -> Vec<u8> {
     let mut err_len = 0u32;
     ext!(oasis_err_len(&mut err_len as *mut _)).unwrap();
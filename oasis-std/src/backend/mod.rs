@@ -9,6 +9,21 @@ cfg_if::cfg_if! {
 }
 
 pub use imp::{
-    aad, address, balance, code, create, emit, err, input, payer, read, ret, sender, transact,
-    value, write,
+    aad, address, balance, code, create, emit, err, input, invalid_input, origin, payer, read, ret,
+    sender, timestamp, transact, value, write,
 };
+
+/// Returns the error output of an `RpcError::InvalidInput`, which is the little-endian
+/// selector of the requested method, if any. @see `RpcError::invalid_input`.
+fn invalid_input_output(method: Option<u32>) -> Vec<u8> {
+    method
+        .map(|selector| selector.to_le_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Returns the keccak256 hash of the code of the executing service, which identifies
+/// the version of the service, e.g., for coordinating upgrades.
+/// Returns `None` if the code of the executing service cannot be read.
+pub fn self_code_hash() -> Option<[u8; 32]> {
+    code(&address()).map(|code| crate::crypto::keccak256(&code))
+}
@@ -59,6 +59,10 @@ pub fn sender() -> Address {
     env_addr("SENDER")
 }
 
+pub fn origin() -> Address {
+    env_addr("ORIGIN")
+}
+
 pub fn payer() -> Address {
     env_addr("PAYER")
 }
@@ -71,6 +75,15 @@ pub fn value() -> Balance {
     Balance(u128::from_str(&std::env::var("VALUE").unwrap()).unwrap())
 }
 
+/// Returns the block timestamp provided by the runtime in `TIMESTAMP`, or 0 if the runtime
+/// does not provide one. Wall-clock time is never read because it would differ between nodes.
+pub fn timestamp() -> u64 {
+    std::env::var("TIMESTAMP")
+        .ok()
+        .and_then(|timestamp| u64::from_str(&timestamp).ok())
+        .unwrap_or_default()
+}
+
 pub fn balance(addr: &Address) -> Option<Balance> {
     Some(match fs::read(home(&*addr, "balance")) {
         Ok(balance) => {
@@ -84,7 +97,7 @@ pub fn balance(addr: &Address) -> Option<Balance> {
 }
 
 pub fn code(addr: &Address) -> Option<Vec<u8>> {
-    Some(match fs::read(home(&*addr, "code")) {
+    Some(match fs::read(home(&*addr, "bytecode")) {
         Ok(code) => code,
         Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
         Err(err) => panic!(err),
@@ -117,7 +130,7 @@ pub fn create(value: Balance, code: &[u8]) -> Result<Address, RpcError> {
                 Ok(addr)
             }
         }
-        __WASI_EFAULT | __WASI_EINVAL => Err(RpcError::InvalidInput),
+        __WASI_EFAULT | __WASI_EINVAL => Err(RpcError::invalid_input(&out)),
         __WASI_ENOENT => Err(RpcError::InvalidCallee),
         __WASI_EDQUOT => Err(RpcError::InsufficientFunds),
         __WASI_ECONNABORTED => Err(RpcError::Execution(out)),
@@ -144,7 +157,7 @@ pub fn transact(callee: &Address, value: Balance, input: &[u8]) -> Result<Vec<u8
     use wasi::wasi_unstable::raw::*;
     match errno {
         __WASI_ESUCCESS => Ok(out),
-        __WASI_EFAULT | __WASI_EINVAL => Err(RpcError::InvalidInput),
+        __WASI_EFAULT | __WASI_EINVAL => Err(RpcError::invalid_input(&out)),
         __WASI_ENOENT => Err(RpcError::InvalidCallee),
         __WASI_EDQUOT => Err(RpcError::InsufficientFunds),
         __WASI_ECONNABORTED => Err(RpcError::Execution(out)),
@@ -168,6 +181,15 @@ pub fn err(err: &[u8]) -> ! {
     std::process::exit(1);
 }
 
+/// Exits with the status of `RpcError::InvalidInput`, which the runtime reports to the
+/// caller as `EINVAL`, so that the caller can tell it apart from an application error.
+pub fn invalid_input(method: Option<u32>) -> ! {
+    io::stderr()
+        .write_all(&super::invalid_input_output(method))
+        .unwrap();
+    std::process::exit(RpcError::InvalidInput(method).code().into());
+}
+
 pub fn read(key: &[u8]) -> Vec<u8> {
     fs::read(std::str::from_utf8(key).unwrap()).unwrap_or_default()
 }
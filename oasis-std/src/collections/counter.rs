@@ -0,0 +1,211 @@
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+};
+
+use oasis_borsh::{BorshDeserialize, BorshSerialize};
+
+use super::Map;
+
+/// `Counter` is a [`Map`](struct.Map.html) from keys to `u64` counts that checks for
+/// overflow and underflow. Keys that have not been counted have a count of zero.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::collections::{Counter, CounterError};
+/// let mut votes = Counter::new();
+/// votes.increment("alice").unwrap();
+/// votes.increment_by("bob", 2).unwrap();
+/// assert_eq!(votes.get("bob"), 2);
+/// assert_eq!(votes.decrement("carol"), Err(CounterError::Underflow));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Counter<K> {
+    counts: Map<K, u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CounterError {
+    #[error("count would exceed `u64::MAX`")]
+    Overflow,
+
+    #[error("count would be less than zero")]
+    Underflow,
+}
+
+impl<K: Eq> Counter<K> {
+    pub fn new() -> Self {
+        Self { counts: Map::new() }
+    }
+
+    /// Returns the count of `key`, which is zero if it has not been counted.
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> u64
+    where
+        K: Borrow<Q>,
+        Q: Eq,
+    {
+        self.counts.get(key).copied().unwrap_or_default()
+    }
+
+    /// Adds one to the count of `key` and returns the new count.
+    pub fn increment(&mut self, key: K) -> Result<u64, CounterError> {
+        self.increment_by(key, 1)
+    }
+
+    /// Adds `amount` to the count of `key` and returns the new count.
+    /// The count is unchanged if it would overflow.
+    pub fn increment_by(&mut self, key: K, amount: u64) -> Result<u64, CounterError> {
+        if amount == 0 {
+            return Ok(self.get(&key));
+        }
+        let count = self.counts.entry(key).or_default();
+        *count = count.checked_add(amount).ok_or(CounterError::Overflow)?;
+        Ok(*count)
+    }
+
+    /// Subtracts one from the count of `key` and returns the new count.
+    pub fn decrement<Q: ?Sized>(&mut self, key: &Q) -> Result<u64, CounterError>
+    where
+        K: Borrow<Q>,
+        Q: Eq,
+    {
+        self.decrement_by(key, 1)
+    }
+
+    /// Subtracts `amount` from the count of `key` and returns the new count.
+    /// The count is unchanged if it would underflow. Keys whose count reaches zero are removed.
+    pub fn decrement_by<Q: ?Sized>(&mut self, key: &Q, amount: u64) -> Result<u64, CounterError>
+    where
+        K: Borrow<Q>,
+        Q: Eq,
+    {
+        let count = self.get(key);
+        let new_count = count.checked_sub(amount).ok_or(CounterError::Underflow)?;
+        if new_count == 0 {
+            self.counts.remove(key);
+        } else if let Some(count) = self.counts.get_mut(key) {
+            *count = new_count;
+        }
+        Ok(new_count)
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns the number of keys with a nonzero count.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the keys with a nonzero count and their counts, in insertion order.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&K, u64)> + DoubleEndedIterator + ExactSizeIterator {
+        self.counts.iter().map(|(k, v)| (k, *v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> + DoubleEndedIterator + ExactSizeIterator {
+        self.counts.keys()
+    }
+}
+
+impl<K: Debug> fmt::Debug for Counter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.counts.fmt(f)
+    }
+}
+
+impl<K: Eq> core::iter::FromIterator<K> for Counter<K> {
+    /// Counts the occurrences of each item. Panics if a count overflows.
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        let mut counter = Self::new();
+        for key in iter {
+            counter.increment(key).unwrap();
+        }
+        counter
+    }
+}
+
+impl<K: BorshSerialize + PartialOrd> BorshSerialize for Counter<K> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.counts.serialize(writer)
+    }
+}
+
+impl<K: BorshDeserialize + Eq> BorshDeserialize for Counter<K> {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            counts: Map::deserialize(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment() {
+        let mut counter = Counter::new();
+        assert_eq!(counter.get("a"), 0);
+        assert_eq!(counter.increment("a"), Ok(1));
+        assert_eq!(counter.increment("a"), Ok(2));
+        assert_eq!(counter.increment_by("b", 5), Ok(5));
+        assert_eq!(counter.get("a"), 2);
+        assert_eq!(counter.get("b"), 5);
+        assert_eq!(
+            counter.iter().collect::<Vec<_>>(),
+            vec![(&"a", 2), (&"b", 5)]
+        );
+    }
+
+    #[test]
+    fn test_increment_overflow() {
+        let mut counter = Counter::new();
+        assert_eq!(
+            counter.increment_by(1, u64::max_value() - 1),
+            Ok(u64::max_value() - 1)
+        );
+        assert_eq!(counter.increment(1), Ok(u64::max_value()));
+        assert_eq!(counter.increment(1), Err(CounterError::Overflow));
+        assert_eq!(
+            counter.increment_by(1, u64::max_value()),
+            Err(CounterError::Overflow)
+        );
+        assert_eq!(counter.get(&1), u64::max_value());
+    }
+
+    #[test]
+    fn test_decrement() {
+        let mut counter: Counter<_> = vec!["a", "a", "b"].into_iter().collect();
+        assert_eq!(counter.decrement("a"), Ok(1));
+        assert_eq!(counter.decrement_by("a", 1), Ok(0));
+        assert_eq!(counter.get("a"), 0);
+        assert_eq!(counter.len(), 1);
+        assert_eq!(counter.keys().collect::<Vec<_>>(), vec![&"b"]);
+    }
+
+    #[test]
+    fn test_decrement_underflow() {
+        let mut counter = Counter::new();
+        assert_eq!(counter.decrement("a"), Err(CounterError::Underflow));
+        counter.increment_by("a", 2).unwrap();
+        assert_eq!(counter.decrement_by("a", 3), Err(CounterError::Underflow));
+        assert_eq!(counter.get("a"), 2);
+        assert!(counter.decrement_by("b", 0).is_ok());
+        assert_eq!(counter.len(), 1);
+    }
+
+    #[test]
+    fn test_serde() {
+        let counter: Counter<_> = vec![1u8, 3, 3].into_iter().collect();
+        let bytes = counter.try_to_vec().unwrap();
+        assert_eq!(Counter::try_from_slice(&bytes).unwrap(), counter);
+    }
+}
@@ -106,6 +106,35 @@ impl<K: Eq, V> Map<K, V> {
             .map(|(_, v)| v)
     }
 
+    /// Returns mutable references to the values of `N` distinct keys at once.
+    /// Returns `None` if any key is missing or if a key is given more than once.
+    pub fn get_disjoint_mut<Q: ?Sized, const N: usize>(
+        &mut self,
+        keys: [&Q; N],
+    ) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+        Q: Eq,
+    {
+        let mut positions = Vec::with_capacity(N);
+        for key in keys.iter() {
+            let pos = self.backing.iter().position(|(k, _)| key.eq(&k.borrow()))?;
+            if positions.contains(&pos) {
+                return None;
+            }
+            positions.push(pos);
+        }
+        let backing = self.backing.as_mut_ptr();
+        let mut values = core::mem::MaybeUninit::<[&mut V; N]>::uninit();
+        let values_ptr = values.as_mut_ptr() as *mut &mut V;
+        for (i, pos) in positions.into_iter().enumerate() {
+            // The positions are in bounds and distinct, so the references don't alias.
+            unsafe { values_ptr.add(i).write(&mut (*backing.add(pos)).1) };
+        }
+        // All `N` references were written above.
+        Some(unsafe { values.assume_init() })
+    }
+
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
         for (k, ref mut v) in self.backing.iter_mut() {
             if *k == key {
@@ -116,6 +145,14 @@ impl<K: Eq, V> Map<K, V> {
         None
     }
 
+    pub fn into_keys(self) -> impl Iterator<Item = K> + DoubleEndedIterator + ExactSizeIterator {
+        self.backing.into_iter().map(|(k, _)| k)
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = V> + DoubleEndedIterator + ExactSizeIterator {
+        self.backing.into_iter().map(|(_, v)| v)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.backing.is_empty()
     }
@@ -262,6 +299,12 @@ impl<Q: Eq + ?Sized, K: Eq + Borrow<Q>, V> core::ops::Index<&Q> for Map<K, V> {
     }
 }
 
+impl<Q: Eq + ?Sized, K: Eq + Borrow<Q>, V> core::ops::IndexMut<&Q> for Map<K, V> {
+    fn index_mut(&mut self, key: &Q) -> &mut V {
+        self.get_mut(key).expect("no entry found for key")
+    }
+}
+
 pub enum Entry<'a, K: 'a, V: 'a> {
     Occupied(OccupiedEntry<'a, K, V>),
     Vacant(VacantEntry<'a, K, V>),
@@ -295,6 +338,16 @@ impl<'a, K, V> Entry<'a, K, V> {
             Entry::Vacant(ve) => ve.insert(f()),
         }
     }
+
+    pub fn or_insert_with_key(self, f: impl FnOnce(&K) -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(oe) => oe.into_mut(),
+            Entry::Vacant(ve) => {
+                let value = f(ve.key());
+                ve.insert(value)
+            }
+        }
+    }
 }
 
 impl<'a, K: 'a, V: Default> Entry<'a, K, V> {
@@ -826,6 +879,40 @@ mod test_map {
         assert!(values.contains(&'c'));
     }
 
+    #[test]
+    fn test_into_keys() {
+        let vec = vec![(1.to_string(), 'a'), (2.to_string(), 'b')];
+        let map: Map<_, _> = vec.into_iter().collect();
+        let keys: Vec<String> = map.into_keys().collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&"1".to_string()));
+        assert!(keys.contains(&"2".to_string()));
+    }
+
+    #[test]
+    fn test_into_values() {
+        let vec = vec![(1, "a".to_string()), (2, "b".to_string())];
+        let map: Map<_, _> = vec.into_iter().collect();
+        let values: Vec<String> = map.into_values().collect();
+        assert_eq!(values.len(), 2);
+        assert!(values.contains(&"a".to_string()));
+        assert!(values.contains(&"b".to_string()));
+    }
+
+    #[test]
+    fn test_move_iter() {
+        let mut map = Map::new();
+        map.insert("a".to_string(), vec![1]);
+        map.insert("b".to_string(), vec![2]);
+
+        let mut entries: Vec<(String, Vec<u8>)> = map.into_iter().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])]
+        );
+    }
+
     #[test]
     fn test_values_mut() {
         let vec = vec![(1, 1), (2, 2), (3, 3)];
@@ -1008,6 +1095,50 @@ mod test_map {
         map[&4];
     }
 
+    #[test]
+    fn test_get_disjoint_mut() {
+        let mut balances = Map::new();
+        balances.insert("alice".to_string(), 100u64);
+        balances.insert("bob".to_string(), 5);
+        balances.insert("carol".to_string(), 0);
+
+        if let Some([from, to]) = balances.get_disjoint_mut(["alice", "bob"]) {
+            *from -= 30;
+            *to += 30;
+        } else {
+            panic!("expected both accounts to exist");
+        }
+        assert_eq!(balances["alice"], 70);
+        assert_eq!(balances["bob"], 35);
+        assert_eq!(balances["carol"], 0);
+
+        assert!(balances.get_disjoint_mut(["alice", "alice"]).is_none());
+        assert!(balances.get_disjoint_mut(["alice", "dave"]).is_none());
+        assert!(balances.get_disjoint_mut(["carol"]).is_some());
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut map = Map::new();
+
+        map.insert("a".to_string(), 1);
+        map.insert("b".to_string(), 2);
+
+        map["b"] += 10;
+        assert_eq!(map["a"], 1);
+        assert_eq!(map["b"], 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry found for key")]
+    fn test_index_mut_nonexistent() {
+        let mut map = Map::new();
+
+        map.insert(1, 2);
+
+        map[&4] = 5;
+    }
+
     #[test]
     fn test_entry() {
         let xs = [(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)];
@@ -1058,6 +1189,66 @@ mod test_map {
         assert_eq!(map.len(), 6);
     }
 
+    #[test]
+    fn test_entry_or_insert() {
+        let mut map: Map<&str, u32> = Map::new();
+
+        map.entry("poneyland").or_insert(3);
+        assert_eq!(map["poneyland"], 3);
+
+        *map.entry("poneyland").or_insert(10) *= 2;
+        assert_eq!(map["poneyland"], 6);
+    }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut map: Map<&str, String> = Map::new();
+        let s = "hoho".to_string();
+
+        map.entry("poneyland").or_insert_with(|| s);
+        assert_eq!(map["poneyland"], "hoho".to_string());
+    }
+
+    #[test]
+    fn test_entry_or_insert_with_key() {
+        let mut map: Map<&str, usize> = Map::new();
+
+        map.entry("poneyland")
+            .or_insert_with_key(|key| key.chars().count());
+        assert_eq!(map["poneyland"], 9);
+
+        *map.entry("poneyland")
+            .or_insert_with_key(|_| unreachable!()) += 1;
+        assert_eq!(map["poneyland"], 10);
+    }
+
+    #[test]
+    fn test_entry_key() {
+        let mut map: Map<&str, u32> = Map::new();
+        assert_eq!(map.entry("poneyland").key(), &"poneyland");
+
+        map.insert("poneyland", 12);
+        assert_eq!(map.entry("poneyland").key(), &"poneyland");
+    }
+
+    #[test]
+    fn test_entry_and_modify() {
+        let mut map: Map<&str, u32> = Map::new();
+
+        map.entry("poneyland").and_modify(|e| *e += 1).or_insert(42);
+        assert_eq!(map["poneyland"], 42);
+
+        map.entry("poneyland").and_modify(|e| *e += 1).or_insert(42);
+        assert_eq!(map["poneyland"], 43);
+    }
+
+    #[test]
+    fn test_entry_or_default() {
+        let mut map: Map<&str, Option<u32>> = Map::new();
+        map.entry("poneyland").or_default();
+        assert_eq!(map["poneyland"], None);
+    }
+
     #[test]
     fn test_entry_take_doesnt_corrupt() {
         #![allow(deprecated)] //rand
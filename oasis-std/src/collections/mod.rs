@@ -1,7 +1,11 @@
-//! Map and Set APIs backed by Vecs.
+//! Map, Set, and Counter APIs backed by Vecs, and collections backed by their own storage slots.
 
+pub mod counter;
 pub mod map;
 pub mod set;
+pub mod storage;
 
+pub use counter::{Counter, CounterError};
 pub use map::Map;
 pub use set::Set;
+pub use storage::{Slotted, StorageCell, StorageMap, StorageVec};
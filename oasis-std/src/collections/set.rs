@@ -76,31 +76,35 @@ impl<T: Eq> Set<T> {
         self.backing.iter().find(|v| value.eq((*v).borrow()))
     }
 
+    /// Inserts `value` if it is not already present and returns a reference to the
+    /// value in the set.
     pub fn get_or_insert(&mut self, value: T) -> &T {
-        let self_ptr = self as *mut Self;
-        for v in self.backing.iter() {
-            if *v == value {
-                return v;
+        let pos = match self.backing.iter().position(|v| *v == value) {
+            Some(pos) => pos,
+            None => {
+                self.backing.push(value);
+                self.backing.len() - 1
             }
-        }
-        // rustc just isn't having it
-        unsafe { (*self_ptr).backing.push(value) };
-        self.backing.last().unwrap()
+        };
+        &self.backing[pos]
     }
 
-    pub fn get_or_insert_with<Q: ?Sized>(&mut self, value: &Q, f: impl FnOnce(&Q) -> T) -> &T
+    /// Inserts the value computed from `value` by `f` if no value equal to `value` is present,
+    /// and returns a reference to the value in the set.
+    pub fn get_or_insert_with<Q: ?Sized, F>(&mut self, value: &Q, f: F) -> &T
     where
         T: Borrow<Q>,
         Q: Eq,
+        F: FnOnce(&Q) -> T,
     {
-        let self_ptr = self as *mut Self;
-        for v in self.backing.iter() {
-            if (*v).borrow() == value {
-                return v;
+        let pos = match self.backing.iter().position(|v| value.eq(v.borrow())) {
+            Some(pos) => pos,
+            None => {
+                self.backing.push(f(value));
+                self.backing.len() - 1
             }
-        }
-        unsafe { (*self_ptr).backing.push(f(value)) };
-        self.backing.last().unwrap()
+        };
+        &self.backing[pos]
     }
 
     pub fn insert(&mut self, value: T) -> bool {
@@ -584,6 +588,17 @@ mod test_set {
         assert!(v == ['a', 'b'] || v == ['b', 'a']);
     }
 
+    #[test]
+    fn test_move_iter_owned() {
+        let mut hs = Set::new();
+        hs.insert("a".to_string());
+        hs.insert("b".to_string());
+
+        let mut v: Vec<String> = hs.into_iter().collect();
+        v.sort();
+        assert_eq!(v, ["a".to_string(), "b".to_string()]);
+    }
+
     #[test]
     fn test_eq() {
         // These constants once happened to expose a bug in insert().
@@ -683,6 +698,34 @@ mod test_set {
         assert_eq!(it.next(), None);
     }
 
+    #[test]
+    fn test_get_or_insert() {
+        let mut s = Set::new();
+        assert_eq!(*s.get_or_insert(1), 1);
+        assert_eq!(*s.get_or_insert(2), 2);
+        assert_eq!(*s.get_or_insert(1), 1);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut s: Set<String> = Set::new();
+        let mut calls = 0;
+        let a_ptr = s
+            .get_or_insert_with("a", |v| {
+                calls += 1;
+                v.to_string()
+            })
+            .as_ptr();
+        let a_again = s.get_or_insert_with("a", |_| unreachable!());
+        assert_eq!(a_again, "a");
+        assert_eq!(a_again.as_ptr(), a_ptr);
+        assert_eq!(s.get_or_insert_with("b", str::to_string), "b");
+        assert_eq!(calls, 1);
+        assert_eq!(s.len(), 2);
+    }
+
     #[test]
     fn test_extend_ref() {
         let mut a = Set::new();
@@ -722,6 +765,29 @@ mod test_set {
         assert!(set.contains(&6));
     }
 
+    #[test]
+    fn test_try_reserve() {
+        use std::collections::TryReserveError;
+
+        let mut set: Set<u64> = Set::new();
+        set.insert(1);
+
+        match set.try_reserve(usize::max_value()) {
+            Err(TryReserveError::CapacityOverflow) => (),
+            res => panic!("usize::MAX should trigger an overflow, not {:?}", res),
+        }
+        match set.try_reserve(usize::max_value() / 32) {
+            Err(TryReserveError::AllocError { .. }) => (),
+            res => panic!("usize::MAX / 32 should trigger an OOM, not {:?}", res),
+        }
+
+        // A failed reservation leaves the set unchanged.
+        assert_eq!(set.len(), 1);
+        assert!(set.try_reserve(16).is_ok());
+        assert!(set.capacity() >= 17);
+        assert!(set.insert(2));
+    }
+
     #[test]
     fn test_borsh_roundtrip() {
         let mut s = Set::new();
@@ -0,0 +1,342 @@
+//! Collections that keep their contents in their own storage slots.
+//!
+//! A service's fields are normally read from and written to storage in their entirety
+//! whenever the service is called. A `StorageCell`, `StorageVec`, or `StorageMap` field
+//! marked `#[oasis(slot)]`, instead, is assigned the slot numbered by its index in the
+//! service struct and reads or writes its contents only when accessed.
+//!
+//! ```
+//! use oasis_std::{collections::StorageVec, Service};
+//!
+//! #[derive(Service)]
+//! pub struct Blog {
+//!     title: String,
+//!     #[oasis(slot)]
+//!     posts: StorageVec<String>, // the field at index 1 is stored in slot 1
+//! }
+//!
+//! let mut blog = Blog {
+//!     title: "Gaul".to_string(),
+//!     posts: StorageVec::new(1),
+//! };
+//! blog.posts.push(&"by Toutatis".to_string());
+//! Blog::sunder(blog);
+//! assert_eq!(Blog::coalesce().posts.len(), 1);
+//! ```
+//!
+//! The contents of slot `n` are stored under the key `\0slot<n>`, which cannot be the key
+//! of a service field. The elements of a `StorageVec` are stored under
+//! `\0slot<n>.<index>`, and its length under `\0slot<n>`. The entries of a `StorageMap`
+//! are stored under `\0slot<n>.<key>`, where `<key>` is the hex-encoded key, and whether
+//! it keeps tombstones under `\0slot<n>`.
+
+use std::marker::PhantomData;
+
+use oasis_borsh::{BorshDeserialize, BorshSerialize};
+
+fn slot_key(slot: u32) -> Vec<u8> {
+    format!("\0slot{}", slot).into_bytes()
+}
+
+/// A collection that keeps its contents in a storage slot.
+/// The `Service` derive assigns each field marked `#[oasis(slot)]` the slot numbered by
+/// the field's index, which the field's handle must have been created with.
+pub trait Slotted {
+    /// Returns a handle to the contents of `slot`.
+    fn at_slot(slot: u32) -> Self;
+
+    /// Returns the slot that holds the contents of this collection.
+    fn slot(&self) -> u32;
+}
+
+/// A single value stored in its own storage slot.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::collections::StorageCell;
+/// let mut motd = StorageCell::new(0);
+/// assert_eq!(motd.get(), None);
+/// motd.set(&"hello, world".to_string());
+/// assert_eq!(motd.get().as_deref(), Some("hello, world"));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct StorageCell<T> {
+    slot: u32,
+    _value: PhantomData<T>,
+}
+
+impl<T: BorshSerialize + BorshDeserialize> StorageCell<T> {
+    /// Returns a handle to the value stored in `slot`.
+    pub fn new(slot: u32) -> Self {
+        Self {
+            slot,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns the stored value, or `None` if no value has been set.
+    pub fn get(&self) -> Option<T> {
+        let bytes = crate::backend::read(&slot_key(self.slot));
+        if bytes.is_empty() {
+            return None;
+        }
+        Some(T::try_from_slice(&bytes).unwrap())
+    }
+
+    pub fn set(&mut self, value: &T) {
+        crate::backend::write(&slot_key(self.slot), &value.try_to_vec().unwrap());
+    }
+}
+
+/// A list whose elements are each stored in their own storage slot so that
+/// pushing or accessing an element does not read or write any of the others.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::collections::StorageVec;
+/// let mut posts = StorageVec::new(0);
+/// posts.push(&"first!".to_string());
+/// posts.push(&"second".to_string());
+/// assert_eq!(posts.len(), 2);
+/// assert_eq!(posts.get(1).as_deref(), Some("second"));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct StorageVec<T> {
+    slot: u32,
+    _elem: PhantomData<T>,
+}
+
+impl<T: BorshSerialize + BorshDeserialize> StorageVec<T> {
+    /// Returns a handle to the list stored in `slot`.
+    pub fn new(slot: u32) -> Self {
+        Self {
+            slot,
+            _elem: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        let len_bytes = crate::backend::read(&slot_key(self.slot));
+        if len_bytes.is_empty() {
+            return 0;
+        }
+        u64::try_from_slice(&len_bytes).unwrap()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: u64) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(T::try_from_slice(&crate::backend::read(&self.elem_key(index))).unwrap())
+    }
+
+    /// Replaces the element at `index`. Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: u64, value: &T) {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index
+        );
+        crate::backend::write(&self.elem_key(index), &value.try_to_vec().unwrap());
+    }
+
+    /// Appends an element to the end of the list.
+    pub fn push(&mut self, value: &T) {
+        let len = self.len();
+        crate::backend::write(&self.elem_key(len), &value.try_to_vec().unwrap());
+        crate::backend::write(&slot_key(self.slot), &(len + 1).try_to_vec().unwrap());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len()).map(move |i| self.get(i).unwrap())
+    }
+
+    fn elem_key(&self, index: u64) -> Vec<u8> {
+        format!("\0slot{}.{}", self.slot, index).into_bytes()
+    }
+}
+
+/// A map whose entries are each stored in their own storage slot so that inserting or
+/// getting an entry does not read or write any of the others.
+///
+/// A map created using `StorageMap::with_tombstones` leaves a tombstone in place of each
+/// removed entry so that `was_removed` can tell a removed key from one that was never set,
+/// e.g., to keep an audit trail of deletions. Tombstones remain in storage until the key
+/// is set again. Whether a map keeps tombstones is stored in its slot, so every handle to
+/// the map keeps them once one was created using `with_tombstones`.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::collections::StorageMap;
+/// let mut balances = StorageMap::with_tombstones(0);
+/// balances.insert(&"alice".to_string(), &42u64);
+/// assert_eq!(balances.get(&"alice".to_string()), Some(42));
+/// assert_eq!(balances.remove(&"alice".to_string()), Some(42));
+/// assert_eq!(balances.get(&"alice".to_string()), None);
+/// assert!(balances.was_removed(&"alice".to_string()));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct StorageMap<K, V> {
+    slot: u32,
+    _entry: PhantomData<(K, V)>,
+}
+
+/// The first byte of a stored entry. An entry that was never set is empty.
+const ENTRY_PRESENT: u8 = 1;
+const ENTRY_REMOVED: u8 = 0;
+
+impl<K: BorshSerialize, V: BorshSerialize + BorshDeserialize> StorageMap<K, V> {
+    /// Returns a handle to the map stored in `slot`.
+    pub fn new(slot: u32) -> Self {
+        Self {
+            slot,
+            _entry: PhantomData,
+        }
+    }
+
+    /// Returns a handle to the map stored in `slot` that records removed keys.
+    pub fn with_tombstones(slot: u32) -> Self {
+        crate::backend::write(&slot_key(slot), &[true as u8]);
+        Self::new(slot)
+    }
+
+    /// Returns the value of `key`, or `None` if it is not in the map.
+    pub fn get(&self, key: &K) -> Option<V> {
+        match crate::backend::read(&self.entry_key(key)).split_first() {
+            Some((&ENTRY_PRESENT, value_bytes)) => Some(V::try_from_slice(value_bytes).unwrap()),
+            _ => None,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        crate::backend::read(&self.entry_key(key)).first() == Some(&ENTRY_PRESENT)
+    }
+
+    pub fn insert(&mut self, key: &K, value: &V) {
+        let mut entry = vec![ENTRY_PRESENT];
+        value.serialize(&mut entry).unwrap();
+        crate::backend::write(&self.entry_key(key), &entry);
+    }
+
+    /// Removes `key` from the map and returns its value, if it was in the map.
+    /// The key is marked as removed if this map has tombstones.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.get(key);
+        if value.is_some() {
+            let tombstones = crate::backend::read(&slot_key(self.slot)) == [true as u8];
+            let entry: &[u8] = if tombstones { &[ENTRY_REMOVED] } else { &[] };
+            crate::backend::write(&self.entry_key(key), entry);
+        }
+        value
+    }
+
+    /// Returns whether `key` was removed and has not been set again.
+    /// This is always `false` for a map without tombstones.
+    pub fn was_removed(&self, key: &K) -> bool {
+        crate::backend::read(&self.entry_key(key)).as_slice() == [ENTRY_REMOVED]
+    }
+
+    fn entry_key(&self, key: &K) -> Vec<u8> {
+        let mut entry_key = format!("\0slot{}.", self.slot);
+        for byte in key.try_to_vec().unwrap() {
+            entry_key.push_str(&format!("{:02x}", byte));
+        }
+        entry_key.into_bytes()
+    }
+}
+
+// Only the slot is serialized, so storing a service does not touch the contents.
+
+impl<T> BorshSerialize for StorageCell<T> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.slot.serialize(writer)
+    }
+}
+
+impl<T> BorshDeserialize for StorageCell<T> {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            slot: u32::deserialize(reader)?,
+            _value: PhantomData,
+        })
+    }
+}
+
+impl<T> BorshSerialize for StorageVec<T> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.slot.serialize(writer)
+    }
+}
+
+impl<T> BorshDeserialize for StorageVec<T> {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            slot: u32::deserialize(reader)?,
+            _elem: PhantomData,
+        })
+    }
+}
+
+impl<K, V> BorshSerialize for StorageMap<K, V> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.slot.serialize(writer)
+    }
+}
+
+impl<K, V> BorshDeserialize for StorageMap<K, V> {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            slot: u32::deserialize(reader)?,
+            _entry: PhantomData,
+        })
+    }
+}
+
+impl<T> Slotted for StorageCell<T> {
+    fn at_slot(slot: u32) -> Self {
+        Self {
+            slot,
+            _value: PhantomData,
+        }
+    }
+
+    fn slot(&self) -> u32 {
+        self.slot
+    }
+}
+
+impl<T> Slotted for StorageVec<T> {
+    fn at_slot(slot: u32) -> Self {
+        Self {
+            slot,
+            _elem: PhantomData,
+        }
+    }
+
+    fn slot(&self) -> u32 {
+        self.slot
+    }
+}
+
+impl<K, V> Slotted for StorageMap<K, V> {
+    fn at_slot(slot: u32) -> Self {
+        Self {
+            slot,
+            _entry: PhantomData,
+        }
+    }
+
+    fn slot(&self) -> u32 {
+        self.slot
+    }
+}
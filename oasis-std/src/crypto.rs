@@ -0,0 +1,93 @@
+//! Hashing and helpers for handling secret values in confidential services.
+
+/// Returns the keccak256 hash of `data`, as used by Ethereum, e.g., to derive storage keys.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    tiny_keccak::keccak256(data)
+}
+
+/// Returns the SHA-256 hash of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    use sha2::Digest as _;
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&sha2::Sha256::digest(data));
+    hash
+}
+
+/// Returns whether `a` and `b` are equal in time that depends only on their lengths.
+///
+/// Unlike `==`, which returns at the first differing byte, every byte is compared,
+/// so the time taken does not reveal how much of a secret (e.g., an auth token)
+/// a guess got right. The lengths are not secret: inputs of different lengths are
+/// unequal without any bytes being compared.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::crypto::ct_eq;
+/// assert!(ct_eq(b"hunter2", b"hunter2"));
+/// assert!(!ct_eq(b"hunter2", b"hunter3"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    // The volatile read keeps the optimizer from turning the fold into an early exit.
+    unsafe { std::ptr::read_volatile(&diff) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            keccak256(b"").to_vec(),
+            hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            keccak256(b"abc").to_vec(),
+            hex("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")
+        );
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(b"").to_vec(),
+            hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        assert_eq!(
+            sha256(b"abc").to_vec(),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let inputs: &[&[u8]] = &[
+            b"",
+            b"a",
+            b"b",
+            b"secret",
+            b"secreT",
+            b"Secret",
+            b"secret!",
+            &[0xff; 32],
+            &[0x00; 32],
+        ];
+        for a in inputs {
+            for b in inputs {
+                assert_eq!(ct_eq(a, b), a == b, "{:?} == {:?}", a, b);
+            }
+        }
+    }
+}
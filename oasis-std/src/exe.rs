@@ -1,5 +1,7 @@
 use oasis_types::{Address, Balance};
 
+use crate::abi::{Deserialize, Serialize};
+
 pub trait Service {
     /// Builds a service struct from items in Storage.
     fn coalesce() -> Self;
@@ -14,10 +16,34 @@ pub trait Service {
 }
 
 pub trait Event: crate::abi::Serialize {
+    /// Returns the topic that identifies this kind of event. It is always the first topic.
+    fn topic_hash() -> [u8; TOPIC_LEN]
+    where
+        Self: Sized;
+
+    /// Returns the encoded topics of the `#[indexed]` fields in declaration order.
+    fn indexed_topics(&self) -> Vec<[u8; TOPIC_LEN]>;
+
     /// Emits the ABI-encoded event with the event name and indexed fields as topics.
     /// Topics are ABI-encoded and then keccak256 hashed if longer than `TOPIC_LEN`.
     /// Currently the maximum topic length is 32.
     fn emit(&self);
+
+    /// Emits each of `events` in order, as by `emit`, but computes the event's topic once.
+    /// The hosts do not yet accept several events in one call, so each event is still
+    /// emitted separately.
+    fn emit_all(events: &[Self])
+    where
+        Self: Sized,
+    {
+        let topic_hash = Self::topic_hash();
+        for event in events {
+            let mut topics = vec![topic_hash];
+            topics.append(&mut event.indexed_topics());
+            let topic_refs: Vec<&[u8]> = topics.iter().map(|t| t.as_ref()).collect();
+            crate::backend::emit(&topic_refs, &event.try_to_vec().unwrap());
+        }
+    }
 }
 
 /// The maximum length of a topic. Topics longer than this will be keccak256 hashed.
@@ -32,10 +58,110 @@ pub fn encode_event_topic<T: crate::abi::Serialize>(topic: &T) -> [u8; TOPIC_LEN
         topic[..repr.len()].copy_from_slice(&repr);
         topic
     } else {
-        tiny_keccak::keccak256(&repr)
+        crate::crypto::keccak256(&repr)
+    }
+}
+
+/// Encodes the output of an RPC that returns a `Result`, however it is aliased.
+/// The `Err` is encoded separately so that the dispatcher can return it as an error.
+///
+/// Together with `EncodeOutput`, this uses autoref-based specialization:
+/// `(&output).encode_output()` resolves to this trait only when `output` is a `Result`.
+#[doc(hidden)]
+pub trait EncodeResultOutput {
+    fn encode_output(&self) -> Result<Vec<u8>, Vec<u8>>;
+}
+
+impl<T: crate::abi::Serialize, E: crate::abi::Serialize> EncodeResultOutput for Result<T, E> {
+    fn encode_output(&self) -> Result<Vec<u8>, Vec<u8>> {
+        match self {
+            Ok(output) => Ok(output.try_to_vec().unwrap()),
+            Err(err) => Err(err.try_to_vec().unwrap()),
+        }
+    }
+}
+
+/// Encodes the output of an RPC that does not return a `Result`. See `EncodeResultOutput`.
+#[doc(hidden)]
+pub trait EncodeOutput {
+    fn encode_output(&self) -> Result<Vec<u8>, Vec<u8>>;
+}
+
+impl<T: crate::abi::Serialize + ?Sized> EncodeOutput for &T {
+    fn encode_output(&self) -> Result<Vec<u8>, Vec<u8>> {
+        Ok((**self).try_to_vec().unwrap())
+    }
+}
+
+/// Converts the output of a service constructor, which is either the service or a
+/// `Result` of the service, into the service or the encoded error.
+/// The generated dispatcher implements this trait for the service itself.
+///
+/// Since the return type is checked after aliases are resolved, a constructor that returns
+/// any other type (e.g., `Option<Self>`) is rejected even if it is spelled like `Fallible<Self>`.
+///
+/// ```compile_fail
+/// struct Service;
+/// type Fallible<T> = Option<T>;
+/// let ctor_output: Fallible<Service> = Some(Service);
+/// oasis_std::CtorOutput::<Service>::into_ctor_result(ctor_output);
+/// ```
+#[doc(hidden)]
+#[rustc_on_unimplemented(
+    message = "service constructor must return `Self` or `Result<Self, E>`, not `{Self}`",
+    label = "the constructor of `{S}` returns `{Self}`"
+)]
+pub trait CtorOutput<S> {
+    fn into_ctor_result(self) -> Result<S, Vec<u8>>;
+}
+
+impl<S, E: Serialize> CtorOutput<S> for Result<S, E> {
+    fn into_ctor_result(self) -> Result<S, Vec<u8>> {
+        // The error is encoded like that of an RPC, so clients can decode the IDL's `E`.
+        self.map_err(|err| err.try_to_vec().unwrap())
+    }
+}
+
+/// An application-level status that an RPC method can return alongside its output as
+/// `(StatusCode, T)`. Unlike an `Err`, the status does not revert the call.
+///
+/// The status is encoded as a `u16` header before the output, and generated clients
+/// return the pair as `(u16, T)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StatusCode(pub u16);
+
+impl Serialize for StatusCode {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.0.serialize(writer)
+    }
+}
+
+impl Deserialize for StatusCode {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self(u16::deserialize(reader)?))
     }
 }
 
+impl From<u16> for StatusCode {
+    fn from(code: u16) -> Self {
+        Self(code)
+    }
+}
+
+/// Reports a panic to the caller as an `RpcError::Execution` containing the panic message,
+/// e.g., `panicked at 'no entry found for key', src/main.rs:10:5`.
+///
+/// The generated dispatcher installs this hook before running the constructor or an RPC.
+/// A service that wishes to report panics differently may install its own hook using
+/// `std::panic::set_hook`.
+pub fn panic_hook(info: &std::panic::PanicInfo) {
+    crate::backend::err(&panic_message(info));
+}
+
+pub(crate) fn panic_message(info: &std::panic::PanicInfo) -> Vec<u8> {
+    info.to_string().into_bytes()
+}
+
 /// The context of the current RPC.
 /// To create a `Context`, use `Context::default()`.
 /// The default `Context` will have its `sender` be the address of the current service
@@ -68,6 +194,18 @@ impl Context {
         self.sender.unwrap_or_else(crate::backend::sender)
     }
 
+    /// Returns the `Address` of the account that initiated the top-level transaction.
+    /// The origin stays the same across cross-service calls, whereas the sender is
+    /// always the immediate caller.
+    ///
+    /// **Security**: do not use `origin` for authorization. Any service that a user calls
+    /// can go on to call (or re-enter) your service with that user as the origin.
+    /// Checking `sender` ensures that the caller is who it claims to be.
+    pub fn origin(&self) -> Address {
+        // A sender set during testing is an external account, which makes it the origin.
+        self.sender.unwrap_or_else(crate::backend::origin)
+    }
+
     /// Returns the AAD of the confidential execution.
     pub fn aad(&self) -> Vec<u8> {
         crate::backend::aad()
@@ -77,6 +215,13 @@ impl Context {
     pub fn value(&self) -> Balance {
         self.value.unwrap_or_else(crate::backend::value)
     }
+
+    /// Returns the time of the block containing the current transaction,
+    /// in seconds since the Unix epoch. In tests, this is the time of the test chain's
+    /// current block, as set by the `TestClock`.
+    pub fn timestamp(&self) -> u64 {
+        crate::backend::timestamp()
+    }
 }
 
 impl Context {
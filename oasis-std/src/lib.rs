@@ -1,24 +1,38 @@
 #![feature(
     linkage,
     proc_macro_hygiene,
+    rustc_attrs, // `CtorOutput`
     specialization,
     trait_alias,
     // the following are used by `collections::*`
+    const_generics,
     drain_filter,
     shrink_to,
     try_reserve,
 )]
+#![allow(incomplete_features)] // `const_generics`
 #![cfg_attr(target_os = "wasi", feature(wasi_ext))]
 
 extern crate oasis_macros;
 
+#[cfg(feature = "wee-alloc")]
+mod alloc;
 pub mod backend;
 pub mod collections;
+pub mod crypto;
 pub mod exe;
+pub mod pagination;
 
+/// Encoding and decoding of RPC payloads and outputs.
+///
+/// The encoding is provided by the `oasis-abi` crate, which can be used without `std`
+/// by off-chain code that needs to encode or decode payloads.
 pub mod abi {
+    pub mod float;
+
     pub extern crate oasis_borsh;
-    pub use oasis_borsh::{BorshDeserialize as Deserialize, BorshSerialize as Serialize};
+    pub use oasis_abi::{i256, u256, Deserialize, Serialize, StreamDecoder, I256, U256};
+    pub use oasis_macros::{OrderedStruct, TaggedEnum};
 
     /// Encodes arguments into the format expected by Oasis services.
     ///
@@ -38,29 +52,24 @@ pub mod abi {
             $crate::abi_encode!($($arg),* => Vec::new())
         };
         ($( $arg:expr ),* $(,)? => $buf:expr) => {
-            Ok($buf)
-                $(
-                    .and_then(|mut buf| {
-                        #[allow(unused)] {
-                            use $crate::abi::Serialize as _;
-                            $arg.serialize(&mut buf)?;
-                        }
-                        Ok(buf)
-                    })
-                )*
-                .map_err(|_: std::io::Error| $crate::RpcError::InvalidInput)
+            $crate::reexports::oasis_abi::abi_encode!($($arg),* => $buf)
+                .map_err(|_| $crate::RpcError::InvalidInput(None))
         };
     }
 }
 
-#[cfg(not(target_os = "wasi"))]
 #[doc(hidden)]
 pub mod reexports {
+    pub extern crate oasis_abi; // used by `abi_encode!`
+    #[cfg(not(target_os = "wasi"))]
     pub extern crate oasis_client; // used by generated clients
+    #[cfg(not(target_os = "wasi"))]
     pub extern crate oasis_test; // links the dylib containing the `backend::ext` externs
+    #[cfg(feature = "cbor")]
+    pub extern crate serde_cbor; // used by `#[oasis(storage = "cbor")]` services
 }
 
-pub use oasis_macros::{default, Event, Service};
+pub use oasis_macros::{default, invariant, skip, view, Event, Service};
 pub use oasis_types::{Address, Balance, RpcError};
 
 pub use crate::exe::*;
@@ -125,4 +134,52 @@ mod tests {
         assert_eq!(decoded.1, things.1);
         assert_eq!(decoded.2, things.2);
     }
+
+    #[test]
+    fn test_panic_message() {
+        thread_local! {
+            static MESSAGE: std::cell::RefCell<Vec<u8>> = Default::default();
+        }
+
+        // `panic_hook` itself would abort the test, so only its message is captured.
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|info| {
+            MESSAGE.with(|message| *message.borrow_mut() = exe::panic_message(info))
+        }));
+        let result = std::panic::catch_unwind(|| {
+            let balances = std::collections::HashMap::<Address, u64>::new();
+            balances[&Address::default()]
+        });
+        std::panic::set_hook(default_hook);
+
+        assert!(result.is_err());
+        let message = MESSAGE.with(|message| String::from_utf8(message.borrow().clone()).unwrap());
+        assert!(
+            message.starts_with("panicked at 'no entry found for key', "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_self_code_hash() {
+        const CODE: &[u8] = b"\0asm self code hash";
+        let service = oasis_test::deploy_service(CODE);
+        assert_eq!(backend::address(), service);
+        let code_hash = backend::self_code_hash().unwrap();
+        assert_eq!(code_hash, crypto::keccak256(CODE));
+
+        oasis_test::deploy_service(b"\0asm upgraded");
+        assert_ne!(backend::self_code_hash().unwrap(), code_hash);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let ctx = Context::default();
+        assert_eq!(ctx.timestamp(), oasis_test::TEST_EPOCH);
+        oasis_test::TestClock::advance(std::time::Duration::from_secs(2));
+        assert_eq!(ctx.timestamp(), oasis_test::TEST_EPOCH + 2);
+        oasis_test::TestClock::set(1_600_000_000);
+        assert_eq!(ctx.timestamp(), 1_600_000_000);
+    }
 }
@@ -0,0 +1,91 @@
+//! Consistent pagination for RPCs that return part of a list.
+
+/// Splits a slice into pages of at most `limit` items starting at `offset`.
+///
+/// A page is returned along with the offset of the next page, which is `None`
+/// if the page reaches the end of the slice. Since both halves are ordinary RPC types,
+/// a service can return the pair directly and clients can iterate until `next_offset`
+/// is `None`.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_std::pagination::Paginate as _;
+/// let posts = vec!["a", "b", "c"];
+/// assert_eq!(posts.paginate(0, 2), (&["a", "b"][..], Some(2)));
+/// assert_eq!(posts.paginate(2, 2), (&["c"][..], None));
+/// ```
+pub trait Paginate {
+    type Item;
+
+    /// Returns the items in `offset..offset + limit` that are in bounds and the offset
+    /// of the item following the page, if any. An `offset` past the end or a `limit` of zero
+    /// yields an empty page with no next offset, so iteration always terminates.
+    fn paginate(&self, offset: u32, limit: u32) -> (&[Self::Item], Option<u32>);
+}
+
+impl<T> Paginate for [T] {
+    type Item = T;
+
+    fn paginate(&self, offset: u32, limit: u32) -> (&[T], Option<u32>) {
+        let start = std::cmp::min(offset as usize, self.len());
+        let end = start + std::cmp::min(limit as usize, self.len() - start);
+        let next_offset = if limit > 0 && end < self.len() {
+            Some(end as u32)
+        } else {
+            None
+        };
+        (&self[start..end], next_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_empty() {
+        let empty: &[u8] = &[];
+        assert_eq!(empty.paginate(0, 10), (empty, None));
+        assert_eq!(empty.paginate(5, 10), (empty, None));
+
+        let items = [1u8, 2, 3];
+        assert_eq!(items.paginate(1, 0), (empty, None));
+    }
+
+    #[test]
+    fn test_paginate_out_of_bounds() {
+        let empty: &[u8] = &[];
+        let items = [1u8, 2, 3];
+        assert_eq!(items.paginate(3, 2), (empty, None));
+        assert_eq!(
+            items.paginate(u32::max_value(), u32::max_value()),
+            (empty, None)
+        );
+    }
+
+    #[test]
+    fn test_paginate_pages() {
+        let items: Vec<u32> = (0..5).collect();
+        assert_eq!(items.paginate(0, 2), (&[0, 1][..], Some(2)));
+        assert_eq!(items.paginate(2, 2), (&[2, 3][..], Some(4)));
+        // The last page may be short.
+        assert_eq!(items.paginate(4, 2), (&[4][..], None));
+        // A page that exactly reaches the end is the last page.
+        assert_eq!(items.paginate(3, 2), (&[3, 4][..], None));
+        assert_eq!(items.paginate(1, u32::max_value()), (&items[1..], None));
+    }
+
+    #[test]
+    fn test_paginate_iterate() {
+        let items: Vec<u32> = (0..7).collect();
+        let mut pages = Vec::new();
+        let mut offset = Some(0);
+        while let Some(page_offset) = offset {
+            let (page, next_offset) = items.paginate(page_offset, 3);
+            pages.push(page.to_vec());
+            offset = next_offset;
+        }
+        assert_eq!(pages, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    }
+}
@@ -0,0 +1,14 @@
+#[test]
+fn test_call_charges_gas_price() {
+    const GAS_PRICE: u64 = 3;
+
+    let payer = oasis_test::create_account(1_000_000_000);
+    let callee = oasis_test::create_account(0);
+
+    oasis_test::transact(payer, callee, 100, &[], Some(100_000), GAS_PRICE).unwrap();
+
+    // The call consumes only the base gas of 2100.
+    let fee = 2100 * u128::from(GAS_PRICE);
+    assert_eq!(oasis_test::balance(&payer), Some(1_000_000_000 - fee - 100));
+    assert_eq!(oasis_test::balance(&callee), Some(100));
+}
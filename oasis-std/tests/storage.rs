@@ -0,0 +1,171 @@
+extern crate oasis_test;
+
+use oasis_std::{
+    abi::*,
+    collections::{StorageCell, StorageMap, StorageVec},
+    Service,
+};
+
+#[derive(Service, Clone, Debug, Default, PartialEq)]
+pub struct BorshCounter {
+    count: u64,
+    names: Vec<String>,
+}
+
+#[test]
+fn test_borsh_storage() {
+    let counter = BorshCounter {
+        count: 42,
+        names: vec!["getafix".to_string(), "caesar".to_string()],
+    };
+    BorshCounter::sunder(counter.clone());
+    assert_eq!(BorshCounter::coalesce(), counter);
+    assert_eq!(oasis_std::backend::read(b"count"), 42u64.to_le_bytes());
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Post {
+    author: String,
+    text: String,
+}
+
+#[derive(Service)]
+pub struct Blog {
+    title: String,
+    #[oasis(slot)]
+    posts: StorageVec<Post>,
+}
+
+#[test]
+fn test_storage_vec() {
+    let post = |text: &str| Post {
+        author: "asterix".to_string(),
+        text: text.to_string(),
+    };
+
+    let mut blog = Blog {
+        title: "Gaul".to_string(),
+        posts: StorageVec::new(1),
+    };
+    blog.posts.push(&post("by Toutatis"));
+    Blog::sunder(blog);
+
+    // The `StorageVec` is not stored with the rest of the service.
+    assert!(oasis_std::backend::read(b"posts").is_empty());
+
+    // Corrupt the first post to show that pushing does not reread earlier elements.
+    oasis_std::backend::write(b"\0slot1.0", b"not a post");
+
+    let mut blog = Blog::coalesce();
+    blog.posts.push(&post("the sky is falling"));
+    blog.posts.push(&post("boar for dinner"));
+    assert_eq!(blog.posts.len(), 3);
+    assert_eq!(blog.posts.get(2), Some(post("boar for dinner")));
+    assert_eq!(blog.posts.get(3), None);
+
+    blog.posts.set(0, &post("by Belenos"));
+    assert_eq!(
+        blog.posts.iter().map(|p| p.text).collect::<Vec<_>>(),
+        vec!["by Belenos", "the sky is falling", "boar for dinner"]
+    );
+}
+
+#[derive(Service)]
+pub struct Registry {
+    #[oasis(slot)]
+    owners: StorageMap<String, oasis_std::Address>,
+}
+
+#[test]
+fn test_storage_map_tombstones() {
+    let owner = oasis_std::Address([1u8; 20]);
+    let name = |name: &str| name.to_string();
+
+    let mut registry = Registry {
+        owners: StorageMap::with_tombstones(0),
+    };
+    registry.owners.insert(&name("gaul"), &owner);
+    registry.owners.insert(&name("rome"), &owner);
+    Registry::sunder(registry);
+
+    let mut registry = Registry::coalesce();
+    assert_eq!(registry.owners.remove(&name("gaul")), Some(owner));
+    assert_eq!(registry.owners.remove(&name("gaul")), None);
+    assert_eq!(registry.owners.get(&name("gaul")), None);
+    assert!(!registry.owners.contains_key(&name("gaul")));
+    assert!(registry.owners.was_removed(&name("gaul")));
+
+    assert!(!registry.owners.was_removed(&name("rome")));
+    assert!(!registry.owners.was_removed(&name("egypt")));
+
+    registry.owners.insert(&name("gaul"), &owner);
+    assert!(!registry.owners.was_removed(&name("gaul")));
+
+    // Without tombstones, a removed key is indistinguishable from one never set.
+    let mut owners = StorageMap::<String, oasis_std::Address>::new(3);
+    owners.insert(&name("gaul"), &owner);
+    assert_eq!(owners.remove(&name("gaul")), Some(owner));
+    assert_eq!(owners.get(&name("gaul")), None);
+    assert!(!owners.was_removed(&name("gaul")));
+}
+
+#[derive(Service)]
+pub struct Archive {
+    shelf: String,
+    _slot2: u64,
+    #[oasis(slot)]
+    scrolls: StorageCell<String>,
+}
+
+#[test]
+fn test_slot_keys() {
+    let mut archive = Archive {
+        shelf: "Alexandria".to_string(),
+        _slot2: 42,
+        scrolls: StorageCell::new(2),
+    };
+    archive.scrolls.set(&"Elements".to_string());
+    Archive::sunder(archive);
+
+    // A slot's key is not the key of a field, even one named like it.
+    let archive = Archive::coalesce();
+    assert_eq!(archive._slot2, 42);
+    assert_eq!(archive.scrolls.get().as_deref(), Some("Elements"));
+}
+
+#[test]
+#[should_panic(expected = "`scrolls` must be stored in slot 2")]
+fn test_wrong_slot() {
+    Archive::sunder(Archive {
+        shelf: String::new(),
+        _slot2: 0,
+        scrolls: StorageCell::new(4),
+    });
+}
+
+#[cfg(feature = "cbor")]
+#[derive(Service, Clone, Debug, Default, PartialEq)]
+#[oasis(storage = "cbor")]
+pub struct CborCounter {
+    count: u64,
+    names: Vec<String>,
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor_storage() {
+    use oasis_std::reexports::serde_cbor;
+
+    let counter = CborCounter {
+        count: 42,
+        names: vec!["getafix".to_string(), "caesar".to_string()],
+    };
+    CborCounter::sunder(counter.clone());
+    assert_eq!(CborCounter::coalesce(), counter);
+
+    let stored_names = oasis_std::backend::read(b"names");
+    assert_eq!(
+        serde_cbor::from_slice::<Vec<String>>(&stored_names).unwrap(),
+        counter.names
+    );
+}
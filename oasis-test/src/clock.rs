@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use crate::MEMCHAIN;
+
+/// The time at which every `TestClock` starts: 2020-01-01T00:00:00Z.
+pub const TEST_EPOCH: u64 = 1_577_836_800;
+
+/// The clock that provides `Context::timestamp` to services under test.
+///
+/// The clock is the timestamp of the test chain's current block, so services called
+/// through the test chain observe the same time as the service under test.
+/// The clock only moves when told to, so tests that depend on time are deterministic.
+/// Each test thread has its own clock, which starts at `TEST_EPOCH`.
+///
+/// ## Example
+///
+/// ```
+/// use oasis_test::TestClock;
+/// use std::time::Duration;
+///
+/// TestClock::set(1_000);
+/// TestClock::advance(Duration::from_secs(60));
+/// assert_eq!(TestClock::now(), 1_060);
+/// ```
+pub struct TestClock;
+
+impl TestClock {
+    /// Returns the current time in seconds since the Unix epoch.
+    pub fn now() -> u64 {
+        MEMCHAIN.with(|memchain| memchain.borrow().blocks.last().unwrap().timestamp)
+    }
+
+    /// Sets the current time to `timestamp` seconds since the Unix epoch.
+    pub fn set(timestamp: u64) {
+        MEMCHAIN.with(|memchain| {
+            memchain.borrow_mut().blocks.last_mut().unwrap().timestamp = timestamp;
+        });
+    }
+
+    /// Moves the clock forward by `duration`, truncated to whole seconds.
+    pub fn advance(duration: Duration) {
+        Self::set(Self::now() + duration.as_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance() {
+        assert_eq!(TestClock::now(), TEST_EPOCH);
+        TestClock::advance(Duration::from_secs(2));
+        assert_eq!(TestClock::now(), TEST_EPOCH + 2);
+        TestClock::advance(Duration::from_millis(999));
+        assert_eq!(TestClock::now(), TEST_EPOCH + 2);
+
+        TestClock::set(42);
+        TestClock::advance(Duration::from_secs(8));
+        assert_eq!(TestClock::now(), 50);
+
+        let mut timestamp = 0u64;
+        crate::ext::oasis_timestamp(&mut timestamp as *mut _);
+        assert_eq!(timestamp, 50);
+    }
+}
@@ -1,32 +1,99 @@
 #![allow(improper_ctypes, unused)] // ExtStatusCode is `repr(u32)` but non-exhaustive
 
-use oasis_types::{Address, ExtStatusCode};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use blockchain_traits::PendingTransaction;
+use oasis_types::{Address, Event, ExtStatusCode};
+
+thread_local! {
+    /// The storage of the service under test.
+    static STORAGE: RefCell<HashMap<Vec<u8>, Vec<u8>>> = RefCell::new(HashMap::new());
+
+    /// The events emitted by the service under test.
+    static EVENTS: RefCell<Vec<Event>> = RefCell::new(Vec::new());
+
+    /// The memchain transaction in which a service is running. @see `run_in_transaction`.
+    pub(crate) static PTX: Cell<Option<*mut dyn PendingTransaction>> = Cell::new(None);
+}
+
+pub(crate) fn take_events() -> Vec<Event> {
+    EVENTS.with(|events| events.borrow_mut().split_off(0))
+}
+
+/// Returns the memchain transaction in which a service is running, if any.
+/// While there is one, the externs operate on it instead of on the service under test.
+fn ptx() -> Option<&'static mut dyn PendingTransaction> {
+    // The pointer is valid until `run_in_transaction` returns, which is after the extern
+    // call that borrows it.
+    PTX.with(Cell::get).map(|ptx| unsafe { &mut *ptx })
+}
+
+unsafe fn storage_key(key: *const u8, key_len: u32) -> Vec<u8> {
+    std::slice::from_raw_parts(key, key_len as usize).to_vec()
+}
 
 #[no_mangle]
 static oasis_testing: bool = true;
 
 #[no_mangle]
 pub extern "C" fn oasis_balance(addr: *const Address, balance: *mut u128) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        match ptx.account_meta_at(unsafe { &*addr }) {
+            Some(meta) => unsafe { *balance = meta.balance },
+            None => return ExtStatusCode::NoAccount,
+        }
+    }
     ExtStatusCode::Success
 }
 
+/// Calls `f` with the code of the account at `addr` on the test chain, if it exists.
+fn with_code<T>(addr: *const Address, f: impl FnOnce(&[u8]) -> T) -> Option<T> {
+    if let Some(ptx) = ptx() {
+        return ptx.code_at(unsafe { &*addr }).map(f);
+    }
+    crate::MEMCHAIN.with(|memchain| {
+        use blockchain_traits::{Block as _, Blockchain as _};
+        memchain
+            .borrow()
+            .last_block()
+            .code_at(unsafe { &*addr })
+            .map(f)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn oasis_code(addr: *const Address, buf: *mut u8) -> ExtStatusCode {
-    ExtStatusCode::Success
+    with_code(addr, |code| unsafe {
+        std::ptr::copy_nonoverlapping(code.as_ptr(), buf, code.len())
+    })
+    .map(|_| ExtStatusCode::Success)
+    .unwrap_or(ExtStatusCode::NoAccount)
 }
 
 #[no_mangle]
 pub extern "C" fn oasis_code_len(at: *const Address, len: *mut u32) -> ExtStatusCode {
-    ExtStatusCode::Success
+    with_code(at, |code| unsafe { *len = code.len() as u32 })
+        .map(|_| ExtStatusCode::Success)
+        .unwrap_or(ExtStatusCode::NoAccount)
 }
 
 #[no_mangle]
 pub extern "C" fn oasis_fetch_input(buf: *mut u8) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        let input = ptx.input();
+        unsafe { buf.copy_from_nonoverlapping(input.as_ptr(), input.len()) };
+    }
     ExtStatusCode::Success
 }
 
 #[no_mangle]
 pub extern "C" fn oasis_input_len(len: *mut u32) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        unsafe { *len = ptx.input().len() as u32 };
+    }
     ExtStatusCode::Success
 }
 
@@ -87,21 +154,64 @@ pub extern "C" fn oasis_transact(
 
 #[no_mangle]
 pub extern "C" fn oasis_address(addr: *mut Address) -> ExtStatusCode {
+    unsafe {
+        *addr = match ptx() {
+            Some(ptx) => *ptx.address(),
+            None => crate::SERVICE_ADDR.with(|service_addr| *service_addr.borrow()),
+        }
+    };
     ExtStatusCode::Success
 }
 
 #[no_mangle]
 pub extern "C" fn oasis_sender(addr: *mut Address) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        unsafe { *addr = *ptx.sender() };
+    }
+    ExtStatusCode::Success
+}
+
+#[no_mangle]
+pub extern "C" fn oasis_origin(addr: *mut Address) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        unsafe { *addr = *ptx.origin() };
+    }
     ExtStatusCode::Success
 }
 
 #[no_mangle]
 pub extern "C" fn oasis_value(value: *mut u128) -> ExtStatusCode {
+    if let Some(ptx) = ptx() {
+        unsafe { *value = ptx.value() };
+    }
     ExtStatusCode::Success
 }
 
+#[no_mangle]
+pub extern "C" fn oasis_timestamp(timestamp: *mut u64) -> ExtStatusCode {
+    unsafe {
+        *timestamp = match ptx() {
+            Some(ptx) => ptx.timestamp(),
+            None => crate::TestClock::now(),
+        }
+    };
+    ExtStatusCode::Success
+}
+
+/// Returns the value stored under `key` by the service.
+fn storage_get(key: &[u8]) -> Option<Vec<u8>> {
+    match ptx() {
+        Some(ptx) => ptx.state().get(key),
+        None => STORAGE.with(|storage| storage.borrow().get(key).cloned()),
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn oasis_read(key: *const u8, key_len: u32, value: *mut u8) -> ExtStatusCode {
+    let key = unsafe { storage_key(key, key_len) };
+    if let Some(val) = storage_get(&key) {
+        unsafe { value.copy_from_nonoverlapping(val.as_ptr(), val.len()) };
+    }
     ExtStatusCode::Success
 }
 
@@ -111,6 +221,9 @@ pub extern "C" fn oasis_read_len(
     key_len: u32,
     value_len: *mut u32,
 ) -> ExtStatusCode {
+    let key = unsafe { storage_key(key, key_len) };
+    let len = storage_get(&key).map(|val| val.len()).unwrap_or(0);
+    unsafe { *value_len = len as u32 };
     ExtStatusCode::Success
 }
 
@@ -121,6 +234,14 @@ pub extern "C" fn oasis_write(
     value: *const u8,
     value_len: u32,
 ) -> ExtStatusCode {
+    let key = unsafe { storage_key(key, key_len) };
+    let value = unsafe { std::slice::from_raw_parts(value, value_len as usize).to_vec() };
+    match ptx() {
+        Some(ptx) => ptx.state_mut().set(&key, &value),
+        None => {
+            STORAGE.with(|storage| storage.borrow_mut().insert(key, value));
+        }
+    }
     ExtStatusCode::Success
 }
 
@@ -132,5 +253,41 @@ pub extern "C" fn oasis_emit(
     data: *const u8,
     data_len: u32,
 ) -> ExtStatusCode {
+    let (topics, topic_lens, data) = unsafe {
+        (
+            std::slice::from_raw_parts(topics, num_topics as usize),
+            std::slice::from_raw_parts(topic_lens, num_topics as usize),
+            std::slice::from_raw_parts(data, data_len as usize),
+        )
+    };
+    if let Some(ptx) = ptx() {
+        let topics: Vec<&[u8]> = topics
+            .iter()
+            .zip(topic_lens)
+            .map(|(&topic, &topic_len)| unsafe {
+                std::slice::from_raw_parts(topic, topic_len as usize)
+            })
+            .collect();
+        ptx.emit(&topics, data);
+        return ExtStatusCode::Success;
+    }
+    let topics = topics
+        .iter()
+        .zip(topic_lens)
+        .map(|(&topic, &topic_len)| {
+            let topic = unsafe { std::slice::from_raw_parts(topic, topic_len as usize) };
+            let mut t_arr = [0u8; 32];
+            let topic_len = std::cmp::min(topic.len(), 32);
+            t_arr[..topic_len].copy_from_slice(&topic[..topic_len]);
+            t_arr
+        })
+        .collect();
+    EVENTS.with(|events| {
+        events.borrow_mut().push(Event {
+            emitter: Address::default(),
+            topics,
+            data: data.to_vec(),
+        })
+    });
     ExtStatusCode::Success
 }
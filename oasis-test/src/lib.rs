@@ -1,25 +1,40 @@
+mod clock;
 mod ext;
 
 use std::cell::RefCell;
 
 use blockchain_traits::Blockchain as _;
-use memchain::Memchain;
-use oasis_types::Address;
+use memchain::{Memchain, PtxPtr};
+use oasis_borsh::BorshSerialize as _;
+use oasis_types::{Address, Event, RpcError};
+
+pub use blockchain_traits::TransactionOutcome;
+pub use clock::{TestClock, TEST_EPOCH};
+pub use memchain::Receipt;
 
 const SEED_ADDR: Address = Address([0xffu8; 20]);
 const BASE_GAS: u64 = 2100;
 
+/// The gas limit of a call made without `Context::with_gas`.
+const DEFAULT_GAS: u64 = 1_000_000;
+
 thread_local! {
-    static MEMCHAIN: RefCell<Memchain<'static>> =
-        RefCell::new(Memchain::new("testnet".to_string(), {
+    static MEMCHAIN: RefCell<Memchain<'static>> = RefCell::new({
+        let mut memchain = Memchain::new("testnet".to_string(), {
             let mut genesis_state = std::collections::HashMap::new();
             genesis_state.insert(SEED_ADDR, std::borrow::Cow::Owned(memchain::Account {
                 balance: u128::max_value(),
                 ..Default::default()
             }));
             genesis_state
-        }, BASE_GAS));
+        }, BASE_GAS);
+        memchain.blocks[0].timestamp = TEST_EPOCH;
+        memchain
+    });
     static NEXT_ADDR: RefCell<u64> = RefCell::new(0);
+
+    /// The address of the service under test. @see `deploy_service`.
+    static SERVICE_ADDR: RefCell<Address> = RefCell::new(Address::default());
 }
 
 pub fn create_account(initial_balance: u128) -> Address {
@@ -40,17 +55,335 @@ pub fn create_account(initial_balance: u128) -> Address {
             }
         });
 
-        // TODO(#77)
-        memchain.last_block_mut().transact(
-            SEED_ADDR,
-            new_addr,
-            SEED_ADDR,
-            initial_balance,
-            &[], /* input */
-            BASE_GAS,
-            0, /* gas price */
-        );
+        fund_new_account(&mut memchain, new_addr, initial_balance);
 
         new_addr
     })
 }
+
+/// Creates an account with `code` on the test chain and makes it the service under test,
+/// so that the service's `address` and its code are those of the new account.
+pub fn deploy_service(code: &[u8]) -> Address {
+    let addr = create_account(0);
+    MEMCHAIN.with(|memchain| {
+        let mut memchain = memchain.borrow_mut();
+        let block = memchain.blocks.last_mut().unwrap();
+        block.state.get_mut(&addr).unwrap().to_mut().code = code.to_vec();
+    });
+    SERVICE_ADDR.with(|service_addr| *service_addr.borrow_mut() = addr);
+    addr
+}
+
+/// Returns the address of an account derived from `seed`, creating the account with
+/// `initial_balance` if it does not already exist. The same seed always yields the same
+/// address, which makes it useful for naming the accounts used by a test.
+pub fn account_from_seed(seed: &str, initial_balance: u128) -> Address {
+    let mut addr = Address::default();
+    let addr_len = addr.0.len();
+    addr.0
+        .copy_from_slice(&tiny_keccak::keccak256(seed.as_bytes())[32 - addr_len..]);
+    MEMCHAIN.with(|memchain| {
+        let mut memchain = memchain.borrow_mut();
+        if memchain.last_block().account_meta_at(&addr).is_none() {
+            fund_new_account(&mut memchain, addr, initial_balance);
+        }
+    });
+    addr
+}
+
+fn fund_new_account(memchain: &mut Memchain<'static>, addr: Address, initial_balance: u128) {
+    // The account must exist before it can be funded.
+    memchain
+        .blocks
+        .last_mut()
+        .unwrap()
+        .state
+        .insert(addr, std::borrow::Cow::Owned(Default::default()));
+    memchain.last_block_mut().transact(
+        SEED_ADDR,
+        addr,
+        SEED_ADDR,
+        initial_balance,
+        &[], /* input */
+        BASE_GAS,
+        0, /* gas price */
+    );
+}
+
+/// Returns the balance of the account at `addr`, if it exists.
+pub fn balance(addr: &Address) -> Option<u128> {
+    MEMCHAIN.with(|memchain| {
+        memchain
+            .borrow()
+            .last_block()
+            .account_meta_at(addr)
+            .map(|meta| meta.balance)
+    })
+}
+
+/// Returns the receipt of the last transaction executed on the test chain.
+/// Use it to inspect the outcome of a call, including one that was reverted.
+///
+/// # Panics
+///
+/// Panics if no transaction has been executed in the current block.
+pub fn last_receipt() -> Receipt {
+    MEMCHAIN.with(|memchain| {
+        memchain
+            .borrow()
+            .blocks
+            .last()
+            .and_then(|block| block.completed_transactions.last().cloned())
+            .expect("`last_receipt` called before any transaction was executed")
+    })
+}
+
+/// Returns the events emitted by the service under test since the last call, oldest first.
+/// Events emitted by services called through the test chain are in their `Receipt`s.
+pub fn take_events() -> Vec<Event> {
+    ext::take_events()
+}
+
+/// Sends `value` and `input` from `caller` to `callee`. The `caller` pays
+/// `gas_used * gas_price` for the transaction.
+///
+/// This is how a test makes a call as an external account and models its fees, since
+/// services cannot choose the gas price of the calls they make.
+pub fn transact(
+    caller: Address,
+    callee: Address,
+    value: u128,
+    input: &[u8],
+    gas: Option<u64>,
+    gas_price: u64,
+) -> Result<Vec<u8>, RpcError> {
+    MEMCHAIN.with(|memchain| {
+        let receipt = memchain.borrow_mut().last_block_mut().transact(
+            caller,
+            callee,
+            caller, /* payer */
+            value,
+            input,
+            gas.unwrap_or(DEFAULT_GAS),
+            gas_price,
+        );
+        match receipt.outcome() {
+            TransactionOutcome::Success => Ok(receipt.output().to_vec()),
+            TransactionOutcome::InsufficientFunds => Err(RpcError::InsufficientFunds),
+            TransactionOutcome::InsufficientGas => Err(RpcError::InsufficientGas),
+            TransactionOutcome::InvalidInput => Err(RpcError::invalid_input(receipt.output())),
+            TransactionOutcome::InvalidCallee => Err(RpcError::InvalidCallee),
+            _ => Err(RpcError::Execution(receipt.output().to_vec())),
+        }
+    })
+}
+
+/// Runs `entrypoint` as the `main` of a memchain account, which is called with `ptx`.
+/// This is how a service built for a native test runs on a memchain, such as that of an
+/// `oasis_client::MemchainGateway`: the entrypoint is one that oasis-build generates in
+/// place of a Wasm export, e.g., `__call_raw` for the service's `main`.
+///
+/// While the entrypoint runs, the service's input, sender, value, storage, and events are
+/// those of the transaction. Its output or error becomes the output of the transaction,
+/// as does the message of a panic. Returns the status with which a `main` exits.
+///
+/// ## Example
+///
+/// ```
+/// # struct Ballot;
+/// # impl Ballot {
+/// #     fn __call_raw() -> Result<Vec<u8>, oasis_types::RpcError> { Ok(Vec::new()) }
+/// # }
+/// extern "C" fn ballot_main(ptx: memchain::PtxPtr) -> u16 {
+///     oasis_test::run_in_transaction(ptx, Ballot::__call_raw)
+/// }
+/// ```
+pub fn run_in_transaction(
+    ptx: PtxPtr,
+    entrypoint: impl FnOnce() -> Result<Vec<u8>, RpcError>,
+) -> u16 {
+    let ptx = unsafe { *ptx };
+    let outer_ptx = ext::PTX.with(|current_ptx| current_ptx.replace(Some(ptx)));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(entrypoint));
+    ext::PTX.with(|current_ptx| current_ptx.set(outer_ptx));
+
+    let ptx = unsafe { &mut *ptx };
+    match result {
+        Ok(Ok(output)) => {
+            ptx.ret(&output);
+            0
+        }
+        Ok(Err(RpcError::Execution(err_output))) => {
+            ptx.err(&err_output);
+            1
+        }
+        Ok(Err(RpcError::InvalidInput(method))) => {
+            // The selector is reported as the error output. @see `RpcError::invalid_input`.
+            if let Some(selector) = method {
+                ptx.err(&selector.to_le_bytes());
+            }
+            TransactionOutcome::InvalidInput as u16
+        }
+        Ok(Err(err)) => {
+            ptx.err(&err.try_to_vec().unwrap());
+            1
+        }
+        Err(panic) => {
+            let message = match panic.downcast::<String>() {
+                Ok(message) => *message,
+                Err(panic) => panic
+                    .downcast_ref::<&str>()
+                    .map(|message| (*message).to_string())
+                    .unwrap_or_default(),
+            };
+            ptx.err(message.as_bytes());
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use blockchain_traits::{PendingTransaction, Receipt as _};
+
+    extern "C" fn abort_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+        let ptx = unsafe { &mut **ptx };
+        ptx.err(b"aborted");
+        1
+    }
+
+    extern "C" fn timestamp_main(ptx: *const *mut dyn PendingTransaction) -> u16 {
+        let ptx = unsafe { &mut **ptx };
+        let timestamp = ptx.timestamp().to_le_bytes();
+        ptx.ret(&timestamp);
+        0
+    }
+
+    /// Stores its input and returns its sender using the externs that a service calls.
+    extern "C" fn store_input_main(ptx: PtxPtr) -> u16 {
+        run_in_transaction(ptx, || {
+            let mut input_len = 0u32;
+            ext::oasis_input_len(&mut input_len);
+            let mut input = vec![0u8; input_len as usize];
+            ext::oasis_fetch_input(input.as_mut_ptr());
+            if input.is_empty() {
+                return Err(RpcError::InvalidInput(Some(7)));
+            }
+
+            let key = b"input";
+            ext::oasis_write(key.as_ptr(), key.len() as u32, input.as_ptr(), input_len);
+            if input == b"panic" {
+                panic!("oops");
+            }
+
+            let mut sender = Address::default();
+            ext::oasis_sender(&mut sender);
+            Ok(sender.0.to_vec())
+        })
+    }
+
+    #[test]
+    fn test_run_in_transaction() {
+        let caller = create_account(0);
+        let callee = create_account(0);
+        MEMCHAIN.with(|memchain| {
+            let mut memchain = memchain.borrow_mut();
+            let block = memchain.blocks.last_mut().unwrap();
+            block.state.get_mut(&callee).unwrap().to_mut().main = Some(store_input_main);
+        });
+        let stored_input = || {
+            MEMCHAIN.with(|memchain| {
+                let memchain = memchain.borrow();
+                memchain
+                    .last_block()
+                    .state_at(&callee)
+                    .unwrap()
+                    .get(b"input")
+            })
+        };
+
+        let output = transact(caller, callee, 0, b"hello", None, 0).unwrap();
+        assert_eq!(output, caller.0.to_vec());
+        assert_eq!(stored_input(), Some(b"hello".to_vec()));
+
+        // The panic is reported, and what the entrypoint stored before it is rolled back.
+        match transact(caller, callee, 0, b"panic", None, 0) {
+            Err(RpcError::Execution(output)) => assert_eq!(output, b"oops"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(stored_input(), Some(b"hello".to_vec()));
+
+        match transact(caller, callee, 0, &[], None, 0) {
+            Err(RpcError::InvalidInput(Some(7))) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let caller = create_account(0);
+        let service = create_account(0);
+        MEMCHAIN.with(|memchain| {
+            let mut memchain = memchain.borrow_mut();
+            let block = memchain.blocks.last_mut().unwrap();
+            block.state.get_mut(&service).unwrap().to_mut().main = Some(timestamp_main);
+        });
+
+        let timestamp = transact(caller, service, 0, &[], None, 0).unwrap();
+        assert_eq!(timestamp, TestClock::now().to_le_bytes());
+
+        TestClock::set(1_600_000_000);
+        let timestamp = transact(caller, service, 0, &[], None, 0).unwrap();
+        assert_eq!(timestamp, 1_600_000_000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_account_from_seed() {
+        let getafix = account_from_seed("getafix", 100);
+        let caesar = account_from_seed("caesar", 200);
+        assert_ne!(getafix, caesar);
+        assert_eq!(balance(&getafix), Some(100));
+        assert_eq!(balance(&caesar), Some(200));
+
+        assert_eq!(account_from_seed("getafix", 300), getafix);
+        assert_eq!(balance(&getafix), Some(100));
+
+        // The address depends only on the seed, so it is the same in every run.
+        assert_eq!(getafix.0, tiny_keccak::keccak256(b"getafix")[12..]);
+    }
+
+    #[test]
+    fn test_last_receipt_aborted() {
+        let caller = create_account(0);
+        let callee = create_account(0);
+        MEMCHAIN.with(|memchain| {
+            let mut memchain = memchain.borrow_mut();
+            let block = memchain.blocks.last_mut().unwrap();
+            block.state.get_mut(&callee).unwrap().to_mut().main = Some(abort_main);
+        });
+
+        match transact(caller, callee, 0, b"input", None, 0) {
+            Err(RpcError::Execution(output)) => assert_eq!(output, b"aborted"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let receipt = last_receipt();
+        assert!(receipt.reverted());
+        assert_eq!(receipt.outcome(), TransactionOutcome::Aborted);
+        assert_eq!(receipt.caller(), &caller);
+        assert_eq!(receipt.callee(), &callee);
+        assert_eq!(receipt.output(), b"aborted");
+    }
+
+    #[test]
+    fn test_last_receipt_none() {
+        // A new thread has its own test chain, on which no transaction has been executed.
+        let panic = std::thread::spawn(last_receipt).join().unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().unwrap(),
+            "`last_receipt` called before any transaction was executed"
+        );
+    }
+}
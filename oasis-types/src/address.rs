@@ -31,9 +31,40 @@ impl Address {
         std::path::PathBuf::from(hex::encode(self))
     }
 
-    /// Alias for `Address::default()`.
-    pub fn zero() -> Self {
-        Self::default()
+    /// Returns the all-zero address. Equivalent to `Address::default()`,
+    /// but usable in const contexts.
+    pub const fn zero() -> Self {
+        Self([0u8; 20])
+    }
+
+    /// Returns whether this is the all-zero address.
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    /// Encodes this `Address` as a bech32 string with the human-readable part `hrp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hrp` is not a valid bech32 human-readable part.
+    #[cfg(feature = "bech32")]
+    pub fn to_bech32(&self, hrp: &str) -> String {
+        use bech32::ToBase32 as _;
+        bech32::encode(hrp, self.0.to_base32()).expect("invalid bech32 human-readable part")
+    }
+
+    /// Decodes a bech32 string into its human-readable part and `Address`.
+    #[cfg(feature = "bech32")]
+    pub fn from_bech32(s: &str) -> Result<(String, Self), bech32::Error> {
+        use bech32::FromBase32 as _;
+        let (hrp, data) = bech32::decode(s)?;
+        let bytes = Vec::<u8>::from_base32(&data)?;
+        if bytes.len() != Self::size() {
+            return Err(bech32::Error::InvalidLength);
+        }
+        let mut addr = Self::default();
+        addr.0.copy_from_slice(&bytes);
+        Ok((hrp, addr))
     }
 }
 
@@ -114,6 +145,69 @@ mod tests {
         ]);
         assert_eq!(unsafe { Address::from_raw(addr.as_ptr()) }, addr);
     }
+
+    #[test]
+    fn zero() {
+        const ZERO: Address = Address::zero();
+        assert_eq!(ZERO, Address::default());
+        assert!(ZERO.is_zero());
+        assert!(!Address([1u8; 20]).is_zero());
+
+        let mut addr = Address::zero();
+        addr.0[19] = 1;
+        assert!(!addr.is_zero());
+    }
+}
+
+#[cfg(all(test, feature = "bech32"))]
+mod bech32_tests {
+    use super::*;
+
+    fn addr() -> Address {
+        let mut addr = Address::zero();
+        for (i, b) in addr.0.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        addr
+    }
+
+    #[test]
+    fn roundtrip_bech32() {
+        let addr_str = "cosmos1qqqsyqcyq5rqwzqfpg9scrgwpugpzysnrk363e";
+        assert_eq!(addr().to_bech32("cosmos"), addr_str);
+        assert_eq!(
+            Address::from_bech32(addr_str).unwrap(),
+            ("cosmos".to_string(), addr())
+        );
+        assert_eq!(
+            Address::from_bech32(&addr().to_bech32("oasis")).unwrap(),
+            ("oasis".to_string(), addr())
+        );
+    }
+
+    #[test]
+    fn bad_checksum_bech32() {
+        let addr_str = "cosmos1qqqsyqcyq5rqwzqfpg9scrgwpugpzysnrk363f";
+        assert_eq!(
+            Address::from_bech32(addr_str),
+            Err(bech32::Error::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn bad_length_bech32() {
+        use bech32::ToBase32 as _;
+        let short = bech32::encode("cosmos", [1u8; 19].to_base32()).unwrap();
+        assert_eq!(
+            Address::from_bech32(&short),
+            Err(bech32::Error::InvalidLength)
+        );
+        let long = bech32::encode("cosmos", [1u8; 21].to_base32()).unwrap();
+        assert_eq!(
+            Address::from_bech32(&long),
+            Err(bech32::Error::InvalidLength)
+        );
+    }
 }
 
 #[cfg(all(test, feature = "serde"))]
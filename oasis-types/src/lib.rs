@@ -34,6 +34,9 @@ impl ExtStatusCode {
 pub struct AccountMeta {
     pub balance: u128,
     pub expiry: Option<std::time::Duration>,
+
+    /// The number of bytes occupied by the keys and values in the account's storage.
+    pub storage_size: usize,
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
@@ -58,8 +61,10 @@ pub enum RpcError {
     #[error("not enough gas provided to transaction")]
     InsufficientGas,
 
+    /// The input could not be decoded. Contains the selector of the requested method,
+    /// if the input named one. @see the `METHODS` of the generated service.
     #[error("transaction received invalid input")]
-    InvalidInput,
+    InvalidInput(Option<u32>),
 
     #[error("transaction returned invalid output")]
     InvalidOutput(Vec<u8>),
@@ -93,9 +98,10 @@ const _IMPL_SERDE_FOR_RPC_ERROR: () = {
                     let variant_idx = 2u8;
                     writer.write_all(&variant_idx.to_le_bytes())?;
                 }
-                RpcError::InvalidInput => {
+                RpcError::InvalidInput(method) => {
                     let variant_idx = 3u8;
                     writer.write_all(&variant_idx.to_le_bytes())?;
+                    oasis_borsh::BorshSerialize::serialize(method, writer)?;
                 }
                 RpcError::InvalidOutput(output) => {
                     let variant_idx = 4u8;
@@ -129,7 +135,7 @@ const _IMPL_SERDE_FOR_RPC_ERROR: () = {
                 0u8 => RpcError::InvalidCallee,
                 1u8 => RpcError::InsufficientFunds,
                 2u8 => RpcError::InsufficientGas,
-                3u8 => RpcError::InvalidInput,
+                3u8 => RpcError::InvalidInput(oasis_borsh::BorshDeserialize::deserialize(reader)?),
                 4u8 => RpcError::InvalidOutput(oasis_borsh::BorshDeserialize::deserialize(reader)?),
                 5u8 => RpcError::Execution(oasis_borsh::BorshDeserialize::deserialize(reader)?),
                 #[cfg(not(target_os = "wasi"))]
@@ -150,10 +156,177 @@ const _IMPL_SERDE_FOR_RPC_ERROR: () = {
 };
 
 impl RpcError {
+    /// Returns a stable numeric code that identifies the kind of error for clients that
+    /// cannot decode an `RpcError`. The codes of errors that are also outcomes of a
+    /// transaction are the discriminants of the corresponding `TransactionOutcome`, so
+    /// `Execution` has the code of `TransactionOutcome::Aborted`. `0` is never an error.
+    pub fn code(&self) -> u16 {
+        match self {
+            RpcError::InsufficientFunds => 1,
+            RpcError::InsufficientGas => 2,
+            RpcError::InvalidInput(_) => 3,
+            RpcError::InvalidCallee => 4,
+            RpcError::Execution(_) => 5,
+            // 6 is `TransactionOutcome::Fatal`, which is not reported as an `RpcError`.
+            RpcError::InvalidOutput(_) => 7,
+            #[cfg(not(target_os = "wasi"))]
+            RpcError::Gateway(_) => 8,
+        }
+    }
+
+    /// Returns the error identified by `code`, or `None` if the code is unknown.
+    /// The `payload` is the output of an `InvalidOutput` or `Execution` error, the UTF-8
+    /// message of a `Gateway` error, or the little-endian method selector of an
+    /// `InvalidInput` error, and is ignored by the other errors.
+    pub fn from_code(code: u16, payload: Option<Vec<u8>>) -> Option<Self> {
+        let payload = payload.unwrap_or_default();
+        Some(match code {
+            1 => RpcError::InsufficientFunds,
+            2 => RpcError::InsufficientGas,
+            3 => RpcError::invalid_input(&payload),
+            4 => RpcError::InvalidCallee,
+            5 => RpcError::Execution(payload),
+            7 => RpcError::InvalidOutput(payload),
+            #[cfg(not(target_os = "wasi"))]
+            8 => RpcError::Gateway(anyhow::anyhow!(
+                String::from_utf8_lossy(&payload).into_owned()
+            )),
+            _ => return None,
+        })
+    }
+
+    /// Returns the `InvalidInput` error reported by a service that could not decode its
+    /// input. The service's error output is the little-endian selector of the requested
+    /// method, or empty if the input did not name one.
+    pub fn invalid_input(err_output: &[u8]) -> Self {
+        RpcError::InvalidInput(
+            std::convert::TryInto::try_into(err_output)
+                .ok()
+                .map(u32::from_le_bytes),
+        )
+    }
+
     pub fn execution(&self) -> Option<&[u8]> {
         match self {
             RpcError::Execution(output) => Some(&output),
             _ => None,
         }
     }
+
+    /// Decodes the output of a failed service call into the service's error type.
+    /// Returns `Err(self)` if this is not an `Execution` error or if its output
+    /// is not a valid encoding of `E`.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn into_typed<E: oasis_borsh::BorshDeserialize>(self) -> Result<E, RpcError> {
+        match self {
+            RpcError::Execution(output) => {
+                E::try_from_slice(&output).map_err(|_| RpcError::Execution(output))
+            }
+            err => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_borsh::{BorshDeserialize as _, BorshSerialize as _};
+
+    #[test]
+    fn test_rpc_error_roundtrip() {
+        let errs = vec![
+            RpcError::InvalidCallee,
+            RpcError::InsufficientFunds,
+            RpcError::InsufficientGas,
+            RpcError::InvalidInput(None),
+            RpcError::InvalidInput(Some(2)),
+            RpcError::InvalidOutput(vec![1, 2, 3]),
+            RpcError::Execution(b"oops".to_vec()),
+        ];
+        for err in errs {
+            let encoded = err.try_to_vec().unwrap();
+            let decoded = RpcError::try_from_slice(&encoded).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", err));
+        }
+    }
+
+    #[test]
+    fn test_rpc_error_invalid_input() {
+        // This is the error output of a service that receives a malformed input.
+        match RpcError::invalid_input(&[2, 0, 0, 0]) {
+            RpcError::InvalidInput(Some(2)) => (),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match RpcError::invalid_input(&[]) {
+            RpcError::InvalidInput(None) => (),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_rpc_error_code_roundtrip() {
+        let errs = vec![
+            (RpcError::InsufficientFunds, 1, None),
+            (RpcError::InsufficientGas, 2, None),
+            (RpcError::InvalidInput(None), 3, None),
+            (RpcError::InvalidInput(Some(2)), 3, Some(vec![2, 0, 0, 0])),
+            (RpcError::InvalidCallee, 4, None),
+            (
+                RpcError::Execution(b"oops".to_vec()),
+                5,
+                Some(b"oops".to_vec()),
+            ),
+            (
+                RpcError::InvalidOutput(vec![1, 2, 3]),
+                7,
+                Some(vec![1, 2, 3]),
+            ),
+            (
+                RpcError::Gateway(anyhow::anyhow!("timed out")),
+                8,
+                Some(b"timed out".to_vec()),
+            ),
+        ];
+        for (err, code, payload) in errs {
+            assert_eq!(err.code(), code);
+            let decoded = RpcError::from_code(code, payload).unwrap();
+            match (&decoded, &err) {
+                // The `Debug` of an `anyhow::Error` includes its backtrace, if captured.
+                (RpcError::Gateway(decoded), RpcError::Gateway(err)) => {
+                    assert_eq!(decoded.to_string(), err.to_string())
+                }
+                _ => assert_eq!(format!("{:?}", decoded), format!("{:?}", err)),
+            }
+        }
+
+        assert!(RpcError::from_code(0, None).is_none());
+        assert!(RpcError::from_code(6, None).is_none());
+        assert!(RpcError::from_code(9, None).is_none());
+        match RpcError::from_code(5, None) {
+            Some(RpcError::Execution(output)) => assert!(output.is_empty()),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_rpc_error_into_typed() {
+        let output = (7u32, "oops".to_string()).try_to_vec().unwrap();
+        assert_eq!(
+            RpcError::Execution(output)
+                .into_typed::<(u32, String)>()
+                .unwrap(),
+            (7, "oops".to_string())
+        );
+
+        match RpcError::Execution(vec![1]).into_typed::<(u32, String)>() {
+            Err(RpcError::Execution(output)) => assert_eq!(output, vec![1]),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match RpcError::InsufficientGas.into_typed::<u32>() {
+            Err(RpcError::InsufficientGas) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
 }
@@ -0,0 +1,124 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum TallyError {
+    Closed,
+    UnknownCandidate { index: u32 },
+}
+
+pub type Fallible<T> = Result<T, String>;
+pub type TallyResult<T> = std::result::Result<T, TallyError>;
+
+#[derive(Service)]
+pub struct AliasedResultService {
+    tally: Vec<u32>,
+    open: bool,
+}
+
+impl AliasedResultService {
+    pub fn new(_ctx: &Context, num_candidates: u32) -> Fallible<Self> {
+        if num_candidates == 0 {
+            return Err("there must be at least one candidate".to_string());
+        }
+        Ok(Self {
+            tally: vec![0; num_candidates as usize],
+            open: true,
+        })
+    }
+
+    pub fn vote(&mut self, _ctx: &Context, candidate: u32) -> TallyResult<u32> {
+        if !self.open {
+            return Err(TallyError::Closed);
+        }
+        match self.tally.get_mut(candidate as usize) {
+            Some(votes) => {
+                *votes += 1;
+                Ok(*votes)
+            }
+            None => Err(TallyError::UnknownCandidate { index: candidate }),
+        }
+    }
+
+    pub fn winner(&self, _ctx: &Context) -> Fallible<u32> {
+        if self.open {
+            return Err("voting is still open".to_string());
+        }
+        Ok((0..self.tally.len() as u32)
+            .max_by_key(|&i| self.tally[i as usize])
+            .unwrap())
+    }
+
+    pub fn tally(&self, _ctx: &Context) -> Vec<u32> {
+        self.tally.clone()
+    }
+}
+
+fn main() {
+    oasis_std::service!(AliasedResultService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::{EncodeOutput as _, EncodeResultOutput as _, RpcError};
+
+    #[test]
+    fn test_aliased_results_encode_errors() {
+        let ctx = Context::default();
+        let mut service = AliasedResultService::new(&ctx, 2).unwrap();
+
+        assert_eq!(
+            (&service.vote(&ctx, 1)).encode_output(),
+            Ok(1u32.try_to_vec().unwrap())
+        );
+        assert_eq!(
+            (&service.vote(&ctx, 2)).encode_output(),
+            Err(TallyError::UnknownCandidate { index: 2 }
+                .try_to_vec()
+                .unwrap())
+        );
+        assert_eq!(
+            (&service.winner(&ctx)).encode_output(),
+            Err("voting is still open".to_string().try_to_vec().unwrap())
+        );
+        assert_eq!(
+            (&service.tally(&ctx)).encode_output(),
+            Ok(vec![0u32, 1].try_to_vec().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_dispatch_arbitrary_payloads() {
+        let ctx = Context::default();
+        let mut service = AliasedResultService::new(&ctx, 3).unwrap();
+
+        // A xorshift PRNG so that failures are reproducible.
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next_byte = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        };
+
+        for len in (0..2000).map(|i| i % 12) {
+            let mut payload: Vec<u8> = (0..len).map(|_| next_byte()).collect();
+            if let Some(rpc_idx) = payload.first_mut() {
+                *rpc_idx %= 4; // Mostly target existing RPCs.
+            }
+            let output = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                AliasedResultService::__dispatch_raw(&mut service, &ctx, &payload)
+            }));
+            match output {
+                Ok(Ok(_))
+                | Ok(Err(RpcError::InvalidInput(_)))
+                | Ok(Err(RpcError::Execution(_))) => (),
+                Ok(Err(err)) => panic!("unexpected error for {:?}: {:?}", payload, err),
+                Err(_) => panic!("dispatching {:?} panicked", payload),
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Round {
+    winner: u8,
+    score: u32,
+}
+
+#[derive(Service)]
+pub struct BracketService {
+    rounds: [Round; 8],
+}
+
+impl BracketService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            rounds: Default::default(),
+        }
+    }
+
+    pub fn set_round(&mut self, _ctx: &Context, index: u8, round: Round) {
+        if let Some(r) = self.rounds.get_mut(index as usize) {
+            *r = round;
+        }
+    }
+
+    pub fn rounds(&self, _ctx: &Context) -> [Round; 8] {
+        self.rounds
+    }
+}
+
+fn main() {
+    oasis_std::service!(BracketService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fixed-size array of defined structs is persisted along with the rest of the state.
+    #[test]
+    fn test_persist_rounds() {
+        let ctx = Context::default();
+        let mut service = BracketService::new(&ctx);
+        let round = Round {
+            winner: 2,
+            score: 21,
+        };
+        service.set_round(&ctx, 3, round);
+        Service::sunder(service);
+
+        let service = BracketService::coalesce();
+        assert_eq!(service.rounds[3], round);
+        assert_eq!(service.rounds[0], Round::default());
+        assert_eq!(service.rounds(&ctx).len(), 8);
+    }
+}
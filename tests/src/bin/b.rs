@@ -39,6 +39,10 @@ impl ServiceB {
     pub fn random(&self, _ctx: &Context, count: Number) -> Vec<Number> {
         vec![Number(4); count.0 as usize]
     }
+
+    pub fn total_value(&self, _ctx: &Context) -> u64 {
+        u64::from(self.seed.0) * 100
+    }
 }
 
 fn main() {
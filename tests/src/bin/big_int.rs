@@ -0,0 +1,104 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, Address, Context, Service};
+
+#[derive(Service)]
+pub struct VaultService {
+    balance: U256,
+    pnl: I256,
+}
+
+impl VaultService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            balance: U256::zero(),
+            pnl: I256::zero(),
+        }
+    }
+
+    pub fn deposit(&mut self, _ctx: &Context, amount: U256) -> Result<U256, String> {
+        self.balance = self
+            .balance
+            .checked_add(*amount)
+            .ok_or_else(|| "balance overflow".to_string())?
+            .into();
+        Ok(self.balance)
+    }
+
+    pub fn balance(&self, _ctx: &Context) -> U256 {
+        self.balance
+    }
+
+    pub fn record_pnl(&mut self, _ctx: &Context, delta: I256) -> Result<I256, String> {
+        self.pnl = self
+            .pnl
+            .checked_add(delta)
+            .ok_or_else(|| "pnl overflow".to_string())?;
+        Ok(self.pnl)
+    }
+}
+
+fn main() {
+    oasis_std::service!(VaultService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A balance above `u128::MAX` survives being persisted.
+    #[test]
+    fn test_persist_balance() {
+        let mut vault = VaultService {
+            balance: U256::from(u128::max_value()),
+            pnl: I256::zero(),
+        };
+        let balance = vault.deposit(&Context::default(), U256::from(1u8)).unwrap();
+        assert_eq!(balance.low_u128(), 0);
+        assert_eq!((*balance >> 128).low_u32(), 1);
+
+        Service::sunder(vault);
+        let vault = VaultService::coalesce();
+        assert_eq!(vault.balance, balance);
+    }
+
+    #[test]
+    fn test_deposit_overflow() {
+        let mut vault = VaultService {
+            balance: U256::max_value(),
+            pnl: I256::zero(),
+        };
+        assert!(vault.deposit(&Context::default(), U256::from(1u8)).is_err());
+        assert_eq!(vault.balance, U256::max_value());
+    }
+
+    /// A negative pnl below `i128::MIN` survives being persisted.
+    #[test]
+    fn test_persist_pnl() {
+        let mut vault = VaultService {
+            balance: U256::zero(),
+            pnl: I256::from(i128::min_value()),
+        };
+        let pnl = vault
+            .record_pnl(&Context::default(), I256::from(-1i8))
+            .unwrap();
+        assert!(pnl < I256::from(i128::min_value()));
+        assert_eq!(pnl.unsigned_abs(), U256::from(u128::max_value() / 2 + 2));
+
+        Service::sunder(vault);
+        let vault = VaultService::coalesce();
+        assert_eq!(vault.pnl, pnl);
+    }
+
+    #[test]
+    fn test_pnl_overflow() {
+        let mut vault = VaultService {
+            balance: U256::zero(),
+            pnl: I256::min_value(),
+        };
+        assert!(vault
+            .record_pnl(&Context::default(), I256::from(-1i8))
+            .is_err());
+        assert_eq!(vault.pnl, I256::min_value());
+    }
+}
@@ -0,0 +1,86 @@
+use oasis_std::{Context, Service};
+
+#[derive(Service)]
+pub struct BorrowedService {
+    name: String,
+    tags: Vec<String>,
+    data: Vec<u8>,
+}
+
+impl BorrowedService {
+    pub fn new(_ctx: &Context, name: String, tags: Vec<String>, data: Vec<u8>) -> Self {
+        Self { name, tags, data }
+    }
+
+    pub fn name(&self, _ctx: &Context) -> &str {
+        &self.name
+    }
+
+    pub fn tags(&self, _ctx: &Context) -> Vec<&str> {
+        self.tags.iter().map(String::as_str).collect()
+    }
+
+    pub fn data(&self, _ctx: &Context) -> Result<&[u8], String> {
+        Ok(&self.data)
+    }
+
+    pub fn tag(&self, _ctx: &Context, index: u32) -> Option<&str> {
+        self.tags.get(index as usize).map(String::as_str)
+    }
+}
+
+fn main() {
+    oasis_std::service!(BorrowedService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::abi::*;
+
+    /// The dispatcher encodes outputs using `Serialize::try_to_vec(&output)`,
+    /// so borrowed outputs must encode identically to their owned counterparts.
+    #[test]
+    fn test_borrowed_output_encoding() {
+        let ctx = Context::default();
+        let name = "borrowed".to_string();
+        let tags = vec!["a".to_string(), "bc".to_string()];
+        let data = vec![1u8, 2, 3];
+        let service = BorrowedService::new(&ctx, name.clone(), tags.clone(), data.clone());
+
+        assert_eq!(
+            Serialize::try_to_vec(&service.name(&ctx)).unwrap(),
+            name.try_to_vec().unwrap()
+        );
+        assert_eq!(
+            Serialize::try_to_vec(&service.tags(&ctx)).unwrap(),
+            tags.try_to_vec().unwrap()
+        );
+        assert_eq!(
+            Serialize::try_to_vec(&service.data(&ctx).unwrap()).unwrap(),
+            data.try_to_vec().unwrap()
+        );
+    }
+    /// Optional borrowed outputs go through the generated dispatcher and decode
+    /// as their owned counterparts.
+    #[test]
+    fn test_dispatch_optional_borrowed_output() {
+        let ctx = Context::default();
+        let tags = vec!["a".to_string(), "bc".to_string()];
+        let mut service = BorrowedService::new(&ctx, String::new(), tags, Vec::new());
+
+        let tag = |service: &mut BorrowedService, index: u32| {
+            let mut payload = vec![3u8];
+            payload.extend(index.try_to_vec().unwrap());
+            let output = BorrowedService::__dispatch_raw(service, &ctx, &payload).unwrap();
+            assert_eq!(
+                output,
+                Serialize::try_to_vec(&service.tag(&ctx, index)).unwrap()
+            );
+            Option::<String>::try_from_slice(&output).unwrap()
+        };
+        assert_eq!(tag(&mut service, 1), Some("bc".to_string()));
+        assert_eq!(tag(&mut service, 2), None);
+    }
+}
@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use oasis_std::{Address, Context, Service};
+
+#[derive(Service)]
+pub struct BorrowedArgsService {
+    owner: String,
+    entries: Vec<(String, Vec<u8>)>,
+    members: Vec<Address>,
+}
+
+impl BorrowedArgsService {
+    pub fn new(_ctx: &Context, owner: Cow<str>) -> Self {
+        Self {
+            owner: owner.into_owned(),
+            entries: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    pub fn put(&mut self, _ctx: &Context, key: &str, value: Cow<[u8]>) {
+        self.entries.push((key.to_string(), value.into_owned()));
+    }
+
+    pub fn get(&self, _ctx: &Context, key: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    pub fn owner(&self, _ctx: &Context) -> &str {
+        &self.owner
+    }
+
+    pub fn add_members(&mut self, _ctx: &Context, members: &[Address]) -> u32 {
+        self.members.extend_from_slice(members);
+        self.members.len() as u32
+    }
+}
+
+fn main() {
+    oasis_std::service!(BorrowedArgsService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::abi::*;
+
+    /// The dispatcher decodes borrowed args into their owned types and passes
+    /// a borrow (or `Cow::Owned`) to the RPC.
+    #[test]
+    fn test_dispatch_borrowed_args() {
+        let ctx = Context::default();
+        let mut service = BorrowedArgsService::new(&ctx, Cow::Borrowed("alice"));
+
+        let mut put_payload = vec![0u8];
+        put_payload.extend("key".to_string().try_to_vec().unwrap());
+        put_payload.extend(vec![1u8, 2, 3].try_to_vec().unwrap());
+        assert_eq!(
+            BorrowedArgsService::__dispatch_raw(&mut service, &ctx, &put_payload).unwrap(),
+            Vec::<u8>::new()
+        );
+
+        let mut get_payload = vec![1u8];
+        get_payload.extend("key".to_string().try_to_vec().unwrap());
+        assert_eq!(
+            BorrowedArgsService::__dispatch_raw(&mut service, &ctx, &get_payload).unwrap(),
+            Some(vec![1u8, 2, 3]).try_to_vec().unwrap()
+        );
+
+        assert_eq!(
+            BorrowedArgsService::__dispatch_raw(&mut service, &ctx, &[2u8]).unwrap(),
+            "alice".to_string().try_to_vec().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dispatch_slice_arg() {
+        let ctx = Context::default();
+        let mut service = BorrowedArgsService::new(&ctx, Cow::Borrowed("alice"));
+
+        let members = vec![Address([1u8; 20]), Address([2u8; 20])];
+        let mut payload = vec![3u8];
+        payload.extend(members.try_to_vec().unwrap());
+        assert_eq!(
+            BorrowedArgsService::__dispatch_raw(&mut service, &ctx, &payload).unwrap(),
+            2u32.try_to_vec().unwrap()
+        );
+        assert_eq!(
+            BorrowedArgsService::__dispatch_raw(&mut service, &ctx, &payload).unwrap(),
+            4u32.try_to_vec().unwrap()
+        );
+        assert_eq!(&service.members[2..], members.as_slice());
+    }
+}
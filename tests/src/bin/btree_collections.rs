@@ -0,0 +1,34 @@
+#![allow(unused)]
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Registry {
+    entries: BTreeMap<String, u32>,
+    owners: BTreeSet<String>,
+}
+
+#[derive(Service)]
+pub struct CollectionsService {
+    registry: Registry,
+}
+
+impl CollectionsService {
+    pub fn new(_ctx: &Context, entries: BTreeMap<String, u32>) -> Self {
+        unimplemented!()
+    }
+
+    pub fn lookup(&self, _ctx: &Context, keys: BTreeSet<String>) -> BTreeMap<String, u32> {
+        unimplemented!()
+    }
+
+    pub fn registry(&self, _ctx: &Context) -> Registry {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    oasis_std::service!(CollectionsService);
+}
@@ -0,0 +1,112 @@
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum CtorError {
+    InvalidSeed,
+    SeedTooLarge { max: u32 },
+}
+
+#[derive(Service)]
+pub struct CtorErrorService {
+    seed: u32,
+}
+
+impl CtorErrorService {
+    pub fn new(_ctx: &Context, seed: u32) -> Result<Self, CtorError> {
+        match seed {
+            0 => Err(CtorError::InvalidSeed),
+            seed if seed > 42 => Err(CtorError::SeedTooLarge { max: 42 }),
+            seed => Ok(Self { seed }),
+        }
+    }
+}
+
+fn main() {
+    oasis_std::service!(CtorErrorService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::borrow::Cow;
+
+    use blockchain_traits::{Block as _, Blockchain as _};
+    use memchain::{Account, Memchain, PtxPtr};
+    use oasis_client::MemchainGateway;
+    use oasis_std::Address;
+
+    extern "C" fn ctor_error_deploy(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, || {
+            CtorErrorService::__deploy_raw().map(|()| Vec::new())
+        })
+    }
+
+    /// The service has no RPCs, so it is never called.
+    extern "C" fn ctor_error_main(_ptx: PtxPtr) -> u16 {
+        unreachable!()
+    }
+
+    fn ctor_error_gateway() -> MemchainGateway<'static> {
+        let sender = Address([1u8; 20]);
+        let mut genesis_state = std::collections::HashMap::new();
+        genesis_state.insert(sender, Cow::Owned(Account::default()));
+        let memchain = Memchain::new("testnet", genesis_state, 0u64 /* base gas */);
+        let mut gateway = MemchainGateway::new(memchain, sender);
+        let wasm_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../target/wasm32-wasi/release/ctor_error.wasm"
+        );
+        let bytecode = std::fs::read(wasm_path).unwrap();
+        gateway.register(&bytecode, ctor_error_deploy, ctor_error_main);
+        gateway
+    }
+
+    #[test]
+    fn test_deploy() {
+        let gateway = ctor_error_gateway();
+        let ctx = Context::default();
+
+        let client = ctor_error::CtorErrorServiceClient::deploy(&gateway, &ctx, 7)
+            .unwrap()
+            .unwrap();
+        let memchain = gateway.memchain();
+        let state = memchain.last_block().state_at(&client.address()).unwrap();
+        assert_eq!(
+            u32::try_from_slice(&state.get(b"seed").unwrap()).unwrap(),
+            7
+        );
+        drop(memchain);
+
+        // The constructor's error is returned as the error type declared in the IDL.
+        match ctor_error::CtorErrorServiceClient::deploy(&gateway, &ctx, 1000) {
+            Ok(Err(ctor_error::CtorError::SeedTooLarge { max })) => assert_eq!(max, 42),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+        match ctor_error::CtorErrorServiceClient::deploy(&gateway, &ctx, 0) {
+            Ok(Err(ctor_error::CtorError::InvalidSeed)) => (),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_ctor_error_roundtrip() {
+        let ctx = Context::default();
+        let errors = vec![
+            (0, CtorError::InvalidSeed),
+            (43, CtorError::SeedTooLarge { max: 42 }),
+        ];
+        for (seed, error) in errors {
+            let err_output = oasis_std::CtorOutput::<CtorErrorService>::into_ctor_result(
+                CtorErrorService::new(&ctx, seed),
+            )
+            .err()
+            .unwrap();
+            assert_eq!(CtorError::try_from_slice(&err_output).unwrap(), error);
+
+            // The output also decodes as the `CtorError` of the service's IDL.
+            let idl_error = ctor_error::CtorError::try_from_slice(&err_output).unwrap();
+            assert_eq!(format!("{:?}", idl_error), format!("{:?}", error));
+        }
+    }
+}
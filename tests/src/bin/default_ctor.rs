@@ -0,0 +1,21 @@
+use oasis_std::{Context, Service};
+
+#[derive(Service, Default)]
+pub struct DefaultCtorService {
+    count: u32,
+}
+
+impl DefaultCtorService {
+    pub fn count(&self, _ctx: &Context) -> u32 {
+        self.count
+    }
+
+    pub fn increment(&mut self, _ctx: &Context) -> u32 {
+        self.count += 1;
+        self.count
+    }
+}
+
+fn main() {
+    oasis_std::service!(DefaultCtorService);
+}
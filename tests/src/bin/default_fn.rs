@@ -0,0 +1,26 @@
+use oasis_std::{default, Context, Service};
+
+#[derive(Service)]
+pub struct DefaultFnService {
+    count: u32,
+}
+
+impl DefaultFnService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self { count: 0 }
+    }
+
+    pub fn count(&self, _ctx: &Context) -> u32 {
+        self.count
+    }
+
+    #[default]
+    pub fn increment(&mut self, _ctx: &Context) -> Result<u32, String> {
+        self.count += 1;
+        Ok(self.count)
+    }
+}
+
+fn main() {
+    oasis_std::service!(DefaultFnService);
+}
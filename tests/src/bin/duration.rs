@@ -0,0 +1,63 @@
+#![allow(unused)]
+
+use std::time::Duration;
+
+use oasis_std::{abi::*, Address, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Lease {
+    holder: Address,
+    term: Duration,
+    grace_period: Option<Duration>,
+}
+
+#[derive(Service)]
+pub struct LeaseService {
+    leases: Vec<Lease>,
+}
+
+impl LeaseService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self { leases: Vec::new() }
+    }
+
+    pub fn lease(&mut self, ctx: &Context, term: Duration) -> Lease {
+        let lease = Lease {
+            holder: ctx.sender(),
+            term,
+            grace_period: None,
+        };
+        self.leases.push(lease.clone());
+        lease
+    }
+
+    pub fn total_term(&self, _ctx: &Context) -> Duration {
+        self.leases.iter().map(|lease| lease.term).sum()
+    }
+}
+
+fn main() {
+    oasis_std::service!(LeaseService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_round_trip() {
+        let term = Duration::new(3, 141_592_653);
+        assert_eq!(
+            term.try_to_vec().unwrap(),
+            3_141_592_653u128.to_le_bytes().to_vec()
+        );
+
+        let lease = Lease {
+            holder: Address([1u8; 20]),
+            term,
+            grace_period: Some(Duration::from_secs(60)),
+        };
+        let encoded = lease.try_to_vec().unwrap();
+        assert_eq!(Lease::try_from_slice(&encoded).unwrap(), lease);
+    }
+}
@@ -0,0 +1,28 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, Address, Context, Service};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Error {
+    Unknown,
+    InsufficientFunds { address: Address },
+    RequestExceedsAllowance { amount: u64, allowance: u64 },
+    Rejected(String, Option<u32>),
+}
+
+#[derive(Service)]
+pub struct EnumErrorService;
+
+impl EnumErrorService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self
+    }
+
+    pub fn withdraw(&mut self, _ctx: &Context, amount: u64) -> Result<u64, Error> {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    oasis_std::service!(EnumErrorService);
+}
@@ -0,0 +1,114 @@
+use oasis_std::{abi::*, Address, Context, Event, Service};
+
+#[derive(Serialize, Deserialize, Event)]
+pub struct Transferred {
+    #[indexed]
+    from: Address,
+    amount: u64,
+    #[indexed]
+    to: Address,
+    memo: String,
+}
+
+#[derive(Service)]
+pub struct EventService;
+
+impl EventService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self
+    }
+
+    pub fn transfer(&mut self, ctx: &Context, to: Address, amount: u64, memo: String) {
+        Event::emit(&Transferred {
+            from: ctx.sender(),
+            amount,
+            to,
+            memo,
+        });
+    }
+}
+
+fn main() {
+    oasis_std::service!(EventService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `topics[0]` of an ERC20 `Transfer(address,address,uint256)` event.
+    const ERC20_TRANSFER_TOPIC: [u8; 32] = [
+        0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d,
+        0xaa, 0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23,
+        0xb3, 0xef,
+    ];
+
+    #[derive(Serialize, Deserialize, Event)]
+    #[event(topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")]
+    pub struct Transfer {
+        #[indexed]
+        from: Address,
+        #[indexed]
+        to: Address,
+        value: u64,
+    }
+
+    #[test]
+    fn test_override_topic0() {
+        oasis_test::take_events();
+
+        let transfer = Transfer {
+            from: Address([1u8; 20]),
+            to: Address([2u8; 20]),
+            value: 3,
+        };
+        Event::emit(&transfer);
+
+        let events = oasis_test::take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].topics[0], ERC20_TRANSFER_TOPIC);
+        assert_eq!(Transfer::topic_hash(), ERC20_TRANSFER_TOPIC);
+        assert_eq!(&events[0].topics[1..], transfer.indexed_topics().as_slice());
+        assert_eq!(events[0].data, transfer.try_to_vec().unwrap());
+    }
+
+    #[test]
+    fn test_emit_all() {
+        oasis_test::take_events();
+
+        let transfers: Vec<_> = (1..=3)
+            .map(|i| Transfer {
+                from: Address([0u8; 20]),
+                to: Address([i; 20]),
+                value: u64::from(i),
+            })
+            .collect();
+        Event::emit_all(&transfers);
+
+        let events = oasis_test::take_events();
+        assert_eq!(events.len(), transfers.len());
+        for (event, transfer) in events.iter().zip(transfers.iter()) {
+            assert_eq!(event.topics[0], ERC20_TRANSFER_TOPIC);
+            assert_eq!(&event.topics[1..], transfer.indexed_topics().as_slice());
+            assert_eq!(event.data, transfer.try_to_vec().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_computed_topic0() {
+        oasis_test::take_events();
+
+        Event::emit(&Transferred {
+            from: Address([1u8; 20]),
+            amount: 3,
+            to: Address([2u8; 20]),
+            memo: "memo".to_string(),
+        });
+
+        let events = oasis_test::take_events();
+        assert_eq!(
+            events[0].topics[0],
+            oasis_std::exe::encode_event_topic(&"Transferred")
+        );
+    }
+}
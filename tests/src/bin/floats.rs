@@ -0,0 +1,96 @@
+use oasis_std::{Context, Service};
+
+#[derive(Service)]
+pub struct ThermostatService {
+    target: f64,
+}
+
+impl ThermostatService {
+    pub fn new(_ctx: &Context, target: f64) -> Self {
+        Self { target }
+    }
+
+    /// Sets the target temperature and returns the previous one.
+    pub fn set_target(&mut self, _ctx: &Context, target: f64) -> f64 {
+        std::mem::replace(&mut self.target, target)
+    }
+
+    pub fn scaled_target(&self, _ctx: &Context, factor: f64) -> f64 {
+        self.target * factor
+    }
+
+    pub fn scaled_targets(&self, _ctx: &Context, factors: Vec<f64>) -> Vec<(f64, f64)> {
+        factors
+            .into_iter()
+            .map(|factor| (factor, self.target * factor))
+            .collect()
+    }
+}
+
+fn main() {
+    oasis_std::service!(ThermostatService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::{abi::*, RpcError};
+
+    fn payload(rpc_idx: u8, arg: f64) -> Vec<u8> {
+        let mut payload = vec![rpc_idx];
+        payload.extend(arg.to_bits().to_le_bytes().iter());
+        payload
+    }
+
+    #[test]
+    fn test_reject_nan() {
+        let ctx = Context::default();
+        let mut service = ThermostatService::new(&ctx, 20.0);
+
+        let output =
+            ThermostatService::__dispatch_raw(&mut service, &ctx, &payload(0, std::f64::NAN));
+        assert!(matches!(output, Err(RpcError::InvalidInput(Some(0)))));
+        assert_eq!(service.target, 20.0);
+
+        // `inf * 0` is NaN.
+        service.target = std::f64::INFINITY;
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload(1, 0.0));
+        assert!(matches!(output, Err(RpcError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_normalize_zero() {
+        let ctx = Context::default();
+        let mut service = ThermostatService::new(&ctx, 20.0);
+        let zero = 0f64.try_to_vec().unwrap();
+
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload(0, -0.0));
+        assert_eq!(output.unwrap(), 20f64.try_to_vec().unwrap());
+        assert!(service.target.is_sign_positive());
+
+        // `0 * -1` is `-0`.
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload(1, -1.0));
+        assert_eq!(output.unwrap(), zero);
+
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload(1, 2.0));
+        assert_eq!(output.unwrap(), zero);
+    }
+
+    #[test]
+    fn test_canonicalize_nested() {
+        let ctx = Context::default();
+        let mut service = ThermostatService::new(&ctx, 0.0);
+
+        let payload = oasis_std::abi_encode!(2u8, vec![1.0f64, std::f64::NAN]).unwrap();
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload);
+        assert!(matches!(output, Err(RpcError::InvalidInput(Some(2)))));
+
+        let payload = oasis_std::abi_encode!(2u8, vec![-0.0f64, -1.0]).unwrap();
+        let output = ThermostatService::__dispatch_raw(&mut service, &ctx, &payload);
+        assert_eq!(
+            output.unwrap(),
+            vec![(0f64, 0f64), (-1f64, 0f64)].try_to_vec().unwrap()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, Address, Context, Service};
+
+#[derive(Service)]
+pub struct LedgerService {
+    owner: Address,
+    total: u64,
+    entries: Vec<u64>,
+}
+
+impl LedgerService {
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            owner: ctx.sender(),
+            total: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn version(&self, _ctx: &Context) -> u32 {
+        1
+    }
+
+    pub fn total(&self, _ctx: &Context) -> u64 {
+        self.total
+    }
+
+    pub fn record(&mut self, ctx: &Context, amount: u64) -> Result<(), String> {
+        if ctx.sender() != self.owner {
+            return Err("only the owner may record entries".to_string());
+        }
+        self.entries.push(amount);
+        self.total += amount;
+        Ok(())
+    }
+
+    /// Recomputes the total and returns the number of entries larger than the old total.
+    pub fn audit(&mut self, _ctx: &Context) -> u64 {
+        let recomputed = self.entries.iter().sum::<u64>();
+        let num_oversized = self
+            .entries
+            .iter()
+            .filter(|&&entry| entry > self.total)
+            .count();
+        self.total = recomputed;
+        num_oversized as u64
+    }
+}
+
+fn main() {
+    oasis_std::service!(LedgerService);
+}
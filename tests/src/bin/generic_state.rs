@@ -0,0 +1,50 @@
+use oasis_std::{abi::*, Address, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Token {
+    symbol: String,
+}
+
+/// A reusable state component that can be instantiated with any currency.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Ledger<Currency: Serialize + Deserialize> {
+    currency: Currency,
+    balances: Vec<(Address, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    ledger: Ledger<Token>,
+    num_accounts: u32,
+}
+
+#[derive(Service)]
+pub struct GenericStateService {
+    ledger: Ledger<Token>,
+}
+
+impl GenericStateService {
+    pub fn new(_ctx: &Context, symbol: String) -> Self {
+        Self {
+            ledger: Ledger {
+                currency: Token { symbol },
+                balances: Vec::new(),
+            },
+        }
+    }
+
+    pub fn ledger(&self, _ctx: &Context) -> &Ledger<Token> {
+        &self.ledger
+    }
+
+    pub fn snapshot(&self, _ctx: &Context) -> Snapshot {
+        Snapshot {
+            ledger: self.ledger.clone(),
+            num_accounts: self.ledger.balances.len() as u32,
+        }
+    }
+}
+
+fn main() {
+    oasis_std::service!(GenericStateService);
+}
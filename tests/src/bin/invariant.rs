@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use oasis_std::{invariant, Address, Context, Service};
+
+#[derive(Service)]
+pub struct LedgerService {
+    balances: HashMap<Address, u64>,
+    total_supply: u64,
+}
+
+impl LedgerService {
+    pub fn new(ctx: &Context, total_supply: u64) -> Self {
+        let mut balances = HashMap::new();
+        balances.insert(ctx.sender(), total_supply);
+        Self {
+            balances,
+            total_supply,
+        }
+    }
+
+    pub fn balance_of(&self, _ctx: &Context, owner: Address) -> u64 {
+        self.balances.get(&owner).copied().unwrap_or_default()
+    }
+
+    /// Moves `amount` from the sender to `to`. An overdraft empties the sender's balance
+    /// but credits the full amount, which is the bug caught by `supply_is_conserved`.
+    pub fn transfer(&mut self, ctx: &Context, to: Address, amount: u64) {
+        let from_balance = self.balances.entry(ctx.sender()).or_default();
+        *from_balance = from_balance.saturating_sub(amount);
+        *self.balances.entry(to).or_default() += amount;
+    }
+
+    #[invariant]
+    pub fn supply_is_conserved(&self) -> bool {
+        self.balances.values().sum::<u64>() == self.total_supply
+    }
+}
+
+fn main() {
+    oasis_std::service!(LedgerService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::abi_encode;
+
+    const ALICE: Address = Address([1u8; 20]);
+    const BOB: Address = Address([2u8; 20]);
+
+    fn transfer(service: &mut LedgerService, amount: u64) {
+        let ctx = Context::default().with_sender(ALICE);
+        let payload = abi_encode!(1u8, BOB, amount).unwrap();
+        LedgerService::__dispatch_raw(service, &ctx, &payload).unwrap();
+    }
+
+    #[test]
+    fn test_invariant_holds() {
+        let mut service = LedgerService::new(&Context::default().with_sender(ALICE), 10);
+        transfer(&mut service, 4);
+        assert_eq!(service.balance_of(&Context::default(), ALICE), 6);
+        assert_eq!(service.balance_of(&Context::default(), BOB), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "service invariant `supply_is_conserved` does not hold")]
+    fn test_invariant_violated() {
+        let mut service = LedgerService::new(&Context::default().with_sender(ALICE), 10);
+        transfer(&mut service, 15);
+    }
+}
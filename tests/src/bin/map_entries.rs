@@ -0,0 +1,29 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, collections::Map, Address, Context, Service};
+
+#[derive(Service)]
+pub struct ScoreboardService {
+    scores: Map<Address, u64>,
+}
+
+impl ScoreboardService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self { scores: Map::new() }
+    }
+
+    pub fn score(&mut self, ctx: &Context, points: u64) {
+        *self.scores.entry(ctx.sender()).or_default() += points;
+    }
+
+    pub fn scores(&self, _ctx: &Context) -> Vec<(Address, u64)> {
+        self.scores
+            .iter()
+            .map(|(player, points)| (*player, *points))
+            .collect()
+    }
+}
+
+fn main() {
+    oasis_std::service!(ScoreboardService);
+}
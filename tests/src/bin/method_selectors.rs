@@ -0,0 +1,46 @@
+use oasis_std::{Context, Service};
+
+#[derive(Service)]
+pub struct NoticeBoardService {
+    notices: Vec<String>,
+}
+
+impl NoticeBoardService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            notices: Vec::new(),
+        }
+    }
+
+    pub fn notices(&self, _ctx: &Context) -> Vec<String> {
+        self.notices.clone()
+    }
+
+    pub fn post(&mut self, _ctx: &Context, notice: String) {
+        self.notices.push(notice);
+    }
+}
+
+fn main() {
+    oasis_std::service!(NoticeBoardService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_selectors() {
+        let ctx = Context::default();
+        let mut board = NoticeBoardService::new(&ctx);
+
+        let (_, post_selector) = NoticeBoardService::METHODS
+            .iter()
+            .find(|(name, _)| *name == "post")
+            .unwrap();
+        let payload = oasis_std::abi_encode!(*post_selector as u8, "selected").unwrap();
+        NoticeBoardService::__dispatch_raw(&mut board, &ctx, &payload).unwrap();
+
+        assert_eq!(board.notices(&ctx), vec!["selected".to_string()]);
+    }
+}
@@ -0,0 +1,103 @@
+use oasis_std::{Address, Context, Service};
+
+#[derive(Service)]
+pub struct BallotService {
+    num_proposals: u8,
+    delegations: Vec<(Address, Address)>,
+    votes: Vec<u8>,
+}
+
+impl BallotService {
+    pub fn new(_ctx: &Context, num_proposals: u8) -> Self {
+        Self {
+            num_proposals,
+            delegations: Vec::new(),
+            votes: Vec::new(),
+        }
+    }
+
+    pub fn delegate(&mut self, ctx: &Context, to_voter: Address) {
+        self.delegations.push((ctx.sender(), to_voter));
+    }
+
+    pub fn vote(&mut self, _ctx: &Context, mut proposal_index: u8, num_votes: u8) {
+        proposal_index %= self.num_proposals;
+        self.votes
+            .extend(std::iter::repeat(proposal_index).take(num_votes as usize));
+    }
+}
+
+fn main() {
+    oasis_std::service!(BallotService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::borrow::Cow;
+
+    use blockchain_traits::{Block as _, Blockchain as _};
+    use memchain::{Account, Memchain, PtxPtr};
+    use oasis_client::{CallOptions, Gateway as _, MemchainGateway};
+    use oasis_std::{abi::*, RpcError};
+
+    const BALLOT_BYTECODE: &[u8] = b"\0asm named_args";
+
+    extern "C" fn ballot_deploy(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, || BallotService::__deploy_raw().map(|()| Vec::new()))
+    }
+
+    extern "C" fn ballot_main(ptx: PtxPtr) -> u16 {
+        oasis_test::run_in_transaction(ptx, BallotService::__call_raw)
+    }
+
+    fn ballot_gateway(sender: Address) -> MemchainGateway<'static> {
+        let mut genesis_state = std::collections::HashMap::new();
+        genesis_state.insert(sender, Cow::Owned(Account::default()));
+        let memchain = Memchain::new("testnet", genesis_state, 0u64 /* base gas */);
+        let mut gateway = MemchainGateway::new(memchain, sender);
+        gateway.register(BALLOT_BYTECODE, ballot_deploy, ballot_main);
+        gateway
+    }
+
+    #[test]
+    fn test_memchain_gateway() {
+        let sender = Address([1u8; 20]);
+        let gateway = ballot_gateway(sender);
+
+        let mut initcode = BALLOT_BYTECODE.to_vec();
+        oasis_std::abi_encode!(3u8 => &mut initcode).unwrap();
+        let address = gateway.deploy(&initcode, CallOptions::default()).unwrap();
+        assert_eq!(gateway.code(address).unwrap(), BALLOT_BYTECODE);
+
+        let ctx = Context::default();
+        let mut client = named_args::BallotServiceClient::new(&gateway, address);
+        client.vote(&ctx, 4, 2).unwrap();
+        client.delegate(&ctx, Address([2u8; 20])).unwrap();
+
+        // Each field of the service is stored in its account under the field's name.
+        let stored_field = |name: &str| {
+            let memchain = gateway.memchain();
+            let state = memchain.last_block().state_at(&address).unwrap();
+            state.get(name.as_bytes()).unwrap()
+        };
+        let stored_votes = || Vec::<u8>::try_from_slice(&stored_field("votes")).unwrap();
+        assert_eq!(stored_votes(), vec![1, 1]); // the proposal index wraps around
+        assert_eq!(
+            Vec::<(Address, Address)>::try_from_slice(&stored_field("delegations")).unwrap(),
+            vec![(sender, Address([2u8; 20]))]
+        );
+
+        // A malformed RPC is rejected by the service without changing its state.
+        let (_, vote_selector) = BallotService::METHODS
+            .iter()
+            .find(|(name, _)| *name == "vote")
+            .unwrap();
+        match gateway.rpc(address, &[*vote_selector as u8, 4]) {
+            Err(RpcError::InvalidInput(Some(selector))) => assert_eq!(selector, *vote_selector),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(stored_votes(), vec![1, 1]);
+    }
+}
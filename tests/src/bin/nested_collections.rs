@@ -0,0 +1,81 @@
+#![allow(unused)]
+
+use oasis_std::{abi::*, collections::Map, Address, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Message {
+    from: Address,
+    text: String,
+}
+
+#[derive(Service)]
+pub struct InboxService {
+    inboxes: Map<Address, Vec<Message>>,
+    tallies: Map<Address, Vec<Map<String, u64>>>,
+    attachments: Vec<Vec<u8>>,
+}
+
+impl InboxService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            inboxes: Map::new(),
+            tallies: Map::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn send(&mut self, ctx: &Context, to: Address, text: String, attachments: Vec<Vec<u8>>) {
+        self.inboxes.entry(to).or_default().push(Message {
+            from: ctx.sender(),
+            text,
+        });
+        self.attachments.extend(attachments);
+    }
+
+    pub fn inboxes(&self, _ctx: &Context) -> Map<Address, Vec<Message>> {
+        self.inboxes.clone()
+    }
+
+    pub fn add_tallies(&mut self, ctx: &Context, tallies: Vec<Map<String, u64>>) {
+        self.tallies
+            .entry(ctx.sender())
+            .or_default()
+            .extend(tallies);
+    }
+
+    pub fn tallies(&self, _ctx: &Context) -> Map<Address, Vec<Map<String, u64>>> {
+        self.tallies.clone()
+    }
+}
+
+fn main() {
+    oasis_std::service!(InboxService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persist_nested_collections() {
+        let ctx = Context::default().with_sender(Address([2u8; 20]));
+        let mut service = InboxService::new(&ctx);
+        service.send(
+            &ctx,
+            Address([1u8; 20]),
+            "hi".to_string(),
+            vec![vec![1, 2], vec![]],
+        );
+        let tally: Map<String, u64> = vec![("votes".to_string(), 3)].into_iter().collect();
+        service.add_tallies(&ctx, vec![tally.clone(), Map::new()]);
+        Service::sunder(service);
+
+        let service = InboxService::coalesce();
+        assert_eq!(service.inboxes[&Address([1u8; 20])][0].text, "hi");
+        assert_eq!(service.attachments, vec![vec![1, 2], vec![]]);
+        assert_eq!(
+            service.tallies(&ctx)[&Address([2u8; 20])],
+            vec![tally, Map::new()]
+        );
+    }
+}
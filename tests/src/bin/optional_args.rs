@@ -0,0 +1,38 @@
+use oasis_std::{Context, Service};
+
+#[derive(Service)]
+pub struct FeedService {
+    char_limit: u32,
+    posts: Vec<String>,
+}
+
+impl FeedService {
+    pub fn new(_ctx: &Context, char_limit: Option<u32>) -> Self {
+        Self {
+            char_limit: char_limit.unwrap_or(280),
+            posts: Vec::new(),
+        }
+    }
+
+    pub fn post(&mut self, _ctx: &Context, text: String) -> Result<u32, String> {
+        if text.len() > self.char_limit as usize {
+            return Err(format!("posts are limited to {} bytes", self.char_limit));
+        }
+        self.posts.push(text);
+        Ok(self.posts.len() as u32 - 1)
+    }
+
+    /// Returns up to `count` posts, starting at `since` or the first post.
+    pub fn posts(&self, _ctx: &Context, count: u32, since: Option<u32>) -> Vec<String> {
+        self.posts
+            .iter()
+            .skip(since.unwrap_or_default() as usize)
+            .take(count as usize)
+            .cloned()
+            .collect()
+    }
+}
+
+fn main() {
+    oasis_std::service!(FeedService);
+}
@@ -0,0 +1,92 @@
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(OrderedStruct, Clone, Debug, PartialEq)]
+pub struct Waypoint {
+    #[oasis(field = 2)]
+    pub label: String,
+    #[oasis(field = 0)]
+    pub x: i32,
+    #[oasis(field = 1)]
+    pub y: i32,
+}
+
+#[derive(Service)]
+pub struct RouteService {
+    waypoints: Vec<Waypoint>,
+}
+
+impl RouteService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            waypoints: Vec::new(),
+        }
+    }
+
+    pub fn add_waypoint(&mut self, _ctx: &Context, waypoint: Waypoint) {
+        self.waypoints.push(waypoint);
+    }
+
+    pub fn waypoints(&self, _ctx: &Context) -> Vec<Waypoint> {
+        self.waypoints.clone()
+    }
+}
+
+fn main() {
+    oasis_std::service!(RouteService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Waypoint` with its fields reordered, as in a later version of the service.
+    #[derive(OrderedStruct, Debug, PartialEq)]
+    struct ReorderedWaypoint {
+        #[oasis(field = 0)]
+        x: i32,
+        #[oasis(field = 1)]
+        y: i32,
+        #[oasis(field = 2)]
+        label: String,
+    }
+
+    #[test]
+    fn test_reordered_fields() {
+        let ctx = Context::default();
+        let mut service = RouteService::new(&ctx);
+        service.add_waypoint(
+            &ctx,
+            Waypoint {
+                label: "home".to_string(),
+                x: -3,
+                y: 7,
+            },
+        );
+        Service::sunder(service);
+
+        let stored = oasis_std::backend::read(b"waypoints");
+        assert_eq!(
+            Vec::<ReorderedWaypoint>::try_from_slice(&stored).unwrap(),
+            vec![ReorderedWaypoint {
+                x: -3,
+                y: 7,
+                label: "home".to_string(),
+            }]
+        );
+
+        let service = RouteService::coalesce();
+        assert_eq!(service.waypoints(&ctx)[0].label, "home");
+    }
+
+    #[test]
+    fn test_pinned_encoding() {
+        let waypoint = Waypoint {
+            label: "a".to_string(),
+            x: 1,
+            y: 2,
+        };
+        let expected = (1i32, 2i32, "a".to_string()).try_to_vec().unwrap();
+        assert_eq!(waypoint.try_to_vec().unwrap(), expected);
+        assert_eq!(Waypoint::try_from_slice(&expected).unwrap(), waypoint);
+    }
+}
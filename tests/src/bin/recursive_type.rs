@@ -0,0 +1,85 @@
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Tree {
+    pub key: u64,
+    pub left: Option<Box<Tree>>,
+    pub right: Option<Box<Tree>>,
+}
+
+impl Tree {
+    fn leaf(key: u64) -> Box<Self> {
+        Box::new(Self {
+            key,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn insert(&mut self, key: u64) {
+        let child = if key < self.key {
+            &mut self.left
+        } else {
+            &mut self.right
+        };
+        match child {
+            Some(child) => child.insert(key),
+            None => *child = Some(Self::leaf(key)),
+        }
+    }
+}
+
+#[derive(Service)]
+pub struct SearchTreeService {
+    root: Option<Box<Tree>>,
+}
+
+impl SearchTreeService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self { root: None }
+    }
+
+    pub fn insert(&mut self, _ctx: &Context, key: u64) {
+        match &mut self.root {
+            Some(root) => root.insert(key),
+            None => self.root = Some(Tree::leaf(key)),
+        }
+    }
+
+    pub fn root(&self, _ctx: &Context) -> Option<Tree> {
+        self.root.as_ref().map(|root| Tree::clone(root))
+    }
+
+    pub fn graft(&mut self, _ctx: &Context, tree: Box<Tree>) {
+        self.root = Some(tree);
+    }
+}
+
+fn main() {
+    oasis_std::service!(SearchTreeService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxed_encoding() {
+        let ctx = Context::default();
+        let mut service = SearchTreeService::new(&ctx);
+        for key in &[2, 1, 3] {
+            service.insert(&ctx, *key);
+        }
+        let tree = service.root(&ctx).unwrap();
+        assert_eq!(tree.left.as_ref().unwrap().key, 1);
+        assert_eq!(tree.right.as_ref().unwrap().key, 3);
+
+        // A `Box` is encoded identically to the value it contains.
+        let encoded = tree.try_to_vec().unwrap();
+        assert_eq!(
+            encoded[..10],
+            [2, 0, 0, 0, 0, 0, 0, 0, /* Some */ 1, /* key */ 1][..]
+        );
+        assert_eq!(Tree::try_from_slice(&encoded).unwrap(), tree);
+    }
+}
@@ -0,0 +1,81 @@
+use oasis_std::{skip, Context, RpcError, Service};
+
+#[derive(Service)]
+pub struct PointsService {
+    points: u32,
+}
+
+impl PointsService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self { points: 0 }
+    }
+
+    pub fn award(&mut self, ctx: &Context, points: u32) -> u32 {
+        self.add_points(ctx, points)
+    }
+
+    pub fn points(&self, _ctx: &Context) -> u32 {
+        self.points
+    }
+
+    #[skip]
+    pub fn add_points(&mut self, _ctx: &Context, points: u32) -> u32 {
+        self.points += points;
+        self.points
+    }
+}
+
+fn main() {
+    oasis_std::service!(PointsService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A skipped method is still a regular method.
+    #[test]
+    fn test_call_skipped() {
+        let ctx = Context::default();
+        let mut service = PointsService::new(&ctx);
+        assert_eq!(service.add_points(&ctx, 3), 3);
+        assert_eq!(service.award(&ctx, 4), 7);
+        assert_eq!(service.points(&ctx), 7);
+    }
+
+    /// The index that `add_points` would have as an RPC is not routed.
+    #[test]
+    fn test_dispatch_skipped() {
+        let ctx = Context::default();
+        let mut service = PointsService::new(&ctx);
+        assert!(PointsService::METHODS
+            .iter()
+            .all(|(name, _)| *name != "add_points"));
+
+        let payload = oasis_std::abi_encode!(2u8, 3u32).unwrap();
+        match PointsService::__dispatch_raw(&mut service, &ctx, &payload) {
+            Err(RpcError::InvalidInput(None)) => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(service.points(&ctx), 0);
+    }
+
+    /// The error of a malformed call names the method.
+    #[test]
+    fn test_dispatch_malformed() {
+        let ctx = Context::default();
+        let mut service = PointsService::new(&ctx);
+        let (_, award_selector) = PointsService::METHODS
+            .iter()
+            .find(|(name, _)| *name == "award")
+            .unwrap();
+
+        // `award` takes a `u32`, of which only one byte is provided.
+        let payload = vec![*award_selector as u8, 3];
+        match PointsService::__dispatch_raw(&mut service, &ctx, &payload) {
+            Err(RpcError::InvalidInput(Some(selector))) => assert_eq!(selector, *award_selector),
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert_eq!(service.points(&ctx), 0);
+    }
+}
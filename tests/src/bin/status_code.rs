@@ -0,0 +1,57 @@
+use oasis_std::{Context, Service, StatusCode};
+
+pub const FULFILLED: StatusCode = StatusCode(200);
+pub const PARTIAL: StatusCode = StatusCode(206);
+
+#[derive(Service)]
+pub struct InventoryService {
+    stock: u32,
+}
+
+impl InventoryService {
+    pub fn new(_ctx: &Context, stock: u32) -> Self {
+        Self { stock }
+    }
+
+    /// Reserves up to `quantity` items and returns how many were reserved.
+    /// A partial reservation is not an error, but the status tells the caller about it.
+    pub fn reserve(&mut self, _ctx: &Context, quantity: u32) -> (StatusCode, u32) {
+        let reserved = quantity.min(self.stock);
+        self.stock -= reserved;
+        let status = if reserved == quantity {
+            FULFILLED
+        } else {
+            PARTIAL
+        };
+        (status, reserved)
+    }
+}
+
+fn main() {
+    oasis_std::service!(InventoryService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oasis_std::{abi::*, abi_encode};
+
+    fn reserve(service: &mut InventoryService, quantity: u32) -> Vec<u8> {
+        let payload = abi_encode!(0u8, quantity).unwrap();
+        InventoryService::__dispatch_raw(service, &Context::default(), &payload).unwrap()
+    }
+
+    #[test]
+    fn test_decode_status() {
+        let mut service = InventoryService::new(&Context::default(), 5);
+
+        let output = reserve(&mut service, 3);
+        // The status is a two-byte header that precedes the output.
+        assert_eq!(&output[..2], &200u16.to_le_bytes());
+        assert_eq!(<(u16, u32)>::try_from_slice(&output).unwrap(), (200, 3));
+
+        let output = reserve(&mut service, 3);
+        assert_eq!(<(u16, u32)>::try_from_slice(&output).unwrap(), (206, 2));
+    }
+}
@@ -0,0 +1,73 @@
+use oasis_std::{abi::*, Context, Service};
+
+#[derive(TaggedEnum, Clone, Debug, PartialEq)]
+pub enum Status {
+    #[oasis(tag = 0)]
+    Active,
+    #[oasis(tag = 1)]
+    Frozen { until: u64 },
+    #[oasis(tag = 2)]
+    Closed(String),
+}
+
+#[derive(Service)]
+pub struct LockerService {
+    status: Status,
+}
+
+impl LockerService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            status: Status::Active,
+        }
+    }
+
+    pub fn status(&self, _ctx: &Context) -> Status {
+        self.status.clone()
+    }
+
+    pub fn set_status(&mut self, _ctx: &Context, status: Status) {
+        self.status = status;
+    }
+}
+
+fn main() {
+    oasis_std::service!(LockerService);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Status` with its variants reordered, as in a later version of the service.
+    #[derive(TaggedEnum, Debug, PartialEq)]
+    enum ReorderedStatus {
+        #[oasis(tag = 2)]
+        Closed(String),
+        #[oasis(tag = 0)]
+        Active,
+        #[oasis(tag = 1)]
+        Frozen { until: u64 },
+    }
+
+    #[test]
+    fn test_reordered_variants() {
+        let ctx = Context::default();
+        let mut service = LockerService::new(&ctx);
+        service.set_status(&ctx, Status::Frozen { until: 42 });
+        Service::sunder(service);
+
+        let stored = oasis_std::backend::read(b"status");
+        assert_eq!(
+            ReorderedStatus::try_from_slice(&stored).unwrap(),
+            ReorderedStatus::Frozen { until: 42 }
+        );
+        assert_eq!(
+            Status::Closed("done".to_string()).try_to_vec().unwrap(),
+            ReorderedStatus::Closed("done".to_string())
+                .try_to_vec()
+                .unwrap()
+        );
+        assert!(ReorderedStatus::try_from_slice(&[3]).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+#![allow(unused)]
+
+use oasis_std::{
+    abi::*,
+    collections::{Map, Set},
+    Context, Service,
+};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Registry {
+    entries: Map<String, u32>,
+    owners: Set<String>,
+}
+
+#[derive(Service)]
+pub struct CollectionsService {
+    registry: Registry,
+}
+
+impl CollectionsService {
+    pub fn new(_ctx: &Context, entries: Map<String, u32>) -> Self {
+        unimplemented!()
+    }
+
+    pub fn lookup(&self, _ctx: &Context, keys: Set<String>) -> Map<String, u32> {
+        unimplemented!()
+    }
+
+    pub fn registry(&self, _ctx: &Context) -> Registry {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    oasis_std::service!(CollectionsService);
+}
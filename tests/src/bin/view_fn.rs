@@ -0,0 +1,27 @@
+use oasis_std::{view, Address, Context, Service};
+
+#[derive(Service)]
+pub struct AllowlistService {
+    allowed: Vec<Address>,
+}
+
+impl AllowlistService {
+    pub fn new(_ctx: &Context) -> Self {
+        Self {
+            allowed: Vec::new(),
+        }
+    }
+
+    pub fn allow(&mut self, _ctx: &Context, account: Address) {
+        self.allowed.push(account);
+    }
+
+    #[view]
+    pub fn is_allowed(&self, _ctx: &Context, account: Address) -> bool {
+        self.allowed.contains(&account)
+    }
+}
+
+fn main() {
+    oasis_std::service!(AllowlistService);
+}
@@ -1,15 +1,20 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::HashMap};
 
+use oasis_client::gateway::CallOptions;
 use oasis_std::{Address, RpcError};
+use oasis_types::Event;
 
 pub struct MockGateway {
     pub handlers: GatewayHandlers,
     pub deploys: RefCell<Vec<DeployCall>>,
     pub rpcs: RefCell<Vec<RpcCall>>,
+    codes: RefCell<HashMap<Address, Vec<u8>>>,
+    events: RefCell<Vec<Event>>,
 }
 
 pub struct DeployCall {
     pub initcode: Vec<u8>,
+    pub options: CallOptions,
     pub outcome: Result<Address, RpcError>,
 }
 
@@ -17,6 +22,9 @@ pub struct RpcCall {
     pub callee: Address,
     pub payload: Vec<u8>,
     pub outcome: Result<Vec<u8>, RpcError>,
+
+    /// Whether the call was made using the read-only `Gateway::query`.
+    pub is_query: bool,
 }
 
 impl MockGateway {
@@ -25,9 +33,37 @@ impl MockGateway {
             handlers,
             deploys: RefCell::new(Vec::new()),
             rpcs: RefCell::new(Vec::new()),
+            codes: RefCell::new(HashMap::new()),
+            events: RefCell::new(Vec::new()),
         }
     }
 
+    /// Records an event with `topic` and `data` as having been emitted by the service
+    /// at `emitter`.
+    pub fn emit(&self, emitter: Address, topic: [u8; 32], data: Vec<u8>) {
+        self.events.borrow_mut().push(Event {
+            emitter,
+            topics: vec![topic],
+            data,
+        });
+    }
+
+    fn record_call(
+        &self,
+        address: Address,
+        payload: &[u8],
+        is_query: bool,
+    ) -> Result<Vec<u8>, RpcError> {
+        let outcome = (self.handlers.rpc)(address, payload);
+        self.rpcs.borrow_mut().push(RpcCall {
+            callee: address,
+            payload: payload.to_vec(),
+            outcome: Self::clone_outcome(&outcome),
+            is_query,
+        });
+        outcome
+    }
+
     fn clone_outcome<T: Clone>(outcome: &Result<T, RpcError>) -> Result<T, RpcError> {
         outcome.as_ref().map(|t| t.clone()).map_err(|err| {
             use RpcError::*;
@@ -35,7 +71,7 @@ impl MockGateway {
                 InvalidCallee => InvalidCallee,
                 InsufficientFunds => InsufficientFunds,
                 InsufficientGas => InsufficientGas,
-                InvalidInput => InvalidInput,
+                InvalidInput(method) => InvalidInput(*method),
                 InvalidOutput(output) => InvalidOutput(output.clone()),
                 Execution(err) => Execution(err.clone()),
                 Gateway(_) => unreachable!(),
@@ -45,23 +81,44 @@ impl MockGateway {
 }
 
 impl oasis_client::gateway::Gateway for MockGateway {
-    fn deploy(&self, initcode: &[u8]) -> Result<Address, RpcError> {
+    fn deploy(&self, initcode: &[u8], options: CallOptions) -> Result<Address, RpcError> {
         let outcome = (self.handlers.deploy)(initcode);
+        if let Ok(address) = &outcome {
+            self.codes.borrow_mut().insert(*address, initcode.to_vec());
+        }
         self.deploys.borrow_mut().push(DeployCall {
             initcode: initcode.to_vec(),
+            options,
             outcome: Self::clone_outcome(&outcome),
         });
         outcome
     }
 
     fn rpc(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
-        let outcome = (self.handlers.rpc)(address, payload);
-        self.rpcs.borrow_mut().push(RpcCall {
-            callee: address,
-            payload: payload.to_vec(),
-            outcome: Self::clone_outcome(&outcome),
-        });
-        outcome
+        self.record_call(address, payload, false /* is_query */)
+    }
+
+    fn query(&self, address: Address, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+        self.record_call(address, payload, true /* is_query */)
+    }
+
+    /// Returns the initcode of the service successfully deployed at `address`.
+    fn code(&self, address: Address) -> Result<Vec<u8>, RpcError> {
+        self.codes
+            .borrow()
+            .get(&address)
+            .cloned()
+            .ok_or(RpcError::InvalidCallee)
+    }
+
+    fn logs(&self, address: Address) -> Result<Vec<Event>, RpcError> {
+        Ok(self
+            .events
+            .borrow()
+            .iter()
+            .filter(|event| event.emitter == address)
+            .cloned()
+            .collect())
     }
 }
 
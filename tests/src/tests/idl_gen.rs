@@ -1,4 +1,4 @@
-use super::test_oasis_interface;
+use super::{load_oasis_interface, test_oasis_interface};
 
 #[test]
 fn test_oasis_build() {
@@ -9,3 +9,235 @@ fn test_oasis_build() {
 fn test_non_default_fn() {
     test_oasis_interface("non_default_fn", "NonDefaultFnService");
 }
+
+#[test]
+fn test_ctor_error() {
+    test_oasis_interface("ctor_error", "CtorErrorService");
+}
+
+#[test]
+fn test_default_fn() {
+    test_oasis_interface("default_fn", "DefaultFnService");
+}
+
+#[test]
+fn test_borrowed_outputs() {
+    test_oasis_interface("borrowed", "BorrowedService");
+}
+
+#[test]
+fn test_borrowed_args() {
+    test_oasis_interface("borrowed_args", "BorrowedArgsService");
+}
+
+#[test]
+fn test_collections_wire_types() {
+    let btree = load_oasis_interface("btree_collections");
+    let vec = load_oasis_interface("vec_collections");
+    assert_eq!(btree.type_defs, vec.type_defs);
+    assert_eq!(btree.constructor, vec.constructor);
+    assert_eq!(btree.functions, vec.functions);
+    assert_eq!(
+        btree.functions[0].output,
+        Some(oasis_rpc::Type::Map(
+            box oasis_rpc::Type::String,
+            box oasis_rpc::Type::U32
+        ))
+    );
+}
+
+#[test]
+fn test_enum_with_data() {
+    test_oasis_interface("enum_error", "EnumErrorService");
+}
+
+#[test]
+fn test_aliased_results() {
+    test_oasis_interface("aliased_result", "AliasedResultService");
+}
+
+#[test]
+fn test_duration() {
+    test_oasis_interface("duration", "LeaseService");
+}
+
+#[test]
+fn test_default_ctor() {
+    test_oasis_interface("default_ctor", "DefaultCtorService");
+}
+
+#[test]
+fn test_indexed_event_fields() {
+    test_oasis_interface("events", "EventService");
+
+    let iface = load_oasis_interface("events");
+    match &iface.type_defs[0] {
+        oasis_rpc::TypeDef::Event { fields, .. } => assert_eq!(
+            fields
+                .iter()
+                .map(|f| (f.name.as_str(), f.indexed))
+                .collect::<Vec<_>>(),
+            vec![
+                ("from", true),
+                ("amount", false),
+                ("to", true),
+                ("memo", false)
+            ]
+        ),
+        td => panic!("expected an event, found {:?}", td),
+    }
+}
+
+#[test]
+fn test_generic_state() {
+    test_oasis_interface("generic_state", "GenericStateService");
+
+    // Every use of `Ledger<Token>` refers to the same monomorphized type def.
+    let iface = load_oasis_interface("generic_state");
+    let ledger_ty = oasis_rpc::Type::Defined {
+        namespace: None,
+        ty: "LedgerToken".to_string(),
+    };
+    assert_eq!(iface.functions[0].output, Some(ledger_ty.clone()));
+    match &iface.type_defs[2] {
+        oasis_rpc::TypeDef::Struct { fields, .. } => assert_eq!(fields[0].ty, ledger_ty),
+        td => panic!("expected a struct, found {:?}", td),
+    }
+    assert_eq!(
+        iface
+            .type_defs
+            .iter()
+            .filter(|td| td.name() == "LedgerToken")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_floats() {
+    test_oasis_interface("floats", "ThermostatService");
+}
+
+#[test]
+fn test_optional_args() {
+    test_oasis_interface("optional_args", "FeedService");
+    let iface = load_oasis_interface("optional_args");
+    assert!(iface.constructor.inputs[0].optional);
+    let posts_inputs = &iface.functions[1].inputs;
+    assert!(!posts_inputs[0].optional);
+    assert!(posts_inputs[1].optional);
+}
+
+#[test]
+fn test_array_of_structs() {
+    test_oasis_interface("array_state", "BracketService");
+}
+
+#[test]
+fn test_big_ints() {
+    test_oasis_interface("big_int", "VaultService");
+}
+
+#[test]
+fn test_nested_collections() {
+    test_oasis_interface("nested_collections", "InboxService");
+}
+
+#[test]
+fn test_estimated_gas() {
+    test_oasis_interface("gas", "LedgerService");
+    let iface = load_oasis_interface("gas");
+    let gas: Vec<u64> = iface
+        .functions
+        .iter()
+        .map(|f| f.estimated_gas.unwrap())
+        .collect();
+    // The functions are declared in order of the number of times they access the state.
+    assert!(gas.windows(2).all(|w| w[0] < w[1]), "{:?}", gas);
+
+    let schedule = memchain::GasSchedule::METERED;
+    let expected_gas: Vec<u64> = [0, 1, 3, 4]
+        .iter()
+        .map(|num_accesses| {
+            schedule.base + (schedule.storage_read + schedule.storage_write) * num_accesses
+        })
+        .collect();
+    assert_eq!(gas, expected_gas);
+}
+
+#[test]
+fn test_list_of_tuples() {
+    test_oasis_interface("map_entries", "ScoreboardService");
+}
+
+#[test]
+fn test_tagged_enum() {
+    test_oasis_interface("tagged_enum", "LockerService");
+}
+
+#[test]
+fn test_ordered_struct() {
+    test_oasis_interface("ordered_struct", "RouteService");
+}
+
+#[test]
+fn test_skipped_method() {
+    test_oasis_interface("skip_method", "PointsService");
+    // The dispatcher routes calls by the index of the function in the interface.
+    let iface = load_oasis_interface("skip_method");
+    assert!(iface.functions.iter().all(|f| f.name != "add_points"));
+}
+
+#[test]
+fn test_invariant_not_rpc() {
+    // RPCs are the methods found while parsing, so a `pub` invariant is not one,
+    // even though its `#[invariant]` attribute is gone by the time types are analyzed.
+    let iface = load_oasis_interface("invariant");
+    let fn_names: Vec<&str> = iface.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(fn_names, ["balance_of", "transfer"]);
+}
+
+#[test]
+fn test_named_args() {
+    test_oasis_interface("named_args", "BallotService");
+    // Inputs are named for the source parameters, including `mut` ones.
+    let iface = load_oasis_interface("named_args");
+    let input_names = |inputs: &[oasis_rpc::Field]| -> Vec<String> {
+        inputs.iter().map(|input| input.name.clone()).collect()
+    };
+    assert_eq!(input_names(&iface.constructor.inputs), ["num_proposals"]);
+    assert_eq!(input_names(&iface.functions[0].inputs), ["to_voter"]);
+    assert_eq!(
+        input_names(&iface.functions[1].inputs),
+        ["proposal_index", "num_votes"]
+    );
+}
+
+#[test]
+fn test_recursive_type() {
+    test_oasis_interface("recursive_type", "SearchTreeService");
+    // `Box` is transparent, so the `Tree` def refers to itself by name.
+    let iface = load_oasis_interface("recursive_type");
+    let tree_ty = oasis_rpc::Type::Defined {
+        namespace: None,
+        ty: "Tree".to_string(),
+    };
+    match &iface.type_defs[..] {
+        [oasis_rpc::TypeDef::Struct { name, fields }] => {
+            assert_eq!(name, "Tree");
+            assert_eq!(fields[1].ty, oasis_rpc::Type::Optional(box tree_ty));
+        }
+        type_defs => panic!("unexpected type defs: {:?}", type_defs),
+    }
+}
+
+#[test]
+fn test_view_fn() {
+    test_oasis_interface("view_fn", "AllowlistService");
+}
+
+#[test]
+fn test_status_code() {
+    // `StatusCode` is encoded as a `u16`, so clients see a `(u16, T)`.
+    test_oasis_interface("status_code", "InventoryService");
+}
@@ -1,12 +1,15 @@
 mod idl_gen;
 mod xcc;
 
-pub fn test_oasis_interface(bin_name: &str, service_name: &str) {
+pub fn load_oasis_interface(bin_name: &str) -> oasis_rpc::Interface {
     let mf_dir = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
     let wasm_path = mf_dir.join(format!("../target/wasm32-wasi/release/{}.wasm", bin_name));
+    oasis_rpc::Interface::from_wasm_bytecode(&std::fs::read(&wasm_path).unwrap()).unwrap()
+}
 
-    let actual =
-        oasis_rpc::Interface::from_wasm_bytecode(&std::fs::read(&wasm_path).unwrap()).unwrap();
+pub fn test_oasis_interface(bin_name: &str, service_name: &str) {
+    let mf_dir = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let actual = load_oasis_interface(bin_name);
 
     let json_path = mf_dir.join(format!("res/{}.json", service_name));
     let expected: oasis_rpc::Interface =
@@ -1,4 +1,5 @@
-use oasis_std::{abi_encode, Address, Context};
+use oasis_client::Gateway;
+use oasis_std::{abi_encode, Address, Context, RpcError};
 
 use crate::mock_gateway::{DeployCall, GatewayHandlers, MockGateway, RpcCall};
 
@@ -54,3 +55,266 @@ fn test_native_client() {
         assert_eq!(payload, &expected_rpc_payload);
     }
 }
+
+#[test]
+fn test_default_fn_client() {
+    let addr = Address([1u8; 20]);
+    let count = 1u32;
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, _| Ok(abi_encode!(count).unwrap()),
+    });
+
+    let mut client = default_fn::DefaultFnServiceClient::new(&gateway, addr);
+    let output = client.increment(&Context::default()).unwrap();
+    assert_eq!(output, Ok(count));
+
+    let rpcs = gateway.rpcs.borrow();
+    assert_eq!(rpcs.len(), 1);
+    let RpcCall {
+        callee, payload, ..
+    } = &rpcs[0];
+    assert_eq!(*callee, addr);
+    assert!(payload.is_empty()); // the default function is called with empty input
+}
+
+#[test]
+fn test_deploy_code() {
+    let addr = Address([1u8; 20]);
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, _| unreachable!(),
+    });
+
+    let _client = a::ServiceAClient::deploy(&gateway, &Context::default(), "message").unwrap();
+    assert!(gateway.rpcs.borrow().is_empty());
+
+    let code = Gateway::code(&gateway, addr).unwrap();
+    assert!(!code.is_empty());
+    assert!(code.starts_with(SERVICE_A_BYTECODE));
+    match Gateway::code(&gateway, Address([2u8; 20])) {
+        Err(RpcError::InvalidCallee) => (),
+        res => panic!("expected `InvalidCallee`, got {:?}", res),
+    }
+}
+
+#[test]
+fn test_deploy_context() {
+    let addr = Address([1u8; 20]);
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, _| unreachable!(),
+    });
+
+    let ctx = Context::default().with_gas(1234).with_value(5);
+    let _client = a::ServiceAClient::deploy(&gateway, &ctx, "message").unwrap();
+    let _client = a::ServiceAClient::deploy(&gateway, &Context::default(), "message").unwrap();
+
+    let deploys = gateway.deploys.borrow();
+    let DeployCall { options, .. } = &deploys[0];
+    assert_eq!(options.gas, Some(1234));
+    assert_eq!(options.value.map(|value| value.0), Some(5));
+    let DeployCall { options, .. } = &deploys[1];
+    assert_eq!(options.gas, None);
+    assert!(options.value.is_none());
+}
+
+#[test]
+fn test_typed_error_client() {
+    let addr = Address([1u8; 20]);
+    let holder = Address([2u8; 20]);
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| unreachable!(),
+        rpc: box move |_, _| {
+            let err = enum_error::Error::InsufficientFunds { address: holder };
+            Err(oasis_std::RpcError::Execution(abi_encode!(err).unwrap()))
+        },
+    });
+
+    // The client decodes the error of a method that returns a `Result`.
+    let mut client = enum_error::EnumErrorServiceClient::new(&gateway, addr);
+    let output = client.withdraw(&Context::default(), 100).unwrap();
+    assert_eq!(
+        output,
+        Err(enum_error::Error::InsufficientFunds { address: holder })
+    );
+
+    // The error of a raw call decodes into the same type.
+    let payload = abi_encode!(0u8, 100u64).unwrap();
+    let err = oasis_client::Gateway::rpc(&gateway, addr, &payload).unwrap_err();
+    assert_eq!(
+        err.into_typed::<enum_error::Error>().unwrap(),
+        enum_error::Error::InsufficientFunds { address: holder }
+    );
+    assert_eq!(gateway.rpcs.borrow()[1].payload, payload);
+
+    // An error that is not an encoded `Error` is returned as is.
+    match oasis_std::RpcError::InsufficientGas.into_typed::<enum_error::Error>() {
+        Err(oasis_std::RpcError::InsufficientGas) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn test_view_fn_client() {
+    let addr = Address([1u8; 20]);
+    let count = 1u32;
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, _| Ok(abi_encode!(count).unwrap()),
+    });
+
+    let mut client = default_fn::DefaultFnServiceClient::new(&gateway, addr);
+    assert_eq!(client.count(&Context::default()).unwrap(), count);
+    assert_eq!(client.increment(&Context::default()).unwrap(), Ok(count));
+
+    let rpcs = gateway.rpcs.borrow();
+    assert_eq!(rpcs.len(), 2);
+    assert!(rpcs[0].is_query); // `count` takes `&self`
+    assert!(!rpcs[1].is_query); // `increment` takes `&mut self`
+}
+
+#[test]
+fn test_imported_view_fn_output() {
+    let addr = Address([2u8; 20]);
+    let total_value = 4200u64;
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, _| Ok(abi_encode!(total_value).unwrap()),
+    });
+
+    // The output is decoded into the IDL type of `b`'s `total_value`.
+    let client = b::ServiceBClient::new(&gateway, addr);
+    let output: u64 = client.total_value(&Context::default()).unwrap();
+    assert_eq!(output, total_value);
+
+    let rpcs = gateway.rpcs.borrow();
+    assert_eq!(rpcs.len(), 1);
+    assert!(rpcs[0].is_query);
+    assert_eq!(rpcs[0].payload, vec![3u8]);
+}
+
+#[test]
+fn test_imported_rpc_panic() {
+    use oasis_std::RpcError;
+
+    let a_addr = Address([1u8; 20]);
+    let message = "panicked at 'no entry found for key', src/bin/a.rs:10:5";
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| unreachable!(),
+        rpc: box move |_, _| Err(RpcError::Execution(message.as_bytes().to_vec())),
+    });
+
+    // The panic message is not an `RpcError`, so it is passed through as the execution error.
+    let client = a::ServiceAClient::new(&gateway, a_addr);
+    match client.call_b(&Context::default(), Address([2u8; 20])) {
+        Err(RpcError::Execution(output)) => assert_eq!(output, message.as_bytes()),
+        Err(err) => panic!("unexpected error: {:?}", err),
+        Ok(_) => panic!("expected a panic"),
+    }
+}
+
+#[test]
+fn test_replay_events() {
+    use oasis_client::Gateway as _;
+    use oasis_std::abi::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    enum Custody {
+        Received { from: Address },
+        Transferred { to: Address },
+    }
+
+    const CUSTODY_TOPIC: [u8; 32] = [1u8; 32];
+
+    let item_addr = Address([1u8; 20]);
+    let other_addr = Address([2u8; 20]);
+    let (alice, bob, carol) = (Address([3u8; 20]), Address([4u8; 20]), Address([5u8; 20]));
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| unreachable!(),
+        rpc: box move |_, _| unreachable!(),
+    });
+
+    let emit =
+        |emitter, event: Custody| gateway.emit(emitter, CUSTODY_TOPIC, abi_encode!(event).unwrap());
+    emit(item_addr, Custody::Received { from: alice });
+    emit(other_addr, Custody::Transferred { to: carol });
+    emit(item_addr, Custody::Transferred { to: bob });
+    gateway.emit(item_addr, [2u8; 32], b"not custody".to_vec());
+    emit(item_addr, Custody::Transferred { to: carol });
+
+    let custodians = gateway
+        .replay_events(
+            item_addr,
+            CUSTODY_TOPIC,
+            Vec::new(),
+            |custodians, event: Custody| match event {
+                Custody::Received { from } => custodians.push(from),
+                Custody::Transferred { to } => custodians.push(to),
+            },
+        )
+        .unwrap();
+    assert_eq!(custodians, vec![alice, bob, carol]);
+
+    gateway.emit(item_addr, CUSTODY_TOPIC, vec![0xff]);
+    match gateway.replay_events(item_addr, CUSTODY_TOPIC, (), |_, _: Custody| ()) {
+        Err(oasis_std::RpcError::InvalidOutput(data)) => assert_eq!(data, vec![0xff]),
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn test_blocking_and_async_gateways() {
+    use oasis_client::{AsyncGateway, BlockingGateway, Gateway};
+
+    let addr = Address([1u8; 20]);
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| Ok(addr),
+        rpc: box move |_, payload: &[u8]| Ok(payload.iter().rev().copied().collect()),
+    });
+
+    let deployed = futures::executor::block_on(AsyncGateway::deploy(&gateway, b"code")).unwrap();
+    assert_eq!(deployed, addr);
+    let output = futures::executor::block_on(AsyncGateway::rpc(&gateway, addr, &[1, 2])).unwrap();
+    assert_eq!(output, vec![2, 1]);
+
+    let gateway = BlockingGateway::new(gateway);
+    assert_eq!(Gateway::query(&gateway, addr, &[3, 4]).unwrap(), vec![4, 3]);
+
+    let rpcs = gateway.inner().rpcs.borrow();
+    assert_eq!(rpcs.len(), 2);
+    assert!(!rpcs[0].is_query);
+    assert!(rpcs[1].is_query);
+}
+
+#[test]
+fn test_json_import() {
+    // The client is generated from the checked-in IDL rather than from bytecode.
+    let addr = Address([1u8; 20]);
+    let points = 7u32;
+
+    let gateway = MockGateway::new(GatewayHandlers {
+        deploy: box move |_| unreachable!(),
+        rpc: box move |_, _| Ok(abi_encode!(points).unwrap()),
+    });
+
+    let client = skip_method::PointsServiceClient::new(&gateway, addr);
+    assert_eq!(client.award(&Context::default(), 3).unwrap(), points);
+    assert_eq!(client.points(&Context::default()).unwrap(), points);
+
+    let rpcs = gateway.rpcs.borrow();
+    assert_eq!(rpcs.len(), 2);
+    assert_eq!(rpcs[0].callee, addr);
+    assert_eq!(rpcs[0].payload, abi_encode!(0u8, 3u32).unwrap());
+    assert!(!rpcs[0].is_query);
+    assert_eq!(rpcs[1].payload, abi_encode!(1u8).unwrap());
+    assert!(rpcs[1].is_query);
+}